@@ -0,0 +1,214 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError};
+use std::time::Duration;
+
+use crate::backend::QueueBackend;
+use crate::hook::Hook;
+use crate::sync::{AtomicBool, AtomicU64, AtomicUsize, RwLock};
+use crate::ThreadPool;
+
+/// How many of the most recent queue wait are kept for [`Load::queue_wait_p95`]
+const RECENT: usize = 256;
+
+/// Load of the pool at the time a job is submitted, see [`AdmissionController`]
+#[derive(Debug)]
+pub struct Load<'a> {
+    queue_len: usize,
+    queue_capacity: Option<usize>,
+    live_workers: usize,
+    idle_workers: usize,
+    recent: &'a RecentWaits,
+}
+
+impl Load<'_> {
+    /// Number of message waiting in the queue
+    pub fn queue_len(&self) -> usize {
+        self.queue_len
+    }
+
+    /// Maximum number of message the queue can hold, [`None`] if it's unbounded
+    pub fn queue_capacity(&self) -> Option<usize> {
+        self.queue_capacity
+    }
+
+    /// Number of worker thread that are currently running
+    pub fn live_workers(&self) -> usize {
+        self.live_workers
+    }
+
+    /// Number of worker thread waiting for a job
+    pub fn idle_workers(&self) -> usize {
+        self.idle_workers
+    }
+
+    /// 95th percentile of the time the last 256 job spent in the queue, it's computed on every call
+    pub fn queue_wait_p95(&self) -> Duration {
+        self.recent.p95()
+    }
+}
+
+/// Decide whether a job is accepted, installed with [`ThreadPool::admission`]
+///
+/// A job that is not admitted fail with [`ThreadPoolError::Overloaded`](crate::error::ThreadPoolError::Overloaded)
+/// so a service can shed load early instead of building a backlog. Any `Fn(&Load) -> bool` closure is a controller.
+pub trait AdmissionController: Send + Sync + 'static {
+    /// Returns `true` if a job can be accepted under this load
+    fn admit(&self, load: &Load<'_>) -> bool;
+}
+
+impl<F> AdmissionController for F
+where
+    F: Fn(&Load<'_>) -> bool + Send + Sync + 'static,
+{
+    fn admit(&self, load: &Load<'_>) -> bool {
+        self(load)
+    }
+}
+
+/// Reject job once this many message are waiting in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxQueueLength(pub usize);
+
+impl AdmissionController for MaxQueueLength {
+    fn admit(&self, load: &Load<'_>) -> bool {
+        load.queue_len() < self.0
+    }
+}
+
+/// Reject job once the recent 95th percentile queue wait reach this duration, see [`Load::queue_wait_p95`]
+///
+/// Job are admitted again once the queue is empty, since the rejected job doesn't lower the recent queue wait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxQueueWait(pub Duration);
+
+impl AdmissionController for MaxQueueWait {
+    fn admit(&self, load: &Load<'_>) -> bool {
+        load.queue_len() == 0 || load.queue_wait_p95() < self.0
+    }
+}
+
+/// Admission controller of the pool and the recent queue wait it's based on
+#[derive(Debug, Default)]
+pub(crate) struct Admission {
+    controller: RwLock<Option<Hook<dyn AdmissionController>>>,
+    /// A controller is installed, the worker only record the queue wait while it's set
+    enabled: AtomicBool,
+    recent: RecentWaits,
+}
+
+impl Admission {
+    pub(crate) fn set(&self, controller: Option<Hook<dyn AdmissionController>>) {
+        let mut current = self
+            .controller
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.enabled.store(controller.is_some(), Ordering::SeqCst);
+        *current = controller;
+    }
+
+    pub(crate) fn record(&self, wait: Duration) {
+        if self.is_enabled() {
+            self.recent.record(wait);
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Ask the controller, check [`Admission::is_enabled`] first so the load is only measured when there are one
+    pub(crate) fn admit(
+        &self,
+        queue_len: usize,
+        queue_capacity: Option<usize>,
+        live_workers: usize,
+        idle_workers: usize,
+    ) -> bool {
+        let controller = self
+            .controller
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        controller.as_ref().is_none_or(|controller| {
+            controller.admit(&Load {
+                queue_len,
+                queue_capacity,
+                live_workers,
+                idle_workers,
+                recent: &self.recent,
+            })
+        })
+    }
+}
+
+/// Ring of the most recent queue wait in nanosecond
+#[derive(Debug)]
+pub(crate) struct RecentWaits {
+    waits: Box<[AtomicU64]>,
+    next: AtomicUsize,
+}
+
+impl Default for RecentWaits {
+    fn default() -> RecentWaits {
+        RecentWaits {
+            waits: (0..RECENT).map(|_| AtomicU64::new(0)).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl RecentWaits {
+    fn record(&self, wait: Duration) {
+        let slot = self.next.fetch_add(1, Ordering::Relaxed) % RECENT;
+        self.waits[slot].store(
+            u64::try_from(wait.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn p95(&self) -> Duration {
+        let len = self.next.load(Ordering::Relaxed).min(RECENT);
+        if len == 0 {
+            return Duration::ZERO;
+        }
+
+        let mut waits: Vec<u64> = self.waits[..len]
+            .iter()
+            .map(|wait| wait.load(Ordering::Relaxed))
+            .collect();
+        let rank = (len * 95).div_ceil(100) - 1;
+
+        Duration::from_nanos(*waits.select_nth_unstable(rank).1)
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Install an [`AdmissionController`] that decide whether a job is accepted,
+    /// replacing the previous one. Every submission fail with
+    /// [`ThreadPoolError::Overloaded`](crate::error::ThreadPoolError::Overloaded) while it reject the load.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, MaxQueueWait, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    /// pool.admission(MaxQueueWait(Duration::from_millis(200)));
+    ///
+    /// match pool.execute(|| println!("handle request")) {
+    ///     Err(ThreadPoolError::Overloaded) => println!("503 Service Unavailable"),
+    ///     result => result.unwrap(),
+    /// }
+    /// ```
+    pub fn admission(&self, controller: impl AdmissionController) {
+        self.shared
+            .admission
+            .set(Some(Hook::new(Arc::new(controller))));
+    }
+
+    /// Remove the [`AdmissionController`] installed with [`ThreadPool::admission`], every job is accepted again
+    pub fn clear_admission(&self) {
+        self.shared.admission.set(None);
+    }
+}
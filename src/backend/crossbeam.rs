@@ -0,0 +1,118 @@
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+
+use crate::backend::{QueueBackend, TryRecvError, TrySendError};
+use crate::sync::RwLock;
+use crate::Message;
+
+/// [`QueueBackend`] backed by `crossbeam-channel`
+#[derive(Debug)]
+pub struct Crossbeam {
+    sender: RwLock<Option<Sender<Message>>>,
+    receiver: Receiver<Message>,
+}
+
+impl Crossbeam {
+    /// Create a queue that hold at most `capacity` message, sending to a full queue wait for room
+    pub fn bounded(capacity: usize) -> Crossbeam {
+        let (sender, receiver) = bounded(capacity);
+
+        Crossbeam {
+            sender: RwLock::new(Some(sender)),
+            receiver,
+        }
+    }
+
+    /// Clone the sender out of the lock, a send blocked on a full queue must not hold [`QueueBackend::close`] back
+    fn sender(&self) -> Option<Sender<Message>> {
+        self.sender
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Default for Crossbeam {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded();
+
+        Crossbeam {
+            sender: RwLock::new(Some(sender)),
+            receiver,
+        }
+    }
+}
+
+impl QueueBackend for Crossbeam {
+    fn send(&self, message: Message) -> Result<(), Message> {
+        match self.sender() {
+            Some(sender) => sender.send(message).map_err(|err| err.into_inner()),
+            None => Err(message),
+        }
+    }
+
+    fn try_send(&self, message: Message) -> Result<(), TrySendError> {
+        match self.sender() {
+            Some(sender) => sender.try_send(message).map_err(|err| match err {
+                crossbeam_channel::TrySendError::Full(message) => TrySendError::Full(message),
+                crossbeam_channel::TrySendError::Disconnected(message) => {
+                    TrySendError::Closed(message)
+                }
+            }),
+            None => Err(TrySendError::Closed(message)),
+        }
+    }
+
+    fn send_timeout(&self, message: Message, timeout: Duration) -> Result<(), TrySendError> {
+        match self.sender() {
+            Some(sender) => sender
+                .send_timeout(message, timeout)
+                .map_err(|err| match err {
+                    crossbeam_channel::SendTimeoutError::Timeout(message) => {
+                        TrySendError::Full(message)
+                    }
+                    crossbeam_channel::SendTimeoutError::Disconnected(message) => {
+                        TrySendError::Closed(message)
+                    }
+                }),
+            None => Err(TrySendError::Closed(message)),
+        }
+    }
+
+    fn recv(&self) -> Option<Message> {
+        self.receiver.recv().ok()
+    }
+
+    fn try_recv(&self) -> Result<Message, TryRecvError> {
+        self.receiver.try_recv().map_err(|err| match err {
+            crossbeam_channel::TryRecvError::Empty => TryRecvError::Empty,
+            crossbeam_channel::TryRecvError::Disconnected => TryRecvError::Closed,
+        })
+    }
+
+    fn recv_timeout(&self, timeout: Duration) -> Result<Message, TryRecvError> {
+        self.receiver
+            .recv_timeout(timeout)
+            .map_err(|err| match err {
+                crossbeam_channel::RecvTimeoutError::Timeout => TryRecvError::Empty,
+                crossbeam_channel::RecvTimeoutError::Disconnected => TryRecvError::Closed,
+            })
+    }
+
+    fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.receiver.capacity()
+    }
+
+    fn close(&self) {
+        self.sender
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+}
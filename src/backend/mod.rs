@@ -0,0 +1,146 @@
+//! Queue used to carry [`Message`] from the [`ThreadPool`](crate::ThreadPool) to it's workers
+//!
+//! The crate provide [`Crossbeam`], [`Flume`] and [`Mpsc`] depending on the enabled feature flag,
+//! any of them can be enabled at the same time, other channel implementation can be plugged
+//! by implementing [`QueueBackend`].
+
+#[cfg(feature = "crossbeam")]
+mod crossbeam;
+
+#[cfg(feature = "flume")]
+mod flume;
+
+#[cfg(feature = "mpsc")]
+mod mpsc;
+
+#[cfg(feature = "crossbeam")]
+pub use self::crossbeam::Crossbeam;
+
+#[cfg(feature = "flume")]
+pub use self::flume::Flume;
+
+#[cfg(feature = "mpsc")]
+pub use self::mpsc::Mpsc;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::Message;
+
+/// Reason [`QueueBackend::try_recv`] didn't return a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// There are no pending message right now
+    Empty,
+    /// The queue is closed and there are no pending message left
+    Closed,
+}
+
+/// Reason [`QueueBackend::try_send`] didn't push the message, the message is given back
+#[derive(Debug)]
+pub enum TrySendError {
+    /// The queue is bounded and has no room left right now
+    Full(Message),
+    /// The queue has been closed
+    Closed(Message),
+}
+
+/// Queue shared between the [`ThreadPool`](crate::ThreadPool) and it's workers
+///
+/// Every worker hold a reference to the same queue and call [`QueueBackend::recv`] on it,
+/// so the implementation must allow multiple consumer.
+pub trait QueueBackend: Send + Sync + 'static {
+    /// Push a message to the queue, waiting for room if it's bounded and full
+    ///
+    /// ## Errors
+    ///
+    /// Give back the message if the queue has been closed
+    fn send(&self, message: Message) -> Result<(), Message>;
+
+    /// Push a message to the queue without blocking if it's full
+    ///
+    /// The default implementation call [`QueueBackend::send`], which is right for unbounded queue.
+    ///
+    /// ## Errors
+    ///
+    /// Give back the message with [`TrySendError::Full`] if the queue is full
+    /// and [`TrySendError::Closed`] if the queue has been closed.
+    fn try_send(&self, message: Message) -> Result<(), TrySendError> {
+        self.send(message).map_err(TrySendError::Closed)
+    }
+
+    /// Push a message to the queue, waiting up to `timeout` for room if it's bounded and full
+    ///
+    /// The default implementation poll [`QueueBackend::try_send`] until the timeout elapse,
+    /// backend that can wait for room should override it.
+    ///
+    /// ## Errors
+    ///
+    /// Give back the message with [`TrySendError::Full`] if the queue is still full after `timeout`
+    /// and [`TrySendError::Closed`] if the queue has been closed.
+    fn send_timeout(&self, message: Message, timeout: Duration) -> Result<(), TrySendError> {
+        let deadline = Instant::now() + timeout;
+        let mut message = message;
+
+        loop {
+            match self.try_send(message) {
+                Err(TrySendError::Full(full)) if Instant::now() < deadline => {
+                    message = full;
+                    thread::sleep(Duration::from_millis(1));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Block until a message is available
+    ///
+    /// Return [`None`] once the queue is closed and there are no pending message left.
+    fn recv(&self) -> Option<Message>;
+
+    /// Receive a message without blocking
+    ///
+    /// ## Errors
+    ///
+    /// Return [`TryRecvError::Empty`] if there are no pending message
+    /// and [`TryRecvError::Closed`] once the queue is closed and drained.
+    fn try_recv(&self) -> Result<Message, TryRecvError>;
+
+    /// Block until a message is available or the timeout elapsed
+    ///
+    /// ## Errors
+    ///
+    /// Return [`TryRecvError::Empty`] if no message arrived in time
+    /// and [`TryRecvError::Closed`] once the queue is closed and drained.
+    fn recv_timeout(&self, timeout: Duration) -> Result<Message, TryRecvError>;
+
+    /// Receive up to `max` pending message into `batch` without blocking
+    ///
+    /// The default implementation call [`QueueBackend::try_recv`] repeatedly,
+    /// backend that need locking should override it to only lock once.
+    fn try_recv_batch(&self, max: usize, batch: &mut Vec<Message>) {
+        for _ in 0..max {
+            match self.try_recv() {
+                Ok(message) => batch.push(message),
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Number of message waiting in the queue
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no message waiting in the queue
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of message the queue can hold, [`None`] if it's unbounded
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Close the queue, further [`QueueBackend::send`] will fail
+    /// but pending message can still be received.
+    fn close(&self);
+}
@@ -0,0 +1,245 @@
+use std::sync::atomic::{fence, Ordering};
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+use crossbeam_queue::{ArrayQueue, SegQueue};
+
+use crate::backend::{QueueBackend, TryRecvError, TrySendError};
+use crate::sync::{AtomicBool, AtomicUsize, Condvar, Mutex, MutexGuard, RwLock};
+use crate::Message;
+
+/// [`QueueBackend`] used by the `mpsc` feature when `crossbeam-channel` isn't wanted
+///
+/// Message are kept in a lock-free queue so every worker can pop concurrently,
+/// a [`Mutex`] is only taken to park a worker when the queue is empty (or a sender when it's full).
+#[derive(Debug)]
+pub struct Mpsc {
+    queue: Queue,
+    /// Held for reading while pushing so no message sneak in after [`QueueBackend::close`]
+    open: RwLock<bool>,
+    closed: AtomicBool,
+    parking: Parking,
+}
+
+#[derive(Debug)]
+enum Queue {
+    Unbounded(SegQueue<Message>),
+    Bounded(ArrayQueue<Message>),
+}
+
+/// Where receiver wait for a message and sender wait for room
+#[derive(Debug, Default)]
+struct Parking {
+    lock: Mutex<()>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    receivers: AtomicUsize,
+    senders: AtomicUsize,
+}
+
+impl Queue {
+    fn push(&self, message: Message) -> Result<(), Message> {
+        match self {
+            Queue::Unbounded(queue) => {
+                queue.push(message);
+                Ok(())
+            }
+            Queue::Bounded(queue) => queue.push(message),
+        }
+    }
+
+    fn pop(&self) -> Option<Message> {
+        match self {
+            Queue::Unbounded(queue) => queue.pop(),
+            Queue::Bounded(queue) => queue.pop(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Queue::Unbounded(queue) => queue.len(),
+            Queue::Bounded(queue) => queue.len(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        match self {
+            Queue::Unbounded(_) => false,
+            Queue::Bounded(queue) => queue.is_full(),
+        }
+    }
+}
+
+impl Parking {
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wake a parked thread, the lock is taken so the wakeup cannot slip between it's check and it's wait
+    fn wake(&self, parked: &AtomicUsize, condvar: &Condvar) {
+        fence(Ordering::SeqCst);
+        if parked.load(Ordering::SeqCst) > 0 {
+            drop(self.lock());
+            condvar.notify_one();
+        }
+    }
+
+    /// Park until woken or `deadline` unless `ready` already hold, the caller has to check again after
+    fn park(
+        &self,
+        parked: &AtomicUsize,
+        condvar: &Condvar,
+        deadline: Option<Instant>,
+        ready: impl Fn() -> bool,
+    ) {
+        let guard = self.lock();
+        parked.fetch_add(1, Ordering::SeqCst);
+
+        if !ready() {
+            match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    let _guard = condvar
+                        .wait_timeout(guard, timeout)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+                None => {
+                    let _guard = condvar.wait(guard).unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+
+        parked.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Mpsc {
+    /// Create a queue that hold at most `capacity` message, sending to a full queue wait for room
+    pub fn bounded(capacity: usize) -> Mpsc {
+        Mpsc::with_queue(Queue::Bounded(ArrayQueue::new(capacity.max(1))))
+    }
+
+    fn with_queue(queue: Queue) -> Mpsc {
+        Mpsc {
+            queue,
+            open: RwLock::new(true),
+            closed: AtomicBool::new(false),
+            parking: Parking::default(),
+        }
+    }
+
+    /// Push a message waiting up to `deadline` for room, [`None`] wait forever
+    fn push(&self, message: Message, deadline: Option<Instant>) -> Result<(), TrySendError> {
+        let mut message = message;
+
+        loop {
+            {
+                let open = self.open.read().unwrap_or_else(PoisonError::into_inner);
+                if !*open {
+                    return Err(TrySendError::Closed(message));
+                }
+
+                match self.queue.push(message) {
+                    Ok(()) => {
+                        self.parking
+                            .wake(&self.parking.receivers, &self.parking.not_empty);
+                        return Ok(());
+                    }
+                    Err(full) => message = full,
+                }
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(TrySendError::Full(message));
+            }
+
+            // The read lock is released while parked so closing the queue isn't held up
+            self.parking.park(
+                &self.parking.senders,
+                &self.parking.not_full,
+                deadline,
+                || !self.queue.is_full() || self.closed.load(Ordering::SeqCst),
+            );
+        }
+    }
+
+    /// Pop a message waiting up to `deadline` for one, [`None`] wait forever
+    fn pop(&self, deadline: Option<Instant>) -> Result<Message, TryRecvError> {
+        loop {
+            if let Some(message) = self.queue.pop() {
+                self.parking
+                    .wake(&self.parking.senders, &self.parking.not_full);
+                return Ok(message);
+            }
+
+            if self.closed.load(Ordering::SeqCst) && self.queue.len() == 0 {
+                return Err(TryRecvError::Closed);
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(TryRecvError::Empty);
+            }
+
+            self.parking.park(
+                &self.parking.receivers,
+                &self.parking.not_empty,
+                deadline,
+                || self.queue.len() > 0 || self.closed.load(Ordering::SeqCst),
+            );
+        }
+    }
+}
+
+impl Default for Mpsc {
+    fn default() -> Self {
+        Mpsc::with_queue(Queue::Unbounded(SegQueue::new()))
+    }
+}
+
+impl QueueBackend for Mpsc {
+    fn send(&self, message: Message) -> Result<(), Message> {
+        self.push(message, None).map_err(|err| match err {
+            TrySendError::Full(message) | TrySendError::Closed(message) => message,
+        })
+    }
+
+    fn try_send(&self, message: Message) -> Result<(), TrySendError> {
+        self.push(message, Some(Instant::now()))
+    }
+
+    fn send_timeout(&self, message: Message, timeout: Duration) -> Result<(), TrySendError> {
+        self.push(message, Some(Instant::now() + timeout))
+    }
+
+    fn recv(&self) -> Option<Message> {
+        self.pop(None).ok()
+    }
+
+    fn try_recv(&self) -> Result<Message, TryRecvError> {
+        self.pop(Some(Instant::now()))
+    }
+
+    fn recv_timeout(&self, timeout: Duration) -> Result<Message, TryRecvError> {
+        self.pop(Some(Instant::now() + timeout))
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        match &self.queue {
+            Queue::Unbounded(_) => None,
+            Queue::Bounded(queue) => Some(queue.capacity()),
+        }
+    }
+
+    fn close(&self) {
+        *self.open.write().unwrap_or_else(PoisonError::into_inner) = false;
+        self.closed.store(true, Ordering::SeqCst);
+
+        drop(self.parking.lock());
+        self.parking.not_empty.notify_all();
+        self.parking.not_full.notify_all();
+    }
+}
@@ -0,0 +1,179 @@
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::join_set::JoinSet;
+use crate::ThreadPool;
+
+/// How [`BatchHandle::wait_collect`] deal with a failed job
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchPolicy {
+    /// Return the first failure as soon as it happen and cancel the job that has not started yet
+    #[default]
+    FirstError,
+    /// Wait for every job and return every failure
+    CollectAll,
+}
+
+/// Why a batch failed, see [`BatchHandle::wait_collect`]
+#[derive(Debug)]
+pub enum BatchError<E> {
+    /// The job at `index` returned an error
+    Job { index: usize, error: E },
+    /// The job at `index` didn't return, it panicked or was cancelled
+    Pool {
+        index: usize,
+        error: ThreadPoolError,
+    },
+    /// Every failure ordered by index, returned with [`BatchPolicy::CollectAll`]
+    Many(Vec<BatchError<E>>),
+}
+
+impl<E> BatchError<E> {
+    fn new(index: usize, result: Result<E, ThreadPoolError>) -> BatchError<E> {
+        match result {
+            Ok(error) => BatchError::Job { index, error },
+            Err(error) => BatchError::Pool { index, error },
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            BatchError::Job { index, .. } | BatchError::Pool { index, .. } => *index,
+            BatchError::Many(errors) => errors.first().map_or(0, BatchError::index),
+        }
+    }
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for BatchError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Job { index, error } => {
+                f.write_fmt(format_args!("Batch job {index} failed: {error}"))
+            }
+            BatchError::Pool { index, error } => {
+                f.write_fmt(format_args!("Batch job {index} failed: {error}"))
+            }
+            BatchError::Many(errors) => {
+                f.write_fmt(format_args!("{} batch job failed", errors.len()))
+            }
+        }
+    }
+}
+
+impl<E: core::fmt::Debug + core::fmt::Display> std::error::Error for BatchError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchError::Pool { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Handle to the fallible job submitted with [`ThreadPool::execute_batch`]
+#[derive(Debug)]
+pub struct BatchHandle<T, E> {
+    set: JoinSet<Result<T, E>>,
+    len: usize,
+    policy: BatchPolicy,
+}
+
+impl<T, E> BatchHandle<T, E> {
+    /// Set how [`BatchHandle::wait_collect`] deal with a failed job, default to [`BatchPolicy::FirstError`]
+    pub fn policy(mut self, policy: BatchPolicy) -> BatchHandle<T, E> {
+        self.policy = policy;
+        self
+    }
+
+    /// Number of job in the batch
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the batch has no job
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Block until every job has finished and return their value in submission order
+    ///
+    /// When called from a worker of the pool, the worker run the queued job while waiting.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return the first [`BatchError`] with [`BatchPolicy::FirstError`],
+    /// or [`BatchError::Many`] with every failure with [`BatchPolicy::CollectAll`].
+    pub fn wait_collect(mut self) -> Result<Vec<T>, BatchError<E>> {
+        let mut values: Vec<Option<T>> = (0..self.len).map(|_| None).collect();
+        let mut errors = Vec::new();
+
+        while let Some((index, result)) = self.set.join_next() {
+            let error = match result {
+                Ok(Ok(value)) => {
+                    values[index] = Some(value);
+                    continue;
+                }
+                Ok(Err(error)) => BatchError::new(index, Ok(error)),
+                Err(error) => BatchError::new(index, Err(error)),
+            };
+
+            if self.policy == BatchPolicy::FirstError {
+                self.set.cancel_queued();
+                return Err(error);
+            }
+            errors.push(error);
+        }
+
+        if !errors.is_empty() {
+            errors.sort_by_key(BatchError::index);
+            return Err(BatchError::Many(errors));
+        }
+
+        Ok(values.into_iter().flatten().collect())
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute every fallible job of `jobs` and return a [`BatchHandle`] to collect their result
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{BatchPolicy, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    /// let paths = ["a.txt", "b.txt", "c.txt"];
+    ///
+    /// let files = pool
+    ///     .execute_batch(paths.map(|path| move || std::fs::read_to_string(path)))
+    ///     .unwrap()
+    ///     .policy(BatchPolicy::CollectAll)
+    ///     .wait_collect();
+    ///
+    /// match files {
+    ///     Ok(files) => println!("read {} file", files.len()),
+    ///     Err(error) => println!("{error}"),
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed, the job already submitted still run.
+    pub fn execute_batch<I, F, T, E>(&self, jobs: I) -> Result<BatchHandle<T, E>, ThreadPoolError>
+    where
+        I: IntoIterator<Item = F>,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+    {
+        let mut set = JoinSet::new();
+        for job in jobs {
+            set.spawn(self, job)?;
+        }
+
+        Ok(BatchHandle {
+            len: set.len(),
+            set,
+            policy: BatchPolicy::default(),
+        })
+    }
+}
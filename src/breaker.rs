@@ -0,0 +1,215 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::sync::Mutex;
+use crate::{Job, ThreadPool};
+
+/// Circuit breaker configuration for a job name, see [`ThreadPoolBuilder::circuit_breaker`](crate::ThreadPoolBuilder::circuit_breaker)
+///
+/// After `failures` consecutive failure within `window` the breaker open and submission under the name
+/// fail fast with [`ThreadPoolError::CircuitOpen`] until `cooldown` has passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// Consecutive failure needed to open the breaker
+    pub failures: usize,
+    /// How old the oldest of those failure can be
+    pub window: Duration,
+    /// How long the breaker stay open
+    pub cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker that open after `failures` consecutive failure within `window`,
+    /// and reject job for `cooldown`. `failures` of `0` is treated as `1`.
+    pub fn new(failures: usize, window: Duration, cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker {
+            failures: failures.max(1),
+            window,
+            cooldown,
+        }
+    }
+}
+
+/// Every circuit breaker of the pool, keyed by job name
+#[derive(Debug, Default)]
+pub(crate) struct Breakers {
+    breakers: HashMap<String, Arc<Breaker>>,
+}
+
+impl Breakers {
+    pub(crate) fn new(config: &HashMap<String, CircuitBreaker>) -> Breakers {
+        Breakers {
+            breakers: config
+                .iter()
+                .map(|(name, config)| (name.clone(), Arc::new(Breaker::new(*config))))
+                .collect(),
+        }
+    }
+
+    /// Check the breaker of the job name and make the job report it's outcome to it
+    pub(crate) fn guard(&self, job: Job) -> Result<Job, ThreadPoolError> {
+        let Some(breaker) = job.name().and_then(|name| self.breakers.get(name)) else {
+            return Ok(job);
+        };
+
+        breaker.check(job.name().unwrap_or_default())?;
+        let breaker = Arc::clone(breaker);
+
+        Ok(job.wrap(move |job| {
+            let outcome = Outcome::new(breaker);
+            job.run();
+            outcome.succeed();
+        }))
+    }
+
+    /// Check the breaker of `name` and wrap a fallible job so an [`Err`] count as a failure
+    pub(crate) fn guard_fallible<F, E>(
+        &self,
+        name: &str,
+        job: F,
+    ) -> Result<Box<dyn FnOnce() + Send>, ThreadPoolError>
+    where
+        F: FnOnce() -> Result<(), E> + Send + 'static,
+    {
+        let Some(breaker) = self.breakers.get(name) else {
+            return Ok(Box::new(move || drop(job())));
+        };
+
+        breaker.check(name)?;
+        let breaker = Arc::clone(breaker);
+
+        Ok(Box::new(move || {
+            let outcome = Outcome::new(breaker);
+            if job().is_ok() {
+                outcome.succeed();
+            }
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct Breaker {
+    config: CircuitBreaker,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// When each of the consecutive failure happened
+    failures: VecDeque<Instant>,
+    open_until: Option<Instant>,
+}
+
+impl Breaker {
+    fn new(config: CircuitBreaker) -> Breaker {
+        Breaker {
+            config,
+            state: Mutex::default(),
+        }
+    }
+
+    fn check(&self, name: &str) -> Result<(), ThreadPoolError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match state.open_until {
+            Some(until) if Instant::now() < until => Err(ThreadPoolError::CircuitOpen {
+                name: String::from(name),
+            }),
+            Some(_) => {
+                // The cooldown passed, start over with a closed breaker
+                state.open_until = None;
+                state.failures.clear();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn record(&self, succeeded: bool) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if succeeded {
+            state.failures.clear();
+            return;
+        }
+
+        let now = Instant::now();
+        state.failures.push_back(now);
+        while state
+            .failures
+            .front()
+            .is_some_and(|failure| now.duration_since(*failure) > self.config.window)
+        {
+            state.failures.pop_front();
+        }
+
+        if state.failures.len() >= self.config.failures {
+            state.open_until = Some(now + self.config.cooldown);
+        }
+    }
+}
+
+/// Report a failure to the breaker unless the job succeeded, so a panic count as one
+struct Outcome {
+    breaker: Arc<Breaker>,
+    succeeded: bool,
+}
+
+impl Outcome {
+    fn new(breaker: Arc<Breaker>) -> Outcome {
+        Outcome {
+            breaker,
+            succeeded: false,
+        }
+    }
+
+    fn succeed(mut self) {
+        self.succeeded = true;
+    }
+}
+
+impl Drop for Outcome {
+    fn drop(&mut self) {
+        self.breaker.record(self.succeeded);
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a fallible job under a name like [`ThreadPool::execute_named`],
+    /// an [`Err`] returned by the job count as a failure for the name circuit breaker just like a panic.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, CircuitBreaker, ThreadPoolBuilder};
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .circuit_breaker(
+    ///         "payment",
+    ///         CircuitBreaker::new(5, Duration::from_secs(10), Duration::from_secs(30)),
+    ///     )
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// match pool.execute_named_fallible("payment", || std::fs::read("payment.json").map(drop)) {
+    ///     Err(ThreadPoolError::CircuitOpen { .. }) => println!("payment is failing, try again later"),
+    ///     result => result.unwrap(),
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::CircuitOpen`] if the circuit breaker of the name is open,
+    /// or an [`Err`] if the communication channel between worker thread and main thread is closed.
+    pub fn execute_named_fallible<F, E>(&self, name: &str, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() -> Result<(), E> + Send + 'static,
+    {
+        let job = self.shared.breakers.guard_fallible(name, job)?;
+        self.shared.submit(Job::new(job).named(Arc::from(name)))
+    }
+}
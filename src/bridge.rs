@@ -0,0 +1,154 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+use crate::backend::QueueBackend;
+use crate::context;
+use crate::error::ThreadPoolError;
+use crate::oneshot::{self, Receiver};
+use crate::{Job, ThreadPool};
+
+/// Closure moved to the other pool, `f` is declared first so it's dropped before `sender` tell the caller it's done
+struct Bridged<F, R> {
+    f: F,
+    sender: oneshot::Sender<thread::Result<R>>,
+}
+
+/// Turn `f` into a job for the other pool, the panic of `f` is carried back to the caller
+///
+/// ## Safety
+///
+/// The caller must not return before the receiver has a result, which happen once the job ran or got dropped,
+/// since the job can borrow from the caller stack frame.
+unsafe fn bridge<'a, F, R>(
+    f: F,
+) -> (
+    Box<dyn FnOnce() + Send + 'static>,
+    Receiver<thread::Result<R>>,
+)
+where
+    F: FnOnce() -> R + Send + 'a,
+    R: Send + 'a,
+{
+    let (sender, receiver) = oneshot::channel();
+    let bridged = Bridged { f, sender };
+
+    let job: Box<dyn FnOnce() + Send + 'a> = Box::new(move || {
+        let Bridged { f, sender } = bridged;
+        sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+    });
+    // SAFETY: upheld by the caller
+    let job: Box<dyn FnOnce() + Send + 'static> = unsafe { std::mem::transmute(job) };
+
+    (job, receiver)
+}
+
+/// Return the result of a bridged job, resuming it's panic on the calling thread
+fn unwrap_bridged<R>(
+    result: Result<thread::Result<R>, ThreadPoolError>,
+) -> Result<R, ThreadPoolError> {
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(err) => Err(err),
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Run a [`rayon::scope`] on rayon's global pool and return it's result
+    ///
+    /// When called from a job of this pool the worker keep running this pool's queued job
+    /// while rayon process the scope, instead of sitting blocked on it.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// let lines = ["a b", "c d e"];
+    ///
+    /// let words = AtomicUsize::new(0);
+    /// pool.rayon_scope(|scope| {
+    ///     for line in &lines {
+    ///         let words = &words;
+    ///         scope.spawn(move |_| {
+    ///             words.fetch_add(line.split_whitespace().count(), Ordering::SeqCst);
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(words.load(Ordering::SeqCst), 5);
+    /// ```
+    ///
+    /// ## Panic
+    ///
+    /// Will panic if the scope panicked.
+    pub fn rayon_scope<'scope, F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&rayon::Scope<'scope>) -> R + Send,
+        R: Send,
+    {
+        let Some((pool, worker)) = context::worker_of(context::pool_id(&self.shared)) else {
+            return rayon::scope(f);
+        };
+
+        // SAFETY: rayon never drop a spawned job, and the result is received before returning
+        let (job, receiver) = unsafe { bridge(move || rayon::scope(f)) };
+        rayon::spawn(job);
+
+        match unwrap_bridged(receiver.recv_helping(|| pool.help(worker))) {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Run `f` on this pool and return it's result, the counterpart of [`ThreadPool::rayon_scope`]
+    ///
+    /// When called from a rayon worker thread it keep running rayon's job while waiting,
+    /// and when called from a worker of this pool `f` simply run on the calling thread.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{rayon::prelude::*, ThreadPool};
+    ///
+    /// let io = ThreadPool::new(4).unwrap();
+    ///
+    /// let sizes: Vec<_> = ["a.txt", "b.txt"]
+    ///     .par_iter()
+    ///     .map(|path| io.install(|| std::fs::metadata(path).map(|meta| meta.len()).ok()))
+    ///     .collect();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    ///
+    /// ## Panic
+    ///
+    /// Will panic if `f` panicked.
+    pub fn install<F, R>(&self, f: F) -> Result<R, ThreadPoolError>
+    where
+        F: FnOnce() -> R + Send,
+        R: Send,
+    {
+        if context::worker_of(context::pool_id(&self.shared)).is_some() {
+            return Ok(f());
+        }
+
+        // SAFETY: a job that cannot be submitted is dropped before `submit` return,
+        // otherwise the result is received before returning
+        let (job, receiver) = unsafe { bridge(f) };
+        self.shared.submit(Job::new(job))?;
+
+        let result = match rayon::current_thread_index() {
+            Some(_) => receiver.recv_helping(|| rayon::yield_now() == Some(rayon::Yield::Executed)),
+            None => receiver.recv(),
+        };
+
+        unwrap_bridged(result)
+    }
+}
@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::backend::QueueBackend;
+use crate::breaker::CircuitBreaker;
+use crate::control::{Control, ControlHandler};
+use crate::error::ThreadPoolError;
+use crate::hook::Hook;
+use crate::idle::IdleStrategy;
+use crate::middleware::Middleware;
+use crate::named::NamedQueueConfig;
+#[cfg(feature = "numa")]
+use crate::numa::StealStrategy;
+#[cfg(feature = "otel")]
+use crate::otel::{OtelSpans, SpanRelation};
+#[cfg(feature = "priority")]
+use crate::priority::ThreadPriority;
+use crate::propagate::{ContextPropagator, Propagate};
+use crate::report::PanicReporter;
+use crate::scheduler::{DeadlineScheduler, LifoScheduler, PriorityScheduler, Scheduler};
+use crate::shared::Shared;
+use crate::spawner::{StdSpawner, ThreadSpawner};
+use crate::starvation::{StarvationHook, StarvedJob};
+use crate::supervisor::{RestartHook, RestartPolicy, WorkerRestart};
+use crate::watch::Watermarks;
+use crate::worker;
+use crate::{DefaultBackend, ThreadPool};
+
+/// What the [`ThreadPool`] should do with it's worker when it's dropped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Wait until every queued job has been executed
+    #[default]
+    WaitForQueued,
+    /// Wait for the running job to finish, queued job will be discarded
+    FinishRunningOnly,
+    /// Don't wait for the worker, they will keep running the queued job in the background
+    Detach,
+}
+
+/// What [`ThreadPool::execute`] do when the queue is bounded and full,
+/// see [`ThreadPoolBuilder::queue_capacity`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RejectionPolicy {
+    /// Wait until a worker make room in the queue
+    #[default]
+    Block,
+    /// Return [`ThreadPoolError::QueueFull`] right away
+    Reject,
+}
+
+impl std::str::FromStr for RejectionPolicy {
+    type Err = ThreadPoolError;
+
+    /// Parse `block` or `reject`, case insensitive
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "block" => Ok(RejectionPolicy::Block),
+            "reject" => Ok(RejectionPolicy::Reject),
+            _ => Err(ThreadPoolError::InvalidConfig {
+                key: String::from("rejection_policy"),
+            }),
+        }
+    }
+}
+
+/// Configure and create a [`ThreadPool`]
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{DropPolicy, ThreadPoolBuilder};
+///
+/// let pool = ThreadPoolBuilder::new(4)
+///     .on_drop(DropPolicy::FinishRunningOnly)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThreadPoolBuilder {
+    pub(crate) core_workers: usize,
+    pub(crate) max_workers: usize,
+    pub(crate) keep_alive: Duration,
+    pub(crate) drop_policy: DropPolicy,
+    pub(crate) job_duration: bool,
+    pub(crate) panic_backtrace: bool,
+    pub(crate) idle: IdleStrategy,
+    pub(crate) batch_size: usize,
+    pub(crate) max_jobs_per_worker: Option<usize>,
+    pub(crate) lifo: bool,
+    pub(crate) earliest_deadline_first: bool,
+    pub(crate) priority_aging: Option<Duration>,
+    pub(crate) scheduler: Option<Hook<dyn Scheduler>>,
+    #[cfg(feature = "numa")]
+    pub(crate) numa: bool,
+    #[cfg(feature = "numa")]
+    pub(crate) steal_strategy: StealStrategy,
+    #[cfg(feature = "numa")]
+    pub(crate) steal_batch: usize,
+    #[cfg(feature = "priority")]
+    pub(crate) priority: Option<ThreadPriority>,
+    #[cfg(feature = "rename")]
+    pub(crate) rename: bool,
+    pub(crate) lazy: bool,
+    pub(crate) restart_policy: RestartPolicy,
+    pub(crate) on_restart: Option<RestartHook>,
+    pub(crate) on_idle: Option<Hook<dyn Fn() + Send + Sync>>,
+    pub(crate) panic_reporter: Option<Hook<dyn PanicReporter>>,
+    pub(crate) starvation: Option<(Duration, StarvationHook)>,
+    pub(crate) queue_capacity: Option<usize>,
+    pub(crate) rejection: RejectionPolicy,
+    pub(crate) caller_runs: Option<usize>,
+    pub(crate) watermarks: Option<Watermarks>,
+    pub(crate) name: Option<String>,
+    pub(crate) thread_name: Option<String>,
+    pub(crate) spawner: Hook<dyn ThreadSpawner>,
+    pub(crate) propagators: Vec<Hook<dyn Propagate>>,
+    pub(crate) controls: Vec<Hook<dyn Control>>,
+    pub(crate) layers: Vec<Hook<dyn Middleware>>,
+    pub(crate) circuit_breakers: HashMap<String, CircuitBreaker>,
+    pub(crate) named_queues: Vec<NamedQueueConfig>,
+}
+
+impl ThreadPoolBuilder {
+    /// Creates a new [`ThreadPoolBuilder`], with passed worker args for how many worker thread to be created
+    pub fn new(worker: usize) -> ThreadPoolBuilder {
+        ThreadPoolBuilder {
+            core_workers: worker,
+            max_workers: worker,
+            keep_alive: Duration::from_secs(60),
+            drop_policy: DropPolicy::default(),
+            job_duration: false,
+            panic_backtrace: false,
+            idle: IdleStrategy::default(),
+            batch_size: 1,
+            max_jobs_per_worker: None,
+            lifo: false,
+            earliest_deadline_first: false,
+            priority_aging: None,
+            scheduler: None,
+            #[cfg(feature = "numa")]
+            numa: false,
+            #[cfg(feature = "numa")]
+            steal_strategy: StealStrategy::default(),
+            #[cfg(feature = "numa")]
+            steal_batch: 1,
+            #[cfg(feature = "priority")]
+            priority: None,
+            #[cfg(feature = "rename")]
+            rename: false,
+            lazy: false,
+            restart_policy: RestartPolicy::default(),
+            on_restart: None,
+            on_idle: None,
+            panic_reporter: None,
+            starvation: None,
+            queue_capacity: None,
+            rejection: RejectionPolicy::default(),
+            caller_runs: None,
+            watermarks: None,
+            name: None,
+            thread_name: None,
+            spawner: Hook::new(Arc::new(StdSpawner)),
+            propagators: Vec::new(),
+            controls: Vec::new(),
+            layers: Vec::new(),
+            circuit_breakers: HashMap::new(),
+            named_queues: Vec::new(),
+        }
+    }
+
+    /// Set how many worker are always kept alive, default to the worker count passed to [`ThreadPoolBuilder::new`]
+    pub fn core_workers(mut self, core_workers: usize) -> ThreadPoolBuilder {
+        self.core_workers = core_workers;
+        self
+    }
+
+    /// Set the maximum number of worker, worker above the core count are spawned when a job is submitted
+    /// and no worker is idle, then retired after being idle for the [`ThreadPoolBuilder::keep_alive`] period.
+    ///
+    /// Default to the worker count passed to [`ThreadPoolBuilder::new`], it's never lower than the core count.
+    pub fn max_workers(mut self, max_workers: usize) -> ThreadPoolBuilder {
+        self.max_workers = max_workers;
+        self
+    }
+
+    /// Set how long a worker above the core count can stay idle before it's retired, default to 60 seconds
+    pub fn keep_alive(mut self, keep_alive: Duration) -> ThreadPoolBuilder {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Set what the [`ThreadPool`] should do with it's worker when it's dropped
+    pub fn on_drop(mut self, policy: DropPolicy) -> ThreadPoolBuilder {
+        self.drop_policy = policy;
+        self
+    }
+
+    /// Record how long every job take to execute, the histogram is available through
+    /// [`ThreadPool::stats`], it's disabled by default since it cost two extra clock read per job.
+    pub fn record_job_duration(mut self, enabled: bool) -> ThreadPoolBuilder {
+        self.job_duration = enabled;
+        self
+    }
+
+    /// Capture a backtrace when a job panic and attach it to [`PanicDetails::backtrace`](crate::PanicDetails::backtrace),
+    /// it's disabled by default. It install a panic hook that capture the backtrace before calling the existing one.
+    pub fn capture_panic_backtrace(mut self, enabled: bool) -> ThreadPoolBuilder {
+        self.panic_backtrace = enabled;
+        self
+    }
+
+    /// Set what the worker do while waiting for a job, see [`IdleStrategy`]
+    pub fn idle_strategy(mut self, idle: IdleStrategy) -> ThreadPoolBuilder {
+        self.idle = idle;
+        self
+    }
+
+    /// Let every worker take up to `batch_size` job from the queue on each wakeup and run them back-to-back,
+    /// it reduce the synchronization overhead on flood of tiny job at the cost of fairness between worker.
+    ///
+    /// Default to `1`, `0` is treated as `1`.
+    pub fn batch_size(mut self, batch_size: usize) -> ThreadPoolBuilder {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Replace a worker with a fresh thread once it ran `max_jobs` job, like the `max_requests` of
+    /// php-fpm or gunicorn it contain slow leak of thread local state in third-party code run by the job.
+    ///
+    /// Worker are never recycled by default, `0` is treated as `1`.
+    pub fn max_jobs_per_worker(mut self, max_jobs: usize) -> ThreadPoolBuilder {
+        self.max_jobs_per_worker = Some(max_jobs.max(1));
+        self
+    }
+
+    /// Run the most recently submitted job first instead of the oldest one,
+    /// it improve cache locality for fork-join style workload. Default to `false` (FIFO).
+    pub fn lifo(mut self, lifo: bool) -> ThreadPoolBuilder {
+        self.lifo = lifo;
+        self
+    }
+
+    /// Run the job with the nearest deadline first, see [`ThreadPool::execute_with_deadline`].
+    /// Job without a deadline run once no job with one is waiting, it take precedence over [`ThreadPoolBuilder::lifo`].
+    /// Default to `false`.
+    pub fn earliest_deadline_first(mut self, edf: bool) -> ThreadPoolBuilder {
+        self.earliest_deadline_first = edf;
+        self
+    }
+
+    /// Run the job with the highest priority first like [`PriorityScheduler`], a waiting job gain one priority level
+    /// every `aging` period so low priority job are not starved. It take precedence over
+    /// [`ThreadPoolBuilder::lifo`] and [`ThreadPoolBuilder::earliest_deadline_first`], a zero `aging` disable the aging.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .priority_aging(Duration::from_millis(100))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Overtake a priority 10 job submitted right now after waiting one second
+    /// pool.execute_with_priority(0, || println!("cleanup")).unwrap();
+    /// ```
+    pub fn priority_aging(mut self, aging: Duration) -> ThreadPoolBuilder {
+        self.priority_aging = Some(aging);
+        self
+    }
+
+    /// Decide which waiting job run next with a custom [`Scheduler`], it take precedence over
+    /// [`ThreadPoolBuilder::lifo`], [`ThreadPoolBuilder::earliest_deadline_first`] and [`ThreadPoolBuilder::priority_aging`].
+    pub fn scheduler<S: Scheduler>(mut self, scheduler: S) -> ThreadPoolBuilder {
+        self.scheduler = Some(Hook::new(Arc::new(scheduler)));
+        self
+    }
+
+    /// Scheduler picked by the configuration, [`None`] keep the queue order
+    pub(crate) fn run_queue(&self) -> Option<Hook<dyn Scheduler>> {
+        if let Some(scheduler) = &self.scheduler {
+            return Some(scheduler.clone());
+        }
+
+        let scheduler: Arc<dyn Scheduler> = if let Some(aging) = self.priority_aging {
+            Arc::new(PriorityScheduler::with_aging(aging))
+        } else if self.earliest_deadline_first {
+            Arc::new(DeadlineScheduler::default())
+        } else if self.lifo {
+            Arc::new(LifoScheduler::default())
+        } else {
+            return None;
+        };
+
+        Some(Hook::new(scheduler))
+    }
+
+    /// Partition the worker across the NUMA node of the machine, every worker is pinned to the CPU
+    /// of it's node and job submitted with [`ThreadPool::execute_on_node`] prefer the worker of that node.
+    #[cfg(feature = "numa")]
+    pub fn numa(mut self, numa: bool) -> ThreadPoolBuilder {
+        self.numa = numa;
+        self
+    }
+
+    /// Set how a worker with nothing left on it's NUMA node pick the node it steal from,
+    /// default to [`StealStrategy::RoundRobin`]
+    #[cfg(feature = "numa")]
+    pub fn steal_strategy(mut self, strategy: StealStrategy) -> ThreadPoolBuilder {
+        self.steal_strategy = strategy;
+        self
+    }
+
+    /// Set how many job a worker take at once when it steal from another NUMA node, the extra one
+    /// are moved to it's own node. A bigger batch suit many tiny job, default to `1`, `0` is treated as `1`.
+    #[cfg(feature = "numa")]
+    pub fn steal_batch(mut self, batch: usize) -> ThreadPoolBuilder {
+        self.steal_batch = batch.max(1);
+        self
+    }
+
+    /// Set the OS scheduling priority of every worker thread,
+    /// useful to keep background work from competing with latency sensitive thread.
+    #[cfg(feature = "priority")]
+    pub fn thread_priority(mut self, priority: ThreadPriority) -> ThreadPoolBuilder {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Set the OS thread name to the job name while a job executed with [`ThreadPool::execute_named`]
+    /// run, so profiler and `top -H` show what each worker is doing. The worker name is restored after.
+    #[cfg(feature = "rename")]
+    pub fn rename_thread_per_job(mut self, rename: bool) -> ThreadPoolBuilder {
+        self.rename = rename;
+        self
+    }
+
+    /// Don't spawn any worker upfront, a worker is spawned when a job is submitted
+    /// and no worker is idle, up to the [`ThreadPoolBuilder::max_workers`] count.
+    pub fn lazy(mut self, lazy: bool) -> ThreadPoolBuilder {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Set whether a worker that died from a panicking job is replaced, see [`RestartPolicy`]
+    pub fn restart_policy(mut self, policy: RestartPolicy) -> ThreadPoolBuilder {
+        self.restart_policy = policy;
+        self
+    }
+
+    /// Call `callback` every time a dead worker is replaced according to the [`RestartPolicy`],
+    /// it's called from the thread of the dead worker.
+    pub fn on_worker_restart<F>(mut self, callback: F) -> ThreadPoolBuilder
+    where
+        F: Fn(&WorkerRestart) + Send + Sync + 'static,
+    {
+        self.on_restart = Some(Hook::new(Arc::new(callback)));
+        self
+    }
+
+    /// Call `callback` every time the pool goes from running job to fully idle, the queue is empty
+    /// and every worker is waiting. It's called from the last worker to become idle.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let (idle, wait_idle) = channel();
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .on_idle(move || idle.send(()).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// for wave in 0..3 {
+    ///     for part in 0..100 {
+    ///         pool.execute(move || println!("wave {wave} part {part}")).unwrap();
+    ///     }
+    ///     // Only submit the next wave once the previous one is done
+    ///     wait_idle.recv().unwrap();
+    /// }
+    /// ```
+    pub fn on_idle<F>(mut self, callback: F) -> ThreadPoolBuilder
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_idle = Some(Hook::new(Arc::new(callback)));
+        self
+    }
+
+    /// Report every job that panic to `reporter`, replacing the previous one, see [`PanicReporter`]
+    pub fn panic_reporter(mut self, reporter: impl PanicReporter) -> ThreadPoolBuilder {
+        self.panic_reporter = Some(Hook::new(Arc::new(reporter)));
+        self
+    }
+
+    /// Call `callback` when a job has been waiting longer than `threshold` without starting,
+    /// it usually mean the pool is undersized or a class of job is starved by the scheduler.
+    /// A [`PoolEvent::JobStarved`](crate::PoolEvent::JobStarved) is sent to subscriber as well.
+    ///
+    /// It's called once per job from the watchdog thread, so it should return quickly.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .on_starvation(Duration::from_secs(30), |starved| {
+    ///         eprintln!("{:?} waited {:?} to start", starved.job, starved.waited)
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn on_starvation<F>(mut self, threshold: Duration, callback: F) -> ThreadPoolBuilder
+    where
+        F: Fn(&StarvedJob) + Send + Sync + 'static,
+    {
+        self.starvation = Some((threshold, Hook::new(Arc::new(callback))));
+        self
+    }
+
+    /// Limit how many job can wait in the queue, what happen to job submitted to a full queue
+    /// is decided by the [`RejectionPolicy`]. The queue is unbounded by default.
+    ///
+    /// Only used by [`ThreadPoolBuilder::build`], a backend passed to [`ThreadPoolBuilder::build_with_backend`]
+    /// is already created with it's own capacity.
+    pub fn queue_capacity(mut self, capacity: usize) -> ThreadPoolBuilder {
+        self.queue_capacity = Some(capacity);
+        self
+    }
+
+    /// Set what happen to job submitted while the queue is full, default to [`RejectionPolicy::Block`]
+    pub fn rejection_policy(mut self, policy: RejectionPolicy) -> ThreadPoolBuilder {
+        self.rejection = policy;
+        self
+    }
+
+    /// Run a submitted job on the calling thread instead of queuing it when every worker is busy,
+    /// the pool can't grow and at least `threshold` message are already waiting in the queue
+    ///
+    /// A producer feeding the pool faster than it can keep up is slowed down by running the job itself,
+    /// it's applied before the [`RejectionPolicy`] which only matter once the queue is full.
+    /// A job that panic on the calling thread is recorded like a worker that died from it.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(4).caller_runs(64).build().unwrap();
+    ///
+    /// for line in std::io::stdin().lines() {
+    ///     let line = line.unwrap();
+    ///     // Once 64 line are waiting the loop parse the next one itself
+    ///     pool.execute(move || println!("{}", line.len())).unwrap();
+    /// }
+    /// ```
+    pub fn caller_runs(mut self, threshold: usize) -> ThreadPoolBuilder {
+        self.caller_runs = Some(threshold);
+        self
+    }
+
+    /// Queue depth from which the pool is considered backed up, crossing it in either direction
+    /// is reported to every [`StateWatch`](crate::StateWatch) of [`ThreadPool::state_watch`]
+    ///
+    /// Same as [`ThreadPoolBuilder::queue_watermarks`] with the low water mark right below it and no callback.
+    pub fn high_water_mark(mut self, depth: usize) -> ThreadPoolBuilder {
+        self.watermarks = Some(Watermarks {
+            high: depth,
+            low: depth.saturating_sub(1),
+            on_high: None,
+            on_low: None,
+        });
+        self
+    }
+
+    /// Call `on_high` once the queue depth reach `high`, then `on_low` once it fall back to `low`,
+    /// so a producer can pause and resume feeding the pool
+    ///
+    /// The gap between the two watermarks keep a queue hovering around one of them from flipping
+    /// back and forth, `low` is lowered to `high - 1` when it's not below `high`.
+    /// Every callback receive the queue depth, `on_high` is called from the submitting thread
+    /// and `on_low` from the worker that took the job. The crossing are also reported
+    /// to [`ThreadPool::state_watch`].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let paused = Arc::new(AtomicBool::new(false));
+    /// let (pause, resume) = (Arc::clone(&paused), Arc::clone(&paused));
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .queue_watermarks(
+    ///         1000,
+    ///         100,
+    ///         move |_| pause.store(true, Ordering::SeqCst),
+    ///         move |_| resume.store(false, Ordering::SeqCst),
+    ///     )
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// for record in 0..1_000_000 {
+    ///     while paused.load(Ordering::SeqCst) {
+    ///         thread::sleep(Duration::from_millis(1));
+    ///     }
+    ///     pool.execute(move || println!("processing record {record}")).unwrap();
+    /// }
+    /// ```
+    pub fn queue_watermarks<H, L>(
+        mut self,
+        high: usize,
+        low: usize,
+        on_high: H,
+        on_low: L,
+    ) -> ThreadPoolBuilder
+    where
+        H: Fn(usize) + Send + Sync + 'static,
+        L: Fn(usize) + Send + Sync + 'static,
+    {
+        self.watermarks = Some(Watermarks {
+            high,
+            low: low.min(high.saturating_sub(1)),
+            on_high: Some(Hook::new(Arc::new(on_high))),
+            on_low: Some(Hook::new(Arc::new(on_low))),
+        });
+        self
+    }
+
+    /// Name the pool so it can be told apart from the other pool in the process
+    ///
+    /// With the `metrics` feature every metric published through the `metrics` crate
+    /// carry it as the `pool` label, unnamed pool are labeled `default`.
+    /// The recorder has to be installed before the pool is built.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let io = ThreadPoolBuilder::new(16).name("io").build().unwrap();
+    /// let cpu = ThreadPoolBuilder::new(4).name("cpu").build().unwrap();
+    ///
+    /// assert_eq!(io.name(), Some("io"));
+    /// ```
+    pub fn name(mut self, name: impl Into<String>) -> ThreadPoolBuilder {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Name every worker thread `{prefix}-{index}`, the name show up in panic message and debugger
+    pub fn thread_name(mut self, prefix: impl Into<String>) -> ThreadPoolBuilder {
+        self.thread_name = Some(prefix.into());
+        self
+    }
+
+    /// Create every worker thread through `spawner` instead of [`StdSpawner`], see [`ThreadSpawner`]
+    pub fn spawner(mut self, spawner: impl ThreadSpawner) -> ThreadPoolBuilder {
+        self.spawner = Hook::new(Arc::new(spawner));
+        self
+    }
+
+    /// Capture a context with `propagator` on every job submission and install it on the worker around the job,
+    /// see [`ContextPropagator`]. It can be called more than once, context are installed in the order they are added.
+    ///
+    /// Job spawned with [`JobContext::spawn_local`](crate::JobContext::spawn_local) already run on the worker
+    /// and doesn't go through the propagator.
+    pub fn context_propagator(mut self, propagator: impl ContextPropagator) -> ThreadPoolBuilder {
+        self.propagators
+            .push(Hook::new(Arc::new(Arc::new(propagator))));
+        self
+    }
+
+    /// Run every job inside an OpenTelemetry span created with the global tracer, the span is named after
+    /// the job name given to [`ThreadPool::execute_named`](crate::ThreadPool::execute_named) or `thread_pool.job`.
+    /// `relation` decide whether it's linked to the span that submitted the job or nested under it,
+    /// so a distributed trace show the hop through the pool. It's installed like a [`ContextPropagator`].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{SpanRelation, ThreadPoolBuilder};
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .otel_spans(SpanRelation::Link)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "otel")]
+    pub fn otel_spans(mut self, relation: SpanRelation) -> ThreadPoolBuilder {
+        self.propagators
+            .push(Hook::new(Arc::new(OtelSpans { relation })));
+        self
+    }
+
+    /// Wrap the execution of every job with `middleware`, see [`Middleware`]. It can be called more than once,
+    /// the first middleware added is the outermost one.
+    pub fn layer(mut self, middleware: impl Middleware) -> ThreadPoolBuilder {
+        self.layers.push(Hook::new(Arc::new(middleware)));
+        self
+    }
+
+    /// Guard job executed under `name` with a [`CircuitBreaker`], once it open [`ThreadPool::execute_named`]
+    /// and [`ThreadPool::execute_named_fallible`] reject the job under this name right away.
+    /// A breaker set for the same name as a previous one replace it.
+    pub fn circuit_breaker(
+        mut self,
+        name: impl Into<String>,
+        breaker: CircuitBreaker,
+    ) -> ThreadPoolBuilder {
+        self.circuit_breakers.insert(name.into(), breaker);
+        self
+    }
+
+    /// Register a [`ControlHandler`] for the command sent with [`ThreadPool::control`] and [`ThreadPool::control_any`],
+    /// a handler registered for the same command type as a previous one replace it.
+    pub fn control_handler(mut self, handler: impl ControlHandler) -> ThreadPoolBuilder {
+        let handler: Hook<dyn Control> = Hook::new(Arc::new(handler));
+        self.controls
+            .retain(|control| control.command() != handler.command());
+        self.controls.push(handler);
+        self
+    }
+
+    /// Declare a named queue reachable through [`ThreadPool::queue`], it can hold up to `capacity` job
+    /// (unbounded if [`None`]) and the worker take up to `weight` job from it in a row before moving on
+    /// to the next named queue. A `weight` of `0` is treated as `1`.
+    pub fn named_queue(
+        mut self,
+        name: impl Into<String>,
+        weight: usize,
+        capacity: Option<usize>,
+    ) -> ThreadPoolBuilder {
+        self.named_queues.push(NamedQueueConfig {
+            name: name.into(),
+            weight: weight.max(1),
+            capacity,
+        });
+        self
+    }
+
+    /// Creates the [`ThreadPool`] using [`DefaultBackend`]
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker
+    pub fn build(self) -> Result<ThreadPool, ThreadPoolError> {
+        let queue = match self.queue_capacity {
+            Some(capacity) => DefaultBackend::bounded(capacity),
+            None => DefaultBackend::default(),
+        };
+
+        self.build_with_backend(queue)
+    }
+
+    /// Creates the [`ThreadPool`] that use the passed [`QueueBackend`] to send job to it's worker
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker,
+    /// the worker that already spawned will be shut down before returning.
+    pub fn build_with_backend<B: QueueBackend>(
+        self,
+        queue: B,
+    ) -> Result<ThreadPool<B>, ThreadPoolError> {
+        let shared = Arc::new(Shared::new(queue, &self));
+
+        let upfront = if self.lazy { 0 } else { self.core_workers };
+        for index in 0..upfront {
+            if let Err(source) = worker::spawn(&shared) {
+                // Let the already spawned worker exit on their own before reporting
+                shared.close();
+                shared.join_workers();
+
+                return Err(ThreadPoolError::SpawnFailed { index, source });
+            }
+        }
+
+        Ok(ThreadPool {
+            shared,
+            drop_policy: self.drop_policy,
+        })
+    }
+}
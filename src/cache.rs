@@ -0,0 +1,117 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::backend::QueueBackend;
+use crate::cancel::Ticket;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::oneshot;
+use crate::sync::Mutex;
+use crate::ThreadPool;
+
+/// Result of [`ThreadPool::execute_cached`] keyed by result type and key
+#[derive(Debug, Default)]
+pub(crate) struct Cache {
+    entries: Mutex<HashMap<(TypeId, String), Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    expires: Instant,
+    value: Box<dyn Any + Send>,
+}
+
+impl Cache {
+    /// Clone of the cached value of `key` unless it has expired
+    fn get<T: Clone + 'static>(&self, key: &(TypeId, String)) -> Option<T> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = entries.get(key)?;
+
+        if entry.expires <= Instant::now() {
+            return None;
+        }
+
+        entry.value.downcast_ref::<T>().cloned()
+    }
+
+    /// Store the value of `key` and evict every entry that has expired
+    fn insert<T: Send + 'static>(&self, key: (TypeId, String), ttl: Duration, value: T) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+        entries.retain(|_, entry| entry.expires > now);
+        entries.insert(
+            key,
+            Entry {
+                expires: now + ttl,
+                value: Box::new(value),
+            },
+        );
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Returns the cached result of `key` if it was computed less than `ttl` ago,
+    /// otherwise execute `f` and cache it's result for `ttl`
+    ///
+    /// Concurrent miss of the same key share one execution like [`ThreadPool::execute_singleflight`].
+    /// Expired entries are evicted by the pool whenever a new result is cached,
+    /// a job that panicked doesn't cache anything.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(4).unwrap();
+    ///     let ttl = Duration::from_secs(60);
+    ///
+    ///     let rates = pool.execute_cached("exchange-rates", ttl, || vec![1.0, 0.92, 0.79])?;
+    ///     println!("{:?}", rates.wait()?);
+    ///
+    ///     // Doesn't run the job again for a minute
+    ///     let rates = pool.execute_cached("exchange-rates", ttl, || vec![1.0, 0.92, 0.79])?;
+    ///     println!("{:?}", rates.wait()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_cached<F, T>(
+        &self,
+        key: &str,
+        ttl: Duration,
+        f: F,
+    ) -> Result<JobHandle<T>, ThreadPoolError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Clone + Send + 'static,
+    {
+        let cache_key = (TypeId::of::<T>(), String::from(key));
+
+        if let Some(value) = self.shared.cache.get::<T>(&cache_key) {
+            let (sender, receiver) = oneshot::channel();
+            let ticket = Arc::new(Ticket::default());
+            ticket.start();
+            sender.send(value);
+
+            return Ok(JobHandle::new(receiver, &self.shared, ticket));
+        }
+
+        let cache = Arc::clone(&self.shared.cache);
+        self.execute_singleflight(key, move || {
+            let value = f();
+            cache.insert(cache_key, ttl, value.clone());
+            value
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::sync::{AtomicBool, AtomicU8};
+
+/// Tell a running job it should stop early, the job has to check it on it's own
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{TaskGroup, ThreadPool};
+///
+/// let pool = ThreadPool::new(2).unwrap();
+/// let group = TaskGroup::new(&pool);
+///
+/// group
+///     .spawn_cancellable(|token| {
+///         while !token.is_cancelled() {
+///             // Process the next item
+///         }
+///     })
+///     .unwrap();
+///
+/// // Dropping the group cancel the job and wait for it
+/// drop(group);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Returns `true` once the job has been asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Job is waiting in the queue
+const QUEUED: u8 = 0;
+/// A worker started the job
+const STARTED: u8 = 1;
+/// The job was cancelled before a worker started it
+const CANCELLED: u8 = 2;
+
+/// Shared between a queued job and it's handle so the job can be tombstoned before a worker start it
+#[derive(Debug, Default)]
+pub(crate) struct Ticket {
+    state: AtomicU8,
+}
+
+impl Ticket {
+    /// Called by the worker before running the job, returns `false` if the job was cancelled
+    pub(crate) fn start(&self) -> bool {
+        self.state
+            .compare_exchange(QUEUED, STARTED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// Returns `true` if the job is still queued and will never run
+    pub(crate) fn cancel(&self) -> bool {
+        match self
+            .state
+            .compare_exchange(QUEUED, CANCELLED, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            Ok(_) => true,
+            Err(state) => state == CANCELLED,
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+use std::sync::atomic::Ordering;
+use std::sync::PoisonError;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::sizing;
+use crate::worker;
+use crate::{Message, RejectionPolicy, ThreadPool, ThreadPoolBuilder};
+
+/// Plain data version of the [`ThreadPoolBuilder`] option, meant to be loaded from the application config
+///
+/// With the `serde` feature it implement `Deserialize`, every field is optional.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{PoolConfig, ThreadPool};
+///
+/// let config = PoolConfig {
+///     workers: 8,
+///     queue_capacity: Some(1024),
+///     thread_name: Some(String::from("io")),
+///     ..PoolConfig::default()
+/// };
+///
+/// let pool = ThreadPool::from_config(&config).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PoolConfig {
+    /// Number of worker always kept alive, default to the available parallelism
+    pub workers: usize,
+    /// Maximum number of worker, default to `workers`
+    pub max_workers: Option<usize>,
+    /// How many job can wait in the queue, unbounded by default
+    pub queue_capacity: Option<usize>,
+    /// How long a worker above `workers` can stay idle before it's retired, in millisecond
+    pub keep_alive_ms: u64,
+    /// Prefix of the worker thread name
+    pub thread_name: Option<String>,
+    /// What happen to job submitted while the queue is full
+    pub rejection_policy: RejectionPolicy,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfig {
+            workers: sizing::available_workers(),
+            max_workers: None,
+            queue_capacity: None,
+            keep_alive_ms: 60_000,
+            thread_name: None,
+            rejection_policy: RejectionPolicy::default(),
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Read the config from the environment variable starting with `prefix`,
+    /// missing variable keep their default value.
+    ///
+    /// | Variable                  | Field              |
+    /// |---------------------------|--------------------|
+    /// | `PREFIX_WORKERS`          | `workers`          |
+    /// | `PREFIX_MAX_WORKERS`      | `max_workers`      |
+    /// | `PREFIX_QUEUE_CAPACITY`   | `queue_capacity`   |
+    /// | `PREFIX_KEEP_ALIVE_MS`    | `keep_alive_ms`    |
+    /// | `PREFIX_THREAD_NAME`      | `thread_name`      |
+    /// | `PREFIX_REJECTION_POLICY` | `rejection_policy` |
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::InvalidConfig`] if a variable cannot be parsed.
+    pub fn from_env(prefix: &str) -> Result<PoolConfig, ThreadPoolError> {
+        let mut config = PoolConfig::default();
+
+        if let Some(workers) = parse_env(prefix, "WORKERS")? {
+            config.workers = workers;
+        }
+        if let Some(max_workers) = parse_env(prefix, "MAX_WORKERS")? {
+            config.max_workers = Some(max_workers);
+        }
+        if let Some(capacity) = parse_env(prefix, "QUEUE_CAPACITY")? {
+            config.queue_capacity = Some(capacity);
+        }
+        if let Some(keep_alive_ms) = parse_env(prefix, "KEEP_ALIVE_MS")? {
+            config.keep_alive_ms = keep_alive_ms;
+        }
+        if let Some(prefix) = parse_env(prefix, "THREAD_NAME")? {
+            config.thread_name = Some(prefix);
+        }
+        if let Some(policy) = parse_env(prefix, "REJECTION_POLICY")? {
+            config.rejection_policy = policy;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Parse the `{prefix}_{name}` environment variable, [`None`] if it's not set
+fn parse_env<T: FromStr>(prefix: &str, name: &str) -> Result<Option<T>, ThreadPoolError> {
+    let key = format!("{prefix}_{name}");
+
+    match env::var(&key) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| ThreadPoolError::InvalidConfig { key }),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => Err(ThreadPoolError::InvalidConfig { key }),
+    }
+}
+
+impl ThreadPoolBuilder {
+    /// Creates a new [`ThreadPoolBuilder`] with the option of a [`PoolConfig`]
+    pub fn from_config(config: &PoolConfig) -> ThreadPoolBuilder {
+        let mut builder = ThreadPoolBuilder::new(config.workers)
+            .keep_alive(Duration::from_millis(config.keep_alive_ms))
+            .rejection_policy(config.rejection_policy);
+
+        if let Some(max_workers) = config.max_workers {
+            builder = builder.max_workers(max_workers);
+        }
+
+        if let Some(capacity) = config.queue_capacity {
+            builder = builder.queue_capacity(capacity);
+        }
+
+        if let Some(prefix) = &config.thread_name {
+            builder = builder.thread_name(prefix.clone());
+        }
+
+        builder
+    }
+}
+
+impl ThreadPool {
+    /// Creates a new [`ThreadPool`] from a [`PoolConfig`]
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker
+    pub fn from_config(config: &PoolConfig) -> Result<ThreadPool, ThreadPoolError> {
+        ThreadPoolBuilder::from_config(config).build()
+    }
+
+    /// Creates a new [`ThreadPool`] configured from the environment variable starting with `prefix`,
+    /// see [`PoolConfig::from_env`]
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// // Read APP_POOL_WORKERS, APP_POOL_QUEUE_CAPACITY, ...
+    /// let pool = ThreadPool::from_env("APP_POOL").unwrap();
+    /// ```
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if a variable is invalid or if cannot create thread worker
+    pub fn from_env(prefix: &str) -> Result<ThreadPool, ThreadPoolError> {
+        ThreadPool::from_config(&PoolConfig::from_env(prefix)?)
+    }
+}
+
+/// Outcome of [`ThreadPool::reconfigure`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconfigureReport {
+    /// Name of the [`PoolConfig`] field that changed and has been applied to the running pool
+    pub applied: Vec<&'static str>,
+    /// Name of the [`PoolConfig`] field that changed but cannot be applied to a running pool,
+    /// the pool has to be built again for them to take effect
+    pub requires_rebuild: Vec<&'static str>,
+    /// Worker spawned to reach the new worker count
+    pub spawned_workers: usize,
+    /// Worker told to exit to reach the new worker count, they finish their current job first
+    pub terminated_workers: usize,
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Apply a [`PoolConfig`] to the running pool
+    ///
+    /// Worker are spawned or terminated to match the new worker count,
+    /// a new thread name only apply to worker spawned afterward.
+    /// The queue capacity cannot change since the queue is already created.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{PoolConfig, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    ///
+    /// let report = pool
+    ///     .reconfigure(&PoolConfig {
+    ///         workers: 8,
+    ///         ..PoolConfig::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// println!("spawned {} worker", report.spawned_workers);
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if cannot create thread worker
+    /// or if the communication channel between worker thread and main thread is closed,
+    /// the setting are already applied when it happen.
+    pub fn reconfigure(&self, config: &PoolConfig) -> Result<ReconfigureReport, ThreadPoolError> {
+        let shared = &self.shared;
+        let mut report = ReconfigureReport::default();
+
+        let max_workers = config.max_workers.unwrap_or(config.workers);
+        if config.workers != shared.core_workers() {
+            report.applied.push("workers");
+        }
+        if max_workers.max(config.workers) != shared.max_workers() {
+            report.applied.push("max_workers");
+        }
+        shared.set_workers(config.workers, max_workers);
+
+        let keep_alive = Duration::from_millis(config.keep_alive_ms);
+        if keep_alive != shared.keep_alive() {
+            shared.set_keep_alive(keep_alive);
+            report.applied.push("keep_alive_ms");
+        }
+
+        if config.rejection_policy != shared.rejection() {
+            shared.set_rejection(config.rejection_policy);
+            report.applied.push("rejection_policy");
+        }
+
+        {
+            let mut thread_name = shared
+                .thread_name
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if config.thread_name != *thread_name {
+                thread_name.clone_from(&config.thread_name);
+                report.applied.push("thread_name");
+            }
+        }
+
+        if config.queue_capacity != shared.queue.capacity() {
+            report.requires_rebuild.push("queue_capacity");
+        }
+
+        let live = shared.live_workers.load(Ordering::SeqCst);
+        if !shared.lazy && live < config.workers {
+            for _ in live..config.workers {
+                worker::spawn(shared).map_err(|source| ThreadPoolError::SpawnFailed {
+                    index: shared.spawned_workers.load(Ordering::SeqCst),
+                    source,
+                })?;
+                report.spawned_workers += 1;
+            }
+        }
+
+        // Worker above the core count retire on their own once idle if the pool is elastic
+        let target = if shared.is_elastic() {
+            shared.max_workers()
+        } else {
+            shared.core_workers()
+        };
+        // The highest index retire so every index below the new count keep it's worker
+        let occupied = shared.inboxes.occupied();
+        for &index in occupied.iter().skip(target) {
+            let inbox = shared.inboxes.get(index);
+            inbox.request_retire();
+            if inbox.is_idle() {
+                shared
+                    .queue
+                    .send(Message::Wake(index))
+                    .map_err(|_| ThreadPoolError::QueueClosed)?;
+            }
+            report.terminated_workers += 1;
+        }
+
+        Ok(report)
+    }
+}
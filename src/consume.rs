@@ -0,0 +1,166 @@
+use std::sync::{mpsc, Arc, PoisonError};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::sync::Mutex;
+use crate::ThreadPool;
+
+/// Receiving end of a channel that can be drained by [`ThreadPool::consume`]
+///
+/// Implemented for [`std::sync::mpsc::Receiver`] and, with the `crossbeam` or `flume` feature,
+/// for [`crossbeam_channel::Receiver`] and [`flume::Receiver`].
+pub trait Source: Send + 'static {
+    /// Message received from the channel
+    type Item: Send + 'static;
+    /// Receiver shared between every consuming worker
+    type Shared: Send + Sync + 'static;
+
+    /// Turn the receiver into something every worker can receive from
+    fn share(self) -> Self::Shared;
+
+    /// Block until the next message arrive, returning [`None`] once the channel is closed
+    fn recv(shared: &Self::Shared) -> Option<Self::Item>;
+}
+
+impl<T: Send + 'static> Source for mpsc::Receiver<T> {
+    type Item = T;
+    type Shared = Mutex<mpsc::Receiver<T>>;
+
+    fn share(self) -> Self::Shared {
+        Mutex::new(self)
+    }
+
+    fn recv(shared: &Self::Shared) -> Option<T> {
+        shared
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recv()
+            .ok()
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl<T: Send + 'static> Source for crossbeam_channel::Receiver<T> {
+    type Item = T;
+    type Shared = crossbeam_channel::Receiver<T>;
+
+    fn share(self) -> Self::Shared {
+        self
+    }
+
+    fn recv(shared: &Self::Shared) -> Option<T> {
+        shared.recv().ok()
+    }
+}
+
+#[cfg(feature = "flume")]
+impl<T: Send + 'static> Source for flume::Receiver<T> {
+    type Item = T;
+    type Shared = flume::Receiver<T>;
+
+    fn share(self) -> Self::Shared {
+        self
+    }
+
+    fn recv(shared: &Self::Shared) -> Option<T> {
+        shared.recv().ok()
+    }
+}
+
+/// Handle returned by [`ThreadPool::consume`] to wait for the channel to be drained
+#[derive(Debug)]
+pub struct Consumer {
+    handles: Vec<JobHandle<usize>>,
+}
+
+impl Consumer {
+    /// Check if every consuming worker has stopped
+    pub fn is_finished(&self) -> bool {
+        self.handles.iter().all(JobHandle::is_finished)
+    }
+
+    /// Block until the channel is closed and drained, returning how many message were handled
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::JobPanicked`] if the handler panicked,
+    /// the remaining message are still drained by the other worker.
+    pub fn wait(self) -> Result<usize, ThreadPoolError> {
+        let mut handled = 0;
+        let mut error = None;
+
+        for handle in self.handles {
+            match handle.wait() {
+                Ok(count) => handled += count,
+                Err(err) => error = error.or(Some(err)),
+            }
+        }
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(handled),
+        }
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Occupy every worker with draining `receiver`, calling `handler` on each message until the channel is closed
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(4).unwrap();
+    ///     let (sender, receiver) = channel();
+    ///
+    ///     let consumer = pool.consume(receiver, |line: String| println!("{line}"))?;
+    ///     for i in 0..100 {
+    ///         sender.send(format!("line {i}")).unwrap();
+    ///     }
+    ///     drop(sender);
+    ///
+    ///     assert_eq!(consumer.wait()?, 100);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Every worker stay busy until the channel close, so job executed in the meantime are
+    /// only picked up by worker spawned on demand. On a pool without worker the channel is
+    /// drained on the calling thread.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn consume<S, F>(&self, receiver: S, handler: F) -> Result<Consumer, ThreadPoolError>
+    where
+        S: Source,
+        F: Fn(S::Item) + Send + Sync + 'static,
+    {
+        let shared = Arc::new(receiver.share());
+        let handler = Arc::new(handler);
+
+        let handles = (0..self.shared.max_workers().max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                let handler = Arc::clone(&handler);
+                self.spawn(move || {
+                    let mut handled = 0;
+                    while let Some(item) = S::recv(&shared) {
+                        handler(item);
+                        handled += 1;
+                    }
+                    handled
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Consumer { handles })
+    }
+}
@@ -0,0 +1,128 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use crate::error::ThreadPoolError;
+use crate::Job;
+
+thread_local! {
+    /// Job spawned from within a job, [`None`] outside of a worker thread
+    static LOCAL: RefCell<Option<Vec<Job>>> = const { RefCell::new(None) };
+    /// Pool of the current worker thread, used to help it while waiting for a job of the same pool
+    static POOL: RefCell<Option<CurrentWorker>> = const { RefCell::new(None) };
+}
+
+/// Pool that can run it's queued job on a thread that is waiting for another job
+pub(crate) trait Helper: Send + Sync {
+    /// Run one queued job on the calling thread, return `false` if there are nothing to run
+    ///
+    /// `worker` is the index of the calling worker, [`None`] if it's not a worker of this pool.
+    fn help(&self, worker: Option<usize>) -> bool;
+
+    /// Submit a job to the pool without knowing it's backend
+    fn submit_job(self: Arc<Self>, job: Job) -> Result<(), ThreadPoolError>;
+}
+
+/// Pool of the current thread with it's worker index, the index is [`None`] while running a job inline
+pub(crate) type CurrentWorker = (Arc<dyn Helper>, Option<usize>);
+
+/// Address of the pool shared state, used to tell pool apart
+pub(crate) fn pool_id<T: ?Sized>(pool: &Arc<T>) -> usize {
+    Arc::as_ptr(pool) as *const () as usize
+}
+
+/// Access to the worker running the current job
+///
+/// Every function is associated and only work when called from a job running on a [`ThreadPool`](crate::ThreadPool).
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{JobContext, ThreadPool};
+///
+/// let pool = ThreadPool::new(2).unwrap();
+///
+/// pool.execute(|| {
+///     for chunk in 0..4 {
+///         JobContext::spawn_local(move || println!("processing chunk {chunk}")).unwrap();
+///     }
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct JobContext {
+    _private: (),
+}
+
+impl JobContext {
+    /// Push a job onto the current worker local queue, the worker run it's local job
+    /// (most recent first) before taking another job from the pool queue
+    /// so recursive task decomposition stay on the same thread.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if it's not called from a worker thread.
+    pub fn spawn_local<F>(job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        LOCAL.with_borrow_mut(|local| match local {
+            Some(local) => {
+                local.push(Job::new(job));
+                Ok(())
+            }
+            None => Err(ThreadPoolError::NotInWorker),
+        })
+    }
+
+    /// Returns `true` if called from a worker thread
+    pub fn in_worker() -> bool {
+        LOCAL.with_borrow(Option::is_some)
+    }
+}
+
+/// Mark the current thread as the `index` worker of `pool` so it can have local job
+pub(crate) fn enter_worker(pool: Arc<dyn Helper>, index: usize) {
+    LOCAL.with_borrow_mut(|local| *local = Some(Vec::new()));
+    POOL.with_borrow_mut(|current| *current = Some((pool, Some(index))));
+}
+
+/// Context the caller thread had before it started running a job inline, restored on drop
+pub(crate) struct InlineContext {
+    local: Option<Vec<Job>>,
+    pool: Option<CurrentWorker>,
+}
+
+/// Let the caller thread act as a worker of `pool` while it run a job inline
+pub(crate) fn enter_inline(pool: Arc<dyn Helper>) -> InlineContext {
+    InlineContext {
+        local: LOCAL.replace(Some(Vec::new())),
+        pool: POOL.replace(Some((pool, None))),
+    }
+}
+
+impl Drop for InlineContext {
+    fn drop(&mut self) {
+        LOCAL.set(self.local.take());
+        POOL.set(self.pool.take());
+    }
+}
+
+/// Pool and index of the current worker thread if it belong to the pool with the passed id
+pub(crate) fn worker_of(pool: usize) -> Option<CurrentWorker> {
+    POOL.with_borrow(|current| {
+        current
+            .as_ref()
+            .filter(|(current, _)| pool_id(current) == pool)
+            .cloned()
+    })
+}
+
+/// Pool and index of the current worker thread, whatever pool it belong to
+pub(crate) fn current_worker() -> Option<CurrentWorker> {
+    POOL.with_borrow(Clone::clone)
+}
+
+/// Take the most recent local job of the current worker
+pub(crate) fn pop_local() -> Option<Job> {
+    LOCAL.with_borrow_mut(|local| local.as_mut()?.pop())
+}
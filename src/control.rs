@@ -0,0 +1,161 @@
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::hook::Hook;
+use crate::{Job, Message, ThreadPool};
+
+/// Handle custom command sent to the worker with [`ThreadPool::control`] and [`ThreadPool::control_any`]
+///
+/// Register it with [`ThreadPoolBuilder::control_handler`](crate::ThreadPoolBuilder::control_handler),
+/// a pool can have one handler per command type.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::cell::RefCell;
+///
+/// use unknownrori_simple_thread_pool::{ControlHandler, ThreadPoolBuilder};
+///
+/// thread_local! {
+///     static BUFFER: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+/// }
+///
+/// enum Command {
+///     Flush,
+///     DumpState,
+/// }
+///
+/// struct Handler;
+///
+/// impl ControlHandler for Handler {
+///     type Command = Command;
+///
+///     fn handle(&self, worker: usize, command: &Command) {
+///         match command {
+///             Command::Flush => BUFFER.with_borrow_mut(|buffer| buffer.clear()),
+///             Command::DumpState => {
+///                 BUFFER.with_borrow(|buffer| println!("worker {worker}: {buffer:?}"))
+///             }
+///         }
+///     }
+/// }
+///
+/// let pool = ThreadPoolBuilder::new(4)
+///     .control_handler(Handler)
+///     .build()
+///     .unwrap();
+///
+/// pool.control(Command::Flush).unwrap();
+/// ```
+pub trait ControlHandler: Send + Sync + 'static {
+    /// Command understood by this handler
+    type Command: Send + Sync + 'static;
+
+    /// Handle a command on the worker with this index
+    fn handle(&self, worker: usize, command: &Self::Command);
+}
+
+/// Type erased [`ControlHandler`]
+pub(crate) trait Control: Send + Sync {
+    fn command(&self) -> TypeId;
+
+    fn handle(&self, worker: usize, command: &(dyn Any + Send + Sync));
+}
+
+impl<H: ControlHandler> Control for H {
+    fn command(&self) -> TypeId {
+        TypeId::of::<H::Command>()
+    }
+
+    fn handle(&self, worker: usize, command: &(dyn Any + Send + Sync)) {
+        if let Some(command) = command.downcast_ref::<H::Command>() {
+            ControlHandler::handle(self, worker, command);
+        }
+    }
+}
+
+/// Run `command` through every handler that understand it
+pub(crate) fn dispatch(
+    controls: &[Hook<dyn Control>],
+    worker: usize,
+    command: &(dyn Any + Send + Sync),
+) {
+    for control in controls {
+        control.handle(worker, command);
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Send `command` to every running worker, each of them pass it to the [`ControlHandler`]
+    /// once it finished the job it's currently running
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::InvalidConfig`] if there are no handler for
+    /// the command type, or an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn control<C: Send + Sync + 'static>(&self, command: C) -> Result<(), ThreadPoolError> {
+        let command: Arc<dyn Any + Send + Sync> = self.control_command(command)?;
+
+        if self.shared.is_inline() {
+            let controls = self.shared.controls.clone();
+            return self
+                .shared
+                .submit(Job::new(move || dispatch(&controls, 0, &*command)));
+        }
+
+        for worker in self.shared.inboxes.occupied() {
+            let controls = self.shared.controls.clone();
+            let command = Arc::clone(&command);
+            self.shared.submit_to(
+                worker,
+                Job::new(move || dispatch(&controls, worker, &*command)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Send `command` to the first worker that receive it from the queue
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::InvalidConfig`] if there are no handler for
+    /// the command type, or an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn control_any<C: Send + Sync + 'static>(&self, command: C) -> Result<(), ThreadPoolError> {
+        let command = self.control_command(command)?;
+
+        if self.shared.is_inline() {
+            dispatch(&self.shared.controls, 0, &*command);
+            return Ok(());
+        }
+
+        self.shared.spawn_on_demand()?;
+        self.shared
+            .queue
+            .send(Message::Control(command))
+            .map_err(|_| ThreadPoolError::QueueClosed)
+    }
+
+    fn control_command<C: Send + Sync + 'static>(
+        &self,
+        command: C,
+    ) -> Result<Arc<dyn Any + Send + Sync>, ThreadPoolError> {
+        if !self
+            .shared
+            .controls
+            .iter()
+            .any(|control| control.command() == TypeId::of::<C>())
+        {
+            return Err(ThreadPoolError::InvalidConfig {
+                key: "control_handler".to_string(),
+            });
+        }
+
+        self.shared.check_accepting()?;
+        Ok(Arc::new(command))
+    }
+}
@@ -0,0 +1,59 @@
+//! CPU time consumed by the current thread, enabled with the `cputime` feature
+//!
+//! It use `clock_gettime(CLOCK_THREAD_CPUTIME_ID)` on unix and `GetThreadTimes` on Windows.
+
+use std::time::Duration;
+
+/// CPU time consumed by the current thread, [`None`] if it cannot be read on this platform
+#[cfg(unix)]
+pub(crate) fn current_thread() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    // SAFETY: `time` is a valid timespec that outlive the call
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) } != 0 {
+        return None;
+    }
+
+    Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+/// CPU time consumed by the current thread, [`None`] if it cannot be read on this platform
+#[cfg(windows)]
+pub(crate) fn current_thread() -> Option<Duration> {
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, GetThreadTimes};
+
+    let empty = || FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    let (mut creation, mut exit, mut kernel, mut user) = (empty(), empty(), empty(), empty());
+
+    // SAFETY: the pseudo handle of the current thread is always valid and every FILETIME outlive the call
+    if unsafe {
+        GetThreadTimes(
+            GetCurrentThread(),
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        )
+    } == 0
+    {
+        return None;
+    }
+
+    // FILETIME count 100 nanosecond interval
+    let ticks =
+        |time: FILETIME| (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+    Some(Duration::from_nanos((ticks(kernel) + ticks(user)) * 100))
+}
+
+/// CPU time consumed by the current thread, not supported on this platform
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn current_thread() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::shared::Shared;
+use crate::sync::Mutex;
+use crate::{Job, ThreadPool};
+
+/// Latest job of every key submitted with [`ThreadPool::execute_debounced`] that hasn't run yet
+#[derive(Debug, Default)]
+pub(crate) struct Debounces {
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+/// The job is only wrapped in a [`Job`] once it's submitted, so the window doesn't count as queue wait
+type Debounced = Box<dyn FnOnce() + Send>;
+
+struct Pending {
+    deadline: Instant,
+    job: Debounced,
+}
+
+impl fmt::Debug for Pending {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pending")
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Debounces {
+    /// Replace the pending job of `key`, returns `false` if there was none and a timer has to be armed
+    fn replace(&self, key: &str, deadline: Instant, job: Debounced) -> bool {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match pending.get_mut(key) {
+            Some(entry) => {
+                *entry = Pending { deadline, job };
+                true
+            }
+            None => {
+                pending.insert(String::from(key), Pending { deadline, job });
+                false
+            }
+        }
+    }
+
+    /// Take the pending job of `key` once it's deadline is reached, otherwise the deadline it moved to
+    fn take_due(&self, key: &str) -> Result<Option<Debounced>, Instant> {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(entry) = pending.get(key) else {
+            return Ok(None);
+        };
+
+        if entry.deadline > Instant::now() {
+            return Err(entry.deadline);
+        }
+
+        Ok(pending.remove(key).map(|entry| entry.job))
+    }
+
+    /// Take every pending job without waiting for their window
+    fn take_all(&self) -> Vec<Debounced> {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+
+        pending.drain().map(|(_, entry)| entry.job).collect()
+    }
+}
+
+impl<B: QueueBackend> Shared<B> {
+    /// Queue every pending debounced job right away, used when the pool is about to stop
+    /// so they are not lost with the timer
+    pub(crate) fn flush_debounced(self: &Arc<Self>) {
+        for job in self.debounces.take_all() {
+            // The job was accepted when it was debounced, it's queued even if the pool is draining
+            drop(self.enqueue(self.propagate(Job::new(job)), None));
+        }
+    }
+}
+
+/// Schedule the pending job of `key` on the watchdog, nothing is scheduled once the pool is gone
+fn arm<B: QueueBackend>(shared: Weak<Shared<B>>, key: String, deadline: Instant) {
+    let Some(watchdog) = shared.upgrade().map(|shared| Arc::clone(&shared.watchdog)) else {
+        return;
+    };
+
+    watchdog.schedule(deadline, move || {
+        let Some(pool) = shared.upgrade() else {
+            return;
+        };
+
+        match pool.debounces.take_due(&key) {
+            // It's submitted like any other job, a job that is refused is dropped
+            Ok(Some(job)) => drop(pool.submit(Job::new(job))),
+            Ok(None) => {}
+            // A submission moved the deadline while the timer was waiting
+            Err(deadline) => {
+                drop(pool);
+                arm(shared, key, deadline);
+            }
+        }
+    });
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a job once `window` has elapsed without another submission of the same `key`,
+    /// every submission within the window replace the previous job so only the latest one run
+    ///
+    /// The window is tracked by the pool timer thread, no worker is held while waiting.
+    /// Once the window is over the job is queued like [`ThreadPool::execute`], the window
+    /// doesn't count as queue wait.
+    ///
+    /// A job still waiting for it's window is queued right away when the pool is dropped with
+    /// [`DropPolicy::WaitForQueued`](crate::DropPolicy::WaitForQueued) or shut down with [`ThreadPool::shutdown`],
+    /// it's dropped with the pool under the other [`DropPolicy`](crate::DropPolicy).
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    ///
+    /// // A burst of file change only rebuild once, 100ms after the last one
+    /// for file in ["lib.rs", "main.rs", "lib.rs"] {
+    ///     pool.execute_debounced("rebuild", Duration::from_millis(100), move || {
+    ///         println!("rebuilding after {file} changed");
+    ///     })
+    ///     .unwrap();
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the pool doesn't accept new job.
+    pub fn execute_debounced<F>(
+        &self,
+        key: &str,
+        window: Duration,
+        job: F,
+    ) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.check_accepting()?;
+
+        let deadline = Instant::now() + window;
+        if !self.shared.debounces.replace(key, deadline, Box::new(job)) {
+            arm(Arc::downgrade(&self.shared), String::from(key), deadline);
+        }
+
+        Ok(())
+    }
+}
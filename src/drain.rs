@@ -0,0 +1,120 @@
+use std::sync::atomic::Ordering;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use crate::backend::QueueBackend;
+use crate::sync::{AtomicBool, Condvar, Mutex};
+use crate::ThreadPool;
+
+/// Drain mode of the pool, once started new job are refused while the queued job keep running
+#[derive(Debug, Default)]
+pub(crate) struct Drain {
+    draining: AtomicBool,
+    lock: Mutex<()>,
+    /// Notified every time a worker become idle while draining
+    idle: Condvar,
+}
+
+impl Drain {
+    pub(crate) fn start(&self) {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        self.draining.store(true, Ordering::SeqCst);
+        self.idle.notify_all();
+    }
+
+    pub(crate) fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Called by a worker that ran out of job
+    pub(crate) fn notify(&self) {
+        if self.is_draining() {
+            let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+            self.idle.notify_all();
+        }
+    }
+
+    /// Block until the drain has started and `is_drained` return `true`
+    pub(crate) fn wait(&self, is_drained: impl Fn() -> bool) {
+        let mut lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while !(self.is_draining() && is_drained()) {
+            // The timeout cover the worker that become idle between the check and the wait
+            lock = self
+                .idle
+                .wait_timeout(lock, Duration::from_millis(10))
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Put the pool in drain mode, new job are refused with [`ThreadPoolError::Draining`](crate::error::ThreadPoolError::Draining)
+    /// while the worker finish the job that are already queued
+    pub fn drain(&self) {
+        self.shared.start_drain();
+    }
+
+    /// Returns `true` if the pool is in drain mode
+    pub fn is_draining(&self) -> bool {
+        self.shared.drain.is_draining()
+    }
+
+    /// Block until the pool is put in drain mode and every queued job has finished
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// pool.execute(|| println!("last job")).unwrap();
+    ///
+    /// pool.drain();
+    /// pool.wait_for_drain();
+    /// ```
+    pub fn wait_for_drain(&self) {
+        self.shared.drain.wait(|| self.shared.is_drained());
+    }
+
+    /// Put the pool in drain mode when the process receive one of the `signals`,
+    /// pair it with [`ThreadPool::wait_for_drain`] to shut down gracefully.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::signal_hook::consts::{SIGINT, SIGTERM};
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// pool.drain_on_signal(&[SIGTERM, SIGINT]).unwrap();
+    ///
+    /// // Serve request until the process is told to stop
+    /// pool.wait_for_drain();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the signal handler cannot be installed,
+    /// for example for a forbidden signal like `SIGKILL`.
+    #[cfg(all(feature = "signal", unix))]
+    pub fn drain_on_signal(&self, signals: &[i32]) -> std::io::Result<()> {
+        use std::sync::Arc;
+
+        let mut signals = signal_hook::iterator::Signals::new(signals)?;
+        self.shared.add_signal_handle(signals.handle());
+
+        let shared = Arc::downgrade(&self.shared);
+        std::thread::Builder::new().spawn(move || {
+            // The handle is closed when the pool is dropped, which end the iteration
+            if signals.forever().next().is_some() {
+                if let Some(shared) = shared.upgrade() {
+                    shared.start_drain();
+                }
+            }
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::ThreadPoolError;
+use crate::sizing::{self, WorkloadProfile, IO_WORKERS_PER_CPU};
+use crate::{ThreadPool, ThreadPoolBuilder};
+
+/// Two [`ThreadPool`] behind one type, one for blocking IO job and one for CPU bound job
+///
+/// The compute pool has one worker per CPU, the IO pool keep the same number of worker
+/// but grow up to eight worker per CPU while job are waiting on IO,
+/// so a burst of blocking call never starve the compute job.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::DualPool;
+///
+/// let pool = DualPool::new().unwrap();
+///
+/// pool.execute_io(|| println!("reading file")).unwrap();
+/// pool.execute_cpu(|| println!("hashing file")).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct DualPool {
+    io: ThreadPool,
+    cpu: ThreadPool,
+}
+
+impl DualPool {
+    /// Creates a [`DualPool`] sized from the CPU the process is allowed to use, see [`recommended_workers`](crate::recommended_workers)
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker
+    pub fn new() -> Result<DualPool, ThreadPoolError> {
+        let cpus = sizing::recommended_workers(WorkloadProfile::Cpu);
+
+        DualPool::with_workers(cpus * IO_WORKERS_PER_CPU, cpus)
+    }
+
+    /// Creates a [`DualPool`] where the IO pool can grow up to `io` worker and the compute pool has `cpu` worker
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker
+    pub fn with_workers(io: usize, cpu: usize) -> Result<DualPool, ThreadPoolError> {
+        Ok(DualPool {
+            io: ThreadPoolBuilder::new(cpu.min(io))
+                .max_workers(io)
+                .thread_name("io")
+                .build()?,
+            cpu: ThreadPoolBuilder::new(cpu).thread_name("cpu").build()?,
+        })
+    }
+
+    /// Execute a job that spend most of it's time blocked on IO
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_io<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.io.execute(job)
+    }
+
+    /// Execute a CPU bound job
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_cpu<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.cpu.execute(job)
+    }
+
+    /// The pool running IO job
+    pub fn io(&self) -> &ThreadPool {
+        &self.io
+    }
+
+    /// The pool running CPU bound job
+    pub fn cpu(&self) -> &ThreadPool {
+        &self.cpu
+    }
+}
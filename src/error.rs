@@ -1,21 +1,73 @@
+use std::io;
+
+/// Error returned by the [`ThreadPool`](crate::ThreadPool)
 #[derive(Debug)]
-pub struct FailedToSendJob;
+#[non_exhaustive]
+pub enum ThreadPoolError {
+    /// Thread pool failed to create worker thread, `index` is the worker that failed to spawn
+    SpawnFailed { index: usize, source: io::Error },
+    /// The queue between thread pool and it's worker has been closed
+    QueueClosed,
+    /// The queue between thread pool and it's worker is full
+    QueueFull,
+    /// The operation did not complete in the given time
+    Timeout,
+    /// The operation can only be done from a worker thread
+    NotInWorker,
+    /// There are no NUMA node with this id in the pool
+    NoSuchNode { node: usize },
+    /// The job panicked before producing it's result
+    JobPanicked,
+    /// There are no worker with this index in the pool
+    NoSuchWorker { worker: usize },
+    /// The configuration value of `key` is invalid
+    InvalidConfig { key: String },
+    /// The pool is draining and doesn't accept new job
+    Draining,
+    /// The job was cancelled before it started
+    Cancelled,
+    /// There are no named queue with this name in the pool
+    NoSuchQueue { name: String },
+    /// There are no permit set with this name in the pool
+    NoSuchPermit { name: String },
+    /// The task graph has a duplicate task, an unknown dependency or a cycle involving `task`
+    InvalidGraph { task: String },
+    /// The circuit breaker of the job name is open after too many failure
+    CircuitOpen { name: String },
+    /// The [`AdmissionController`](crate::AdmissionController) rejected the job because the pool is overloaded
+    Overloaded,
+}
 
-impl core::fmt::Display for FailedToSendJob {
+impl core::fmt::Display for ThreadPoolError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Thread pool failed to send a job to it's worker! the channel connection has been abruptly closed!"))?;
-
-        Ok(())
+        match self {
+            ThreadPoolError::SpawnFailed { index, .. } => f.write_fmt(format_args!(
+                "Thread pool failed to create worker thread with index {index}!"
+            )),
+            ThreadPoolError::QueueClosed => f.write_fmt(format_args!("Thread pool failed to send a job to it's worker! the channel connection has been abruptly closed!")),
+            ThreadPoolError::QueueFull => f.write_fmt(format_args!("Thread pool failed to send a job to it's worker! the queue is full!")),
+            ThreadPoolError::Timeout => f.write_fmt(format_args!("Thread pool operation timed out!")),
+            ThreadPoolError::NotInWorker => f.write_fmt(format_args!("Thread pool operation can only be done from a worker thread!")),
+            ThreadPoolError::NoSuchNode { node } => f.write_fmt(format_args!("Thread pool doesn't have NUMA node {node}!")),
+            ThreadPoolError::JobPanicked => f.write_fmt(format_args!("Thread pool job panicked before producing it's result!")),
+            ThreadPoolError::NoSuchWorker { worker } => f.write_fmt(format_args!("Thread pool doesn't have worker {worker}!")),
+            ThreadPoolError::InvalidConfig { key } => f.write_fmt(format_args!("Thread pool configuration `{key}` is invalid!")),
+            ThreadPoolError::Draining => f.write_fmt(format_args!("Thread pool is draining and doesn't accept new job!")),
+            ThreadPoolError::Cancelled => f.write_fmt(format_args!("Thread pool job was cancelled before it started!")),
+            ThreadPoolError::NoSuchQueue { name } => f.write_fmt(format_args!("Thread pool doesn't have queue `{name}`!")),
+            ThreadPoolError::NoSuchPermit { name } => f.write_fmt(format_args!("Thread pool doesn't have permit `{name}`!")),
+            ThreadPoolError::InvalidGraph { task } => f.write_fmt(format_args!("Thread pool task graph is invalid at task `{task}`!")),
+            ThreadPoolError::CircuitOpen { name } => f.write_fmt(format_args!("Thread pool circuit breaker for `{name}` is open!")),
+            ThreadPoolError::Overloaded => f.write_fmt(format_args!("Thread pool is overloaded and rejected the job!")),
+        }
     }
 }
 
-#[derive(Debug)]
-pub struct FailedToSpawnThread;
-
-impl core::fmt::Display for FailedToSpawnThread {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Thread pool failed to create worker thread!"))?;
-
-        Ok(())
+impl std::error::Error for ThreadPoolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ThreadPoolError::SpawnFailed { source, .. } => Some(source),
+            _ => None,
+        }
     }
 }
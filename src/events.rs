@@ -0,0 +1,178 @@
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+use crate::backend::QueueBackend;
+use crate::shutdown::ShutdownPhase;
+use crate::sync::{AtomicBool, Mutex};
+use crate::{Job, ThreadPool};
+
+/// Something that happened in the pool, see [`ThreadPool::subscribe`]
+///
+/// `worker` is the index of the worker running the job, [`None`] when the job run on the caller thread
+/// like a pool without worker or a thread helping while it wait. `job` is the name given to
+/// [`ThreadPool::execute_named`]. Event sent from different thread may arrive out of order,
+/// a job can be reported as started right before it's reported as queued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolEvent {
+    /// A job has been handed to the pool
+    JobQueued { job: Option<String> },
+    /// A job started running
+    JobStarted {
+        worker: Option<usize>,
+        job: Option<String>,
+    },
+    /// A job returned
+    JobFinished {
+        worker: Option<usize>,
+        job: Option<String>,
+        elapsed: Duration,
+    },
+    /// A job panicked, it's worker die right after
+    JobPanicked {
+        worker: Option<usize>,
+        job: Option<String>,
+    },
+    /// A worker thread has been spawned
+    WorkerSpawned { worker: usize },
+    /// A worker thread has exited, retired or died from a panic
+    WorkerExited { worker: usize },
+    /// A job has been waiting longer than the threshold of
+    /// [`ThreadPoolBuilder::on_starvation`](crate::ThreadPoolBuilder::on_starvation)
+    JobStarved {
+        job: Option<String>,
+        waited: Duration,
+    },
+    /// A job has been submitted while the bounded queue is full
+    QueueSaturated { len: usize, capacity: usize },
+    /// [`ThreadPool::shutdown`] has reached a new phase
+    Shutdown { phase: ShutdownPhase },
+}
+
+/// Every subscriber of the pool event
+#[derive(Debug, Default)]
+pub(crate) struct Events {
+    subscribers: Mutex<Vec<Sender<PoolEvent>>>,
+    /// There is at least one subscriber, so the event is not built for nothing
+    active: AtomicBool,
+}
+
+impl Events {
+    pub(crate) fn subscribe(&self) -> Receiver<PoolEvent> {
+        let (sender, receiver) = channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(sender);
+        self.active.store(true, Ordering::SeqCst);
+
+        receiver
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Send the event to every subscriber, it's only built when there are one
+    pub(crate) fn emit(&self, event: impl FnOnce() -> PoolEvent) {
+        if self.is_active() {
+            self.send(Some(event()));
+        }
+    }
+
+    /// Send an event built ahead of time, dropping the subscriber that are gone
+    pub(crate) fn send(&self, event: Option<PoolEvent>) {
+        let Some(event) = event else {
+            return;
+        };
+
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        self.active.store(!subscribers.is_empty(), Ordering::SeqCst);
+    }
+
+    /// [`PoolEvent::JobQueued`] for the job, built before the job is handed over
+    pub(crate) fn job_queued(&self, job: &Job) -> Option<PoolEvent> {
+        self.is_active().then(|| PoolEvent::JobQueued {
+            job: job.name().map(String::from),
+        })
+    }
+
+    /// Report the job as started, the returned guard report it as finished or panicked once dropped
+    pub(crate) fn job_started(&self, worker: Option<usize>, job: &Job) -> Option<Running<'_>> {
+        if !self.is_active() {
+            return None;
+        }
+
+        let job = job.name().map(String::from);
+        self.send(Some(PoolEvent::JobStarted {
+            worker,
+            job: job.clone(),
+        }));
+
+        Some(Running {
+            events: self,
+            worker,
+            job,
+            start: Instant::now(),
+        })
+    }
+}
+
+/// Report a running job as finished, or as panicked if it's dropped while unwinding
+pub(crate) struct Running<'a> {
+    events: &'a Events,
+    worker: Option<usize>,
+    job: Option<String>,
+    start: Instant,
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        let worker = self.worker;
+        let job = self.job.take();
+
+        self.events.send(Some(if std::thread::panicking() {
+            PoolEvent::JobPanicked { worker, job }
+        } else {
+            PoolEvent::JobFinished {
+                worker,
+                job,
+                elapsed: self.start.elapsed(),
+            }
+        }));
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Subscribe to the event of the pool, so it can be observed without polling
+    ///
+    /// Every subscriber receive every event from now on, the event pile up in the channel
+    /// until they are received. Dropping the receiver unsubscribe it.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::thread;
+    ///
+    /// use unknownrori_simple_thread_pool::{PoolEvent, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    /// let events = pool.subscribe();
+    ///
+    /// thread::spawn(move || {
+    ///     for event in events {
+    ///         if let PoolEvent::JobPanicked { worker, job } = event {
+    ///             eprintln!("{job:?} panicked on worker {worker:?}");
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe(&self) -> Receiver<PoolEvent> {
+        self.shared.events.subscribe()
+    }
+}
@@ -0,0 +1,39 @@
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::ThreadPool;
+
+/// Something that can run job, implemented by [`ThreadPool`] and [`MockPool`](crate::MockPool)
+///
+/// Code that only submit job can be generic over it so it can be tested without real thread.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, Executor, MockPool};
+///
+/// fn schedule_cleanup(executor: &impl Executor) -> Result<(), ThreadPoolError> {
+///     executor.execute(|| println!("cleaning up"))
+/// }
+///
+/// let pool = MockPool::inline();
+/// schedule_cleanup(&pool).unwrap();
+/// ```
+pub trait Executor {
+    /// Run `job` at some point, see [`ThreadPool::execute`]
+    ///
+    /// ## Errors
+    ///
+    /// Return an [`Err`] if the job cannot be accepted.
+    fn execute<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static;
+}
+
+impl<B: QueueBackend> Executor for ThreadPool<B> {
+    fn execute<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        ThreadPool::execute(self, job)
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::ThreadPool;
+
+/// Why a job executed with [`ThreadPool::execute_fallible`] didn't produce a value,
+/// see [`JobHandle::wait_fallible`]
+#[derive(Debug)]
+pub enum JobError<E> {
+    /// The job returned an [`Err`]
+    Failed(E),
+    /// The job panicked before returning
+    Panicked,
+    /// The job didn't run, it was cancelled or discarded by the pool
+    Pool(ThreadPoolError),
+}
+
+impl<E: fmt::Display> fmt::Display for JobError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobError::Failed(err) => f.write_fmt(format_args!("Thread pool job failed: {err}")),
+            JobError::Panicked => ThreadPoolError::JobPanicked.fmt(f),
+            JobError::Pool(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for JobError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JobError::Failed(err) => Some(err),
+            JobError::Panicked => None,
+            JobError::Pool(err) => Some(err),
+        }
+    }
+}
+
+impl<T, E> JobHandle<Result<T, E>> {
+    /// Block until the job has finished like [`JobHandle::wait`], flattening the error returned
+    /// by the job with the one of the pool so the three outcome can be matched at once
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`JobError::Failed`] if the job returned an [`Err`],
+    /// [`JobError::Panicked`] if it panicked and [`JobError::Pool`] if it never ran.
+    pub fn wait_fallible(self) -> Result<T, JobError<E>> {
+        match self.wait() {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(err)) => Err(JobError::Failed(err)),
+            Err(ThreadPoolError::JobPanicked) => Err(JobError::Panicked),
+            Err(err) => Err(JobError::Pool(err)),
+        }
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a fallible job to worker thread and return a [`JobHandle`] to it's result,
+    /// [`JobHandle::wait_fallible`] tell apart a job that succeeded, failed or panicked
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{JobError, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    ///
+    /// let config = pool
+    ///     .execute_fallible(|| std::fs::read_to_string("config.toml"))
+    ///     .unwrap();
+    ///
+    /// match config.wait_fallible() {
+    ///     Ok(config) => println!("{config}"),
+    ///     Err(JobError::Failed(err)) => eprintln!("cannot read the config: {err}"),
+    ///     Err(JobError::Panicked) => eprintln!("reading the config panicked"),
+    ///     Err(err) => eprintln!("{err}"),
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_fallible<F, T, E>(
+        &self,
+        job: F,
+    ) -> Result<JobHandle<Result<T, E>>, ThreadPoolError>
+    where
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+    {
+        self.spawn(job)
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::join_set::JoinSet;
+use crate::ThreadPool;
+
+type Task<T> = Box<dyn FnOnce() -> T + Send>;
+
+/// Set of named task with dependency between them, executed by [`ThreadPool::run`]
+///
+/// A task start as soon as every task it depend on has finished, so independent task run in parallel.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, TaskGraph, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(4).unwrap();
+///     let mut graph = TaskGraph::new();
+///
+///     graph
+///         .add("link", &["compile-a", "compile-b"], || println!("linking"))
+///         .add("compile-a", &[], || println!("compiling a"))
+///         .add("compile-b", &[], || println!("compiling b"));
+///
+///     for (task, result) in pool.run(graph)? {
+///         println!("{task}: {:?}", result);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct TaskGraph<T> {
+    names: Vec<String>,
+    deps: Vec<Vec<String>>,
+    tasks: Vec<Task<T>>,
+}
+
+impl<T> std::fmt::Debug for TaskGraph<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskGraph")
+            .field("names", &self.names)
+            .field("deps", &self.deps)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Default for TaskGraph<T> {
+    fn default() -> Self {
+        TaskGraph::new()
+    }
+}
+
+impl<T> TaskGraph<T> {
+    /// Create an empty [`TaskGraph`]
+    pub fn new() -> TaskGraph<T> {
+        TaskGraph {
+            names: Vec::new(),
+            deps: Vec::new(),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Add a task that run once every task named in `deps` has finished,
+    /// the dependency can be added before or after it.
+    pub fn add<F>(&mut self, name: impl Into<String>, deps: &[&str], job: F) -> &mut Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.names.push(name.into());
+        self.deps
+            .push(deps.iter().map(|dep| String::from(*dep)).collect());
+        self.tasks.push(Box::new(job));
+        self
+    }
+
+    /// Number of task in the graph
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if the graph has no task
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Resolve the dependency into the index of the task depending on each task,
+    /// and check every task can eventually run
+    fn dependents(&self) -> Result<Vec<Vec<usize>>, ThreadPoolError> {
+        let mut index = HashMap::with_capacity(self.names.len());
+        for (task, name) in self.names.iter().enumerate() {
+            if index.insert(name.as_str(), task).is_some() {
+                return Err(ThreadPoolError::InvalidGraph { task: name.clone() });
+            }
+        }
+
+        let mut dependents = vec![Vec::new(); self.names.len()];
+        for (task, deps) in self.deps.iter().enumerate() {
+            for dep in deps {
+                match index.get(dep.as_str()) {
+                    Some(dep) => dependents[*dep].push(task),
+                    None => return Err(ThreadPoolError::InvalidGraph { task: dep.clone() }),
+                }
+            }
+        }
+
+        // Kahn's algorithm, a task never reached is part of a cycle
+        let mut pending: Vec<usize> = self.deps.iter().map(Vec::len).collect();
+        let mut ready: VecDeque<usize> = (0..pending.len())
+            .filter(|task| pending[*task] == 0)
+            .collect();
+        let mut reached = 0;
+        while let Some(task) = ready.pop_front() {
+            reached += 1;
+            for dependent in &dependents[task] {
+                pending[*dependent] -= 1;
+                if pending[*dependent] == 0 {
+                    ready.push_back(*dependent);
+                }
+            }
+        }
+
+        if reached < self.names.len() {
+            let task = pending.iter().position(|pending| *pending > 0).unwrap_or(0);
+            return Err(ThreadPoolError::InvalidGraph {
+                task: self.names[task].clone(),
+            });
+        }
+
+        Ok(dependents)
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute every task of the graph in dependency order and return the result of each of them by name
+    ///
+    /// A task whose dependency failed is not run and report [`ThreadPoolError::Cancelled`].
+    /// When called from a worker of the pool, the worker run the queued job while waiting.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::InvalidGraph`] if a name is used twice,
+    /// a dependency doesn't exist or the dependency form a cycle, or an [`Err`] if the
+    /// communication channel between worker thread and main thread is closed.
+    pub fn run<T: Send + 'static>(
+        &self,
+        graph: TaskGraph<T>,
+    ) -> Result<HashMap<String, Result<T, ThreadPoolError>>, ThreadPoolError> {
+        let dependents = graph.dependents()?;
+        let mut pending: Vec<usize> = graph.deps.iter().map(Vec::len).collect();
+        let mut tasks: Vec<Option<Task<T>>> = graph.tasks.into_iter().map(Some).collect();
+        let mut results: Vec<Option<Result<T, ThreadPoolError>>> =
+            (0..tasks.len()).map(|_| None).collect();
+
+        let mut set = JoinSet::new();
+        // Id given by the join set to the task index
+        let mut running = HashMap::new();
+
+        for task in (0..tasks.len()).filter(|task| pending[*task] == 0) {
+            if let Some(job) = tasks[task].take() {
+                running.insert(set.spawn(self, job)?, task);
+            }
+        }
+
+        while let Some((id, result)) = set.join_next() {
+            let task = running[&id];
+            let succeeded = result.is_ok();
+            results[task] = Some(result);
+
+            // Cancel everything downstream of a failed task
+            let mut finished = vec![(task, succeeded)];
+            while let Some((task, succeeded)) = finished.pop() {
+                for dependent in &dependents[task] {
+                    if !succeeded {
+                        if tasks[*dependent].take().is_some() {
+                            results[*dependent] = Some(Err(ThreadPoolError::Cancelled));
+                            finished.push((*dependent, false));
+                        }
+                        continue;
+                    }
+
+                    pending[*dependent] -= 1;
+                    if pending[*dependent] == 0 {
+                        if let Some(job) = tasks[*dependent].take() {
+                            running.insert(set.spawn(self, job)?, *dependent);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(graph
+            .names
+            .into_iter()
+            .zip(results)
+            .map(|(name, result)| (name, result.unwrap_or(Err(ThreadPoolError::Cancelled))))
+            .collect())
+    }
+}
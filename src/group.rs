@@ -0,0 +1,171 @@
+use std::sync::{Arc, PoisonError};
+
+use crate::backend::QueueBackend;
+use crate::cancel::{CancelToken, Ticket};
+use crate::context;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::oneshot;
+use crate::sync::{Condvar, Mutex};
+use crate::{DefaultBackend, ThreadPool};
+
+/// Group of job that doesn't outlive it, created with [`TaskGroup::new`]
+///
+/// Dropping the group cancel it, job that are still queued never run and job spawned with
+/// [`TaskGroup::spawn_cancellable`] see their [`CancelToken`] cancelled, then it wait for
+/// the running job so no background work leak past the group.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, TaskGroup, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(2).unwrap();
+///     let group = TaskGroup::new(&pool);
+///
+///     let handles = (0..4)
+///         .map(|i| group.spawn(move || i * 2))
+///         .collect::<Result<Vec<_>, _>>()?;
+///     group.wait();
+///
+///     for (i, handle) in handles.into_iter().enumerate() {
+///         assert_eq!(handle.wait()?, i * 2);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TaskGroup<'pool, B: QueueBackend = DefaultBackend> {
+    pool: &'pool ThreadPool<B>,
+    state: Arc<GroupState>,
+    token: CancelToken,
+}
+
+#[derive(Debug, Default)]
+struct GroupState {
+    pending: Mutex<usize>,
+    done: Condvar,
+}
+
+/// Held by every job of the group, count it as finished even if it's dropped without running
+struct Pending(Arc<GroupState>);
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        let mut pending = self
+            .0
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *pending -= 1;
+        if *pending == 0 {
+            self.0.done.notify_all();
+        }
+    }
+}
+
+impl<'pool, B: QueueBackend> TaskGroup<'pool, B> {
+    /// Create an empty group that submit it's job to `pool`
+    pub fn new(pool: &'pool ThreadPool<B>) -> TaskGroup<'pool, B> {
+        TaskGroup {
+            pool,
+            state: Arc::default(),
+            token: CancelToken::default(),
+        }
+    }
+
+    /// Execute a job in the group and return a [`JobHandle`] to wait for it's result
+    ///
+    /// If the group is cancelled before the job start, it never run and it's handle
+    /// return [`ThreadPoolError::Cancelled`].
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn spawn<F, T>(&self, job: F) -> Result<JobHandle<T>, ThreadPoolError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.spawn_cancellable(move |_| job())
+    }
+
+    /// Same as [`TaskGroup::spawn`] but the job receive the group [`CancelToken`]
+    /// so it can stop early once the group is cancelled.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn spawn_cancellable<F, T>(&self, job: F) -> Result<JobHandle<T>, ThreadPoolError>
+    where
+        F: FnOnce(&CancelToken) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        *self
+            .state
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) += 1;
+        let pending = Pending(Arc::clone(&self.state));
+        let token = self.token.clone();
+        let (sender, receiver) = oneshot::channel();
+        let ticket = Arc::new(Ticket::default());
+        let job_ticket = Arc::clone(&ticket);
+
+        self.pool.execute(move || {
+            let _pending = pending;
+            if !token.is_cancelled() && job_ticket.start() {
+                sender.send(job(&token));
+            } else {
+                sender.cancel();
+            }
+        })?;
+
+        Ok(JobHandle::new(receiver, &self.pool.shared, ticket))
+    }
+
+    /// Cancel every job of the group without waiting for them
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns `true` if the group has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Block until every job of the group is finished, a worker of the same pool run the queued job meanwhile
+    pub fn wait(&self) {
+        let pending = || {
+            self.state
+                .pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+
+        if let Some((pool, index)) = context::worker_of(context::pool_id(&self.pool.shared)) {
+            oneshot::help_until(&*pool, index, || *pending() == 0);
+            return;
+        }
+
+        let mut pending = pending();
+        while *pending > 0 {
+            pending = self
+                .state
+                .done
+                .wait(pending)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl<B: QueueBackend> Drop for TaskGroup<'_, B> {
+    fn drop(&mut self) {
+        self.cancel();
+        self.wait();
+    }
+}
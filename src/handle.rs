@@ -0,0 +1,229 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use crate::backend::QueueBackend;
+use crate::cancel::Ticket;
+use crate::context::{self, Helper};
+use crate::error::ThreadPoolError;
+use crate::oneshot::{self, Receiver};
+use crate::shared::Shared;
+use crate::Job;
+
+/// Handle to the result of a job submitted with [`ThreadPool::spawn`](crate::ThreadPool::spawn)
+///
+/// Waiting on a handle from a job running on the same pool doesn't block the worker,
+/// it run the other queued job until the result is available so a pool where
+/// every worker wait on another job cannot deadlock.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(2).unwrap();
+///
+///     let handle = pool.spawn(|| 40 + 2)?;
+///     assert_eq!(handle.wait()?, 42);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct JobHandle<T> {
+    receiver: Receiver<T>,
+    pool: usize,
+    /// Where the continuation added with [`JobHandle::then`] are submitted
+    shared: Weak<dyn Helper>,
+    ticket: Arc<Ticket>,
+}
+
+impl<T> JobHandle<T> {
+    pub(crate) fn new<B: QueueBackend>(
+        receiver: Receiver<T>,
+        shared: &Arc<Shared<B>>,
+        ticket: Arc<Ticket>,
+    ) -> JobHandle<T> {
+        let weak: Weak<dyn Helper> = Arc::downgrade(shared) as _;
+
+        JobHandle {
+            receiver,
+            pool: context::pool_id(shared),
+            shared: weak,
+            ticket,
+        }
+    }
+
+    /// Returns `true` if the job has finished
+    pub fn is_finished(&self) -> bool {
+        self.receiver.is_ready()
+    }
+
+    /// Make sure the job never run if it's still queued, returns `true` if it was,
+    /// [`JobHandle::wait`] then return [`ThreadPoolError::Cancelled`].
+    ///
+    /// A job that already started is not interrupted and `false` is returned.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(1).unwrap();
+    ///
+    /// let report = pool.spawn(|| println!("sending report")).unwrap();
+    /// if report.abort() {
+    ///     assert!(matches!(report.wait(), Err(ThreadPoolError::Cancelled)));
+    /// }
+    /// ```
+    pub fn abort(&self) -> bool {
+        self.ticket.cancel()
+    }
+
+    /// Block until the job has finished and return it's result
+    ///
+    /// When called from a worker of the same pool the worker run the queued job while waiting.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::JobPanicked`] if the job panicked,
+    /// [`ThreadPoolError::Cancelled`] if it was aborted
+    /// and [`ThreadPoolError::QueueClosed`] if the job was discarded without running.
+    pub fn wait(self) -> Result<T, ThreadPoolError> {
+        match context::worker_of(self.pool) {
+            Some((pool, worker)) => self.receiver.recv_helping(|| pool.help(worker)),
+            None => self.receiver.recv(),
+        }
+    }
+
+    /// Execute `f` on the same pool with the result of this job once it finished,
+    /// no worker is blocked waiting for it in the meantime
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///
+    ///     let length = pool
+    ///         .spawn(|| String::from("downloaded body"))?
+    ///         .then(|body| body.map_or(0, |body| body.len()))
+    ///         .then(|length| length.map_or(0, |length| length * 2));
+    ///     assert_eq!(length.wait()?, 30);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// The returned handle report [`ThreadPoolError::QueueClosed`] if the pool is gone
+    /// or doesn't accept job anymore when this job finish.
+    pub fn then<U, F>(self, f: F) -> JobHandle<U>
+    where
+        T: Send + 'static,
+        U: Send + 'static,
+        F: FnOnce(Result<T, ThreadPoolError>) -> U + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let ticket = Arc::new(Ticket::default());
+
+        let job_ticket = Arc::clone(&ticket);
+        let shared = Weak::clone(&self.shared);
+        self.receiver.on_complete(move |result| {
+            let job = Job::new(move || {
+                if job_ticket.start() {
+                    sender.send(f(result));
+                } else {
+                    sender.cancel();
+                }
+            });
+
+            // A job that cannot be submitted drop it's sender, which complete the handle
+            if let Some(shared) = shared.upgrade() {
+                let _ = shared.submit_job(job);
+            }
+        });
+
+        JobHandle {
+            receiver,
+            pool: self.pool,
+            shared: self.shared,
+            ticket,
+        }
+    }
+
+    /// Take the result without blocking, [`None`] if the job has not finished yet
+    pub(crate) fn try_recv(&self) -> Option<Result<T, ThreadPoolError>> {
+        self.receiver.try_recv()
+    }
+
+    /// Call `watcher` once the job has finished
+    pub(crate) fn watch(&self, watcher: impl FnOnce() + Send + 'static) {
+        self.receiver.watch(watcher);
+    }
+
+    /// Identity of the pool that run the job, see [`context::pool_id`]
+    pub(crate) fn pool(&self) -> usize {
+        self.pool
+    }
+
+    /// Wait for the result while running the job of `pool` on the calling thread
+    pub(crate) fn wait_helping(self, help: impl FnMut() -> bool) -> Result<T, ThreadPoolError> {
+        self.receiver.recv_helping(help)
+    }
+}
+
+/// Receiving end of [`ThreadPool::execute_with_result`](crate::ThreadPool::execute_with_result)
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(2).unwrap();
+///
+///     let result = pool.execute_with_result(|| 40 + 2);
+///     assert_eq!(result.recv_timeout(Duration::from_secs(1))?, 42);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ResultReceiver<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> ResultReceiver<T> {
+    pub(crate) fn new(receiver: Receiver<T>) -> ResultReceiver<T> {
+        ResultReceiver { receiver }
+    }
+
+    /// Block until the job has finished and return it's result
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::JobPanicked`] if the job panicked
+    /// and [`ThreadPoolError::QueueClosed`] if the job could not be sent to the worker or was discarded.
+    pub fn recv(self) -> Result<T, ThreadPoolError> {
+        self.receiver.recv()
+    }
+
+    /// Block until the job has finished or the timeout elapsed
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`ResultReceiver::recv`], and [`ThreadPoolError::Timeout`] if the job didn't finish in time,
+    /// the result can still be received later.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, ThreadPoolError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// Take the result without blocking, [`None`] if the job has not finished yet
+    pub fn try_recv(&self) -> Option<Result<T, ThreadPoolError>> {
+        self.receiver.try_recv()
+    }
+}
@@ -0,0 +1,52 @@
+use std::time::SystemTime;
+
+use crate::stats::PanicDetails;
+
+/// Snapshot of the [`ThreadPool`](crate::ThreadPool) health, returned by [`ThreadPool::health`](crate::ThreadPool::health)
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::ThreadPool;
+///
+/// let pool = ThreadPool::new(2).unwrap();
+/// let health = pool.health();
+///
+/// let status = if health.is_healthy() { 200 } else { 503 };
+/// println!("{status}: {health:?}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Number of worker thread that are currently running
+    pub live_workers: usize,
+    /// Number of worker the pool is configured to keep alive
+    pub core_workers: usize,
+    /// Maximum number of worker the pool can spawn
+    pub max_workers: usize,
+    /// Number of message waiting in the queue
+    pub queue_depth: usize,
+    /// Maximum number of message the queue can hold, [`None`] if it's unbounded
+    pub queue_capacity: Option<usize>,
+    /// When a job last panicked and killed it's worker
+    pub last_panic: Option<SystemTime>,
+    /// Number of job that panicked since the pool is created
+    pub panics: usize,
+    /// Detail of the most recent job panic
+    pub last_panic_details: Option<PanicDetails>,
+    /// Returns `true` if the queue still accept new job
+    pub queue_open: bool,
+}
+
+impl HealthReport {
+    /// Returns `true` if the queue is open, no worker is missing and the queue is not full
+    ///
+    /// A lazy pool that has not spawned it's core worker yet is still considered healthy.
+    pub fn is_healthy(&self) -> bool {
+        let workers = self.live_workers >= self.core_workers || self.last_panic.is_none();
+        let queue = self
+            .queue_capacity
+            .is_none_or(|capacity| self.queue_depth < capacity);
+
+        self.queue_open && workers && queue
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crossbeam_utils::CachePadded;
+
+/// Number of sub bucket for every power of two, the recorded value has at most 1/16 relative error
+const SUB_BUCKET_BITS: u32 = 4;
+const SUB_BUCKET: usize = 1 << SUB_BUCKET_BITS;
+const BUCKET: usize = (64 - SUB_BUCKET_BITS as usize + 1) * SUB_BUCKET;
+
+/// Lightweight lock-free log-linear histogram of [`Duration`] recorded in nanosecond
+///
+/// Every worker update `max` on each record, it's padded so it doesn't share a line with the bucket.
+pub(crate) struct Histogram {
+    counts: Box<[AtomicU64]>,
+    max: CachePadded<AtomicU64>,
+}
+
+impl Histogram {
+    pub(crate) fn new() -> Histogram {
+        Histogram {
+            counts: (0..BUCKET).map(|_| AtomicU64::new(0)).collect(),
+            max: CachePadded::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub(crate) fn record(&self, value: Duration) {
+        let value = u64::try_from(value.as_nanos()).unwrap_or(u64::MAX);
+
+        self.counts[index_of(value)].fetch_add(1, Ordering::Relaxed);
+        self.max.fetch_max(value, Ordering::Relaxed);
+    }
+
+    pub(crate) fn reset(&self) {
+        for count in self.counts.iter() {
+            count.store(0, Ordering::Relaxed);
+        }
+
+        self.max.store(0, Ordering::Relaxed);
+    }
+
+    /// How many value has been recorded, the sum of every bucket
+    fn count(&self) -> u64 {
+        self.counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    pub(crate) fn summary(&self) -> HistogramSummary {
+        let counts: Vec<u64> = self
+            .counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect();
+        let count: u64 = counts.iter().sum();
+
+        let quantile = |quantile: f64| {
+            if count == 0 {
+                return Duration::ZERO;
+            }
+
+            let rank = ((count as f64 * quantile).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (index, bucket) in counts.iter().enumerate() {
+                seen += bucket;
+                if seen >= rank {
+                    return Duration::from_nanos(highest_of(index));
+                }
+            }
+
+            Duration::ZERO
+        };
+
+        HistogramSummary {
+            count,
+            p50: quantile(0.50),
+            p95: quantile(0.95),
+            p99: quantile(0.99),
+            max: Duration::from_nanos(self.max.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl core::fmt::Debug for Histogram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Histogram")
+            .field("count", &self.count())
+            .finish_non_exhaustive()
+    }
+}
+
+fn index_of(value: u64) -> usize {
+    if value < SUB_BUCKET as u64 {
+        return value as usize;
+    }
+
+    let exponent = 63 - value.leading_zeros();
+    let mantissa = (value >> (exponent - SUB_BUCKET_BITS)) as usize & (SUB_BUCKET - 1);
+
+    (exponent - SUB_BUCKET_BITS + 1) as usize * SUB_BUCKET + mantissa
+}
+
+fn highest_of(index: usize) -> u64 {
+    if index < SUB_BUCKET {
+        return index as u64;
+    }
+
+    let exponent = (index / SUB_BUCKET) as u32 + SUB_BUCKET_BITS - 1;
+    let mantissa = (index % SUB_BUCKET) as u64;
+    let width = 1u64 << (exponent - SUB_BUCKET_BITS);
+
+    ((SUB_BUCKET as u64 + mantissa) << (exponent - SUB_BUCKET_BITS)).saturating_add(width - 1)
+}
+
+/// Percentile summary of a recorded [`Duration`] distribution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistogramSummary {
+    /// How many value has been recorded
+    pub count: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
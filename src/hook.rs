@@ -0,0 +1,32 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Callback registered by the user, it's shared between the builder and the worker
+pub(crate) struct Hook<F: ?Sized>(Arc<F>);
+
+impl<F: ?Sized> Hook<F> {
+    pub(crate) fn new(callback: Arc<F>) -> Hook<F> {
+        Hook(callback)
+    }
+}
+
+impl<F: ?Sized> Clone for Hook<F> {
+    fn clone(&self) -> Self {
+        Hook(Arc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> Deref for Hook<F> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F: ?Sized> Debug for Hook<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Hook")
+    }
+}
@@ -0,0 +1,154 @@
+use std::hint;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::backend::{QueueBackend, TryRecvError};
+use crate::Message;
+
+/// What the worker do while waiting for a job
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdleStrategy {
+    /// Block on the queue until a job arrive
+    #[default]
+    Park,
+    /// Busy-spin on the queue, lowest latency but every idle worker burn a full core
+    Spin,
+    /// Busy-spin `spins` times, then yield the thread `yields` times before blocking on the queue
+    SpinThenYield { spins: u32, yields: u32 },
+    /// Busy-spin for a window tuned to how long the worker recently waited for a job, up to `max`,
+    /// then block on the queue
+    ///
+    /// Every worker keep a moving average of it's recent wait. It spin for twice the average while job
+    /// arrive faster than `max` and block right away once they arrive slower, so the pool trade CPU for
+    /// latency during a burst and stop burning CPU when it's mostly idle.
+    AdaptiveSpin { max: Duration },
+}
+
+/// Moving average of how long a worker waited for it's job, used by [`IdleStrategy::AdaptiveSpin`]
+#[derive(Debug, Default)]
+pub(crate) struct SpinWindow {
+    average: Duration,
+}
+
+impl SpinWindow {
+    /// How long to spin before blocking
+    fn window(&self, max: Duration) -> Duration {
+        if self.average > max {
+            Duration::ZERO
+        } else {
+            (self.average * 2).min(max)
+        }
+    }
+
+    /// Fold a wait into the average with a weight of 1/8,
+    /// it's capped so a long idle period doesn't disable spinning for too many job
+    fn record(&mut self, waited: Duration, max: Duration) {
+        let waited = waited.min(max * 2);
+        self.average = if waited > self.average {
+            self.average + (waited - self.average) / 8
+        } else {
+            self.average - (self.average - waited) / 8
+        };
+    }
+}
+
+impl IdleStrategy {
+    /// Wait for the next message according to the strategy, giving up after `timeout` if there is one
+    ///
+    /// ## Errors
+    ///
+    /// Return [`TryRecvError::Empty`] if the timeout elapsed
+    /// and [`TryRecvError::Closed`] once the queue is closed and drained.
+    pub(crate) fn recv<B: QueueBackend>(
+        &self,
+        queue: &B,
+        timeout: Option<Duration>,
+        window: &mut SpinWindow,
+    ) -> Result<Message, TryRecvError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let block = |queue: &B| match deadline {
+            Some(deadline) => {
+                queue.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => queue.recv().ok_or(TryRecvError::Closed),
+        };
+
+        let (spins, yields) = match *self {
+            IdleStrategy::Park => return block(queue),
+            IdleStrategy::AdaptiveSpin { max } => {
+                let start = Instant::now();
+                let spin = window.window(max);
+                let result = loop {
+                    match queue.try_recv() {
+                        Err(TryRecvError::Empty) if start.elapsed() < spin => hint::spin_loop(),
+                        Err(TryRecvError::Empty) => break block(queue),
+                        result => break result,
+                    }
+                };
+
+                if result.is_ok() {
+                    window.record(start.elapsed(), max);
+                }
+                return result;
+            }
+            IdleStrategy::Spin => (u32::MAX, 0),
+            IdleStrategy::SpinThenYield { spins, yields } => (spins, yields),
+        };
+
+        let mut attempt: u64 = 0;
+        loop {
+            match queue.try_recv() {
+                Err(TryRecvError::Empty) => {}
+                result => return result,
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(TryRecvError::Empty);
+            }
+
+            if *self == IdleStrategy::Spin || attempt < u64::from(spins) {
+                hint::spin_loop();
+            } else if attempt < u64::from(spins) + u64::from(yields) {
+                thread::yield_now();
+            } else {
+                return block(queue);
+            }
+
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::SpinWindow;
+
+    #[test]
+    fn spin_window_follow_recent_wait() {
+        let max = Duration::from_millis(1);
+        let mut window = SpinWindow::default();
+        assert_eq!(window.window(max), Duration::ZERO);
+
+        // Job arriving quickly open the window, up to `max`
+        for _ in 0..64 {
+            window.record(Duration::from_micros(400), max);
+        }
+        let burst = window.window(max);
+        assert!(burst > Duration::from_micros(700) && burst <= max);
+
+        // Long wait close it so an idle worker block right away
+        for _ in 0..64 {
+            window.record(Duration::from_secs(1), max);
+        }
+        assert_eq!(window.window(max), Duration::ZERO);
+
+        // And it open again once job arrive quickly
+        for _ in 0..64 {
+            window.record(Duration::from_micros(100), max);
+        }
+        let reopened = window.window(max);
+        assert!(reopened > Duration::ZERO && reopened < burst);
+    }
+}
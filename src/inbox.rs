@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError};
+#[cfg(feature = "cputime")]
+use std::time::Duration;
+
+use crossbeam_utils::CachePadded;
+
+#[cfg(feature = "cputime")]
+use crate::sync::AtomicU64;
+use crate::sync::{AtomicBool, AtomicUsize, Mutex, RwLock};
+use crate::Job;
+
+/// Job queue of a single worker, used for job that must run on a particular worker
+///
+/// The inbox outlive the worker, a worker that replace a dead or retired one
+/// take over it's index and run the job left in it's inbox.
+///
+/// The flag written by the worker on every job are padded to their own cache line
+/// so worker don't slow each other down through neighbouring inbox.
+#[derive(Debug, Default)]
+pub(crate) struct Inbox {
+    jobs: Mutex<VecDeque<Job>>,
+    /// A running worker own this inbox
+    occupied: AtomicBool,
+    /// The worker is waiting on the pool queue and need a [`Message::Wake`](crate::Message::Wake) to notice new job
+    idle: CachePadded<AtomicBool>,
+    /// Thread name of the worker owning this inbox
+    name: Mutex<Option<String>>,
+    /// The worker is running a job, named or not
+    busy: CachePadded<AtomicBool>,
+    job: Mutex<Option<Arc<str>>>,
+    /// How many job the worker owning this inbox has run
+    completed: CachePadded<AtomicUsize>,
+    /// The worker should exit and be replaced once it's done with it's current job
+    restart: AtomicBool,
+    /// The worker should exit without replacement once it's done with the job in it's inbox
+    retire: AtomicBool,
+    /// CPU time consumed by the worker thread in nanosecond, updated after every job
+    #[cfg(feature = "cputime")]
+    cpu_time: AtomicU64,
+    /// OS handle of the worker thread, used to interrupt it for a stack capture
+    #[cfg(feature = "stacks")]
+    os_thread: Mutex<Option<crate::stacks::OsThread>>,
+}
+
+impl Inbox {
+    pub(crate) fn push(&self, job: Job) {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(job);
+    }
+
+    pub(crate) fn pop(&self) -> Option<Job> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+
+    /// Take back the job pushed last, used when nobody is left to run it
+    pub(crate) fn take_last(&self) -> Option<Job> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_back()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    pub(crate) fn is_occupied(&self) -> bool {
+        self.occupied.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        self.idle.load(Ordering::SeqCst)
+    }
+
+    /// Mark the worker as waiting on the pool queue, it must check the inbox again after this
+    pub(crate) fn set_idle(&self, idle: bool) {
+        self.idle.store(idle, Ordering::SeqCst);
+    }
+
+    pub(crate) fn name(&self) -> Option<String> {
+        self.name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn set_name(&self, name: Option<String>) {
+        *self.name.lock().unwrap_or_else(PoisonError::into_inner) = name;
+    }
+
+    /// Record the job the worker is running, see [`WorkerHandle::status`](crate::WorkerHandle::status)
+    pub(crate) fn start(&self, job: Option<Arc<str>>) {
+        *self.job.lock().unwrap_or_else(PoisonError::into_inner) = job;
+        self.busy.store(true, Ordering::SeqCst);
+    }
+
+    /// Mark the worker as idle again, returning how many job it has run
+    pub(crate) fn finish(&self) -> usize {
+        self.busy.store(false, Ordering::SeqCst);
+        #[cfg(feature = "cputime")]
+        if let Some(cpu_time) = crate::cputime::current_thread() {
+            let nanos = u64::try_from(cpu_time.as_nanos()).unwrap_or(u64::MAX);
+            self.cpu_time.store(nanos, Ordering::Relaxed);
+        }
+        self.job
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        self.completed.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// CPU time consumed by the worker owning this inbox as of it's last finished job
+    #[cfg(feature = "cputime")]
+    pub(crate) fn cpu_time(&self) -> Duration {
+        Duration::from_nanos(self.cpu_time.load(Ordering::Relaxed))
+    }
+
+    /// Capture the stack of the worker owning this inbox
+    ///
+    /// The handle stay locked until the capture is over, the worker clear it before exiting
+    /// so it cannot be interrupted once it's gone.
+    #[cfg(feature = "stacks")]
+    pub(crate) fn capture_stack(&self) -> Option<Vec<crate::StackFrame>> {
+        let os_thread = self
+            .os_thread
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        os_thread.and_then(crate::stacks::capture)
+    }
+
+    /// Record the calling thread as the worker owning this inbox, it must be cleared before the thread exit
+    #[cfg(feature = "stacks")]
+    pub(crate) fn set_os_thread(&self, thread: Option<crate::stacks::OsThread>) {
+        *self
+            .os_thread
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = thread;
+    }
+
+    pub(crate) fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn job(&self) -> Option<Arc<str>> {
+        self.job
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Ask the worker to exit and be replaced, it must be woken if it's idle
+    pub(crate) fn request_restart(&self) {
+        self.restart.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_restarting(&self) -> bool {
+        self.restart.load(Ordering::SeqCst)
+    }
+
+    /// Ask the worker to exit for good, it must be woken if it's idle
+    pub(crate) fn request_retire(&self) {
+        self.retire.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the worker has been asked to restart or retire
+    pub(crate) fn is_exiting(&self) -> bool {
+        self.is_restarting() || self.retire.load(Ordering::SeqCst)
+    }
+
+    /// Give the inbox back once it's worker exit
+    pub(crate) fn release(&self) {
+        self.idle.store(false, Ordering::SeqCst);
+        self.busy.store(false, Ordering::SeqCst);
+        self.restart.store(false, Ordering::SeqCst);
+        self.retire.store(false, Ordering::SeqCst);
+        self.completed.store(0, Ordering::SeqCst);
+        #[cfg(feature = "stacks")]
+        self.set_os_thread(None);
+        self.occupied.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Inbox of every worker, indexed by the worker index
+#[derive(Debug, Default)]
+pub(crate) struct Inboxes {
+    inboxes: RwLock<Vec<Arc<Inbox>>>,
+}
+
+impl Inboxes {
+    /// Take the lowest free index for a new worker
+    pub(crate) fn claim(&self) -> (usize, Arc<Inbox>) {
+        let mut inboxes = self.inboxes.write().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(index) = inboxes.iter().position(|inbox| !inbox.is_occupied()) {
+            inboxes[index].occupied.store(true, Ordering::SeqCst);
+            return (index, Arc::clone(&inboxes[index]));
+        }
+
+        let inbox = Arc::new(Inbox {
+            occupied: AtomicBool::new(true),
+            ..Inbox::default()
+        });
+        inboxes.push(Arc::clone(&inbox));
+
+        (inboxes.len() - 1, inbox)
+    }
+
+    /// Inbox of the worker with this index, it's created if the index is not used yet
+    pub(crate) fn get(&self, index: usize) -> Arc<Inbox> {
+        if let Some(inbox) = self
+            .inboxes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(index)
+        {
+            return Arc::clone(inbox);
+        }
+
+        let mut inboxes = self.inboxes.write().unwrap_or_else(PoisonError::into_inner);
+        if inboxes.len() <= index {
+            inboxes.resize_with(index + 1, Arc::default);
+        }
+
+        Arc::clone(&inboxes[index])
+    }
+
+    /// Returns `true` if no job is waiting in any inbox
+    pub(crate) fn is_empty(&self) -> bool {
+        self.inboxes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .all(|inbox| inbox.is_empty())
+    }
+
+    /// CPU time of every worker, indexed by the worker index
+    #[cfg(feature = "cputime")]
+    pub(crate) fn cpu_time(&self) -> Vec<Duration> {
+        self.inboxes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|inbox| inbox.cpu_time())
+            .collect()
+    }
+
+    /// Returns `true` if no worker with a higher index than `index` is running
+    pub(crate) fn is_highest_occupied(&self, index: usize) -> bool {
+        self.inboxes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .skip(index + 1)
+            .all(|inbox| !inbox.is_occupied())
+    }
+
+    /// Index of every running worker
+    pub(crate) fn occupied(&self) -> Vec<usize> {
+        self.inboxes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .enumerate()
+            .filter(|(_, inbox)| inbox.is_occupied())
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
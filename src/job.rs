@@ -0,0 +1,267 @@
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ptr::{self, NonNull};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::scheduler::Qos;
+use crate::slab;
+
+/// How many word a closure can take before it get boxed
+const INLINE_WORDS: usize = 4;
+
+type Inline = [MaybeUninit<usize>; INLINE_WORDS];
+
+/// Type erased operation on the closure stored inside a [`Job`]
+struct VTable {
+    call: unsafe fn(*mut Inline),
+    drop: unsafe fn(*mut Inline),
+}
+
+/// Closure stored directly inside [`Job`]
+struct InlineFn<F>(PhantomData<F>);
+
+impl<F: FnOnce() + Send + 'static> InlineFn<F> {
+    const VTABLE: &'static VTable = &VTable {
+        call: Self::call,
+        drop: Self::drop,
+    };
+
+    const FITS: bool = mem::size_of::<F>() <= mem::size_of::<Inline>()
+        && mem::align_of::<F>() <= mem::align_of::<Inline>();
+
+    unsafe fn call(storage: *mut Inline) {
+        let f = unsafe { ptr::read(storage.cast::<F>()) };
+        f()
+    }
+
+    unsafe fn drop(storage: *mut Inline) {
+        unsafe { ptr::drop_in_place(storage.cast::<F>()) }
+    }
+}
+
+/// Closure that is too big for [`Job`], stored in a recycled block from the [`slab`]
+struct PooledFn<F>(PhantomData<F>);
+
+impl<F: FnOnce() + Send + 'static> PooledFn<F> {
+    const VTABLE: &'static VTable = &VTable {
+        call: Self::call,
+        drop: Self::drop,
+    };
+
+    const CLASS: Option<usize> = slab::class_of(mem::size_of::<F>(), mem::align_of::<F>());
+
+    unsafe fn call(storage: *mut Inline) {
+        let block = unsafe { ptr::read(storage.cast::<NonNull<F>>()) };
+        let f = unsafe { ptr::read(block.as_ptr()) };
+        unsafe { slab::release(block.cast(), Self::CLASS.unwrap()) };
+        f()
+    }
+
+    unsafe fn drop(storage: *mut Inline) {
+        let block = unsafe { ptr::read(storage.cast::<NonNull<F>>()) };
+        unsafe {
+            ptr::drop_in_place(block.as_ptr());
+            slab::release(block.cast(), Self::CLASS.unwrap());
+        }
+    }
+}
+
+/// Closure that is too big for the [`slab`], only the pointer is stored
+struct BoxedFn<F>(PhantomData<F>);
+
+impl<F: FnOnce() + Send + 'static> BoxedFn<F> {
+    const VTABLE: &'static VTable = &VTable {
+        call: Self::call,
+        drop: Self::drop,
+    };
+
+    unsafe fn call(storage: *mut Inline) {
+        let f = unsafe { Box::from_raw(ptr::read(storage.cast::<*mut F>())) };
+        f()
+    }
+
+    unsafe fn drop(storage: *mut Inline) {
+        drop(unsafe { Box::from_raw(ptr::read(storage.cast::<*mut F>())) })
+    }
+}
+
+/// A unit of work submitted to the [`ThreadPool`](crate::ThreadPool)
+///
+/// It's opaque on purpose, a [`QueueBackend`](crate::backend::QueueBackend)
+/// only need to move it around until a worker run it.
+///
+/// Small closure (up to four word) are stored inline so submitting them doesn't allocate,
+/// bigger one are stored in a recycled block and only huge one get boxed.
+pub struct Job {
+    storage: Inline,
+    vtable: &'static VTable,
+    enqueued_at: Instant,
+    name: Option<Arc<str>>,
+    deadline: Option<Instant>,
+    priority: i32,
+    qos: Qos,
+    // The stored closure is only `Send`
+    _marker: PhantomData<Box<dyn FnOnce() + Send>>,
+}
+
+impl Job {
+    /// Creates a new [`Job`] from a closure
+    ///
+    /// With the `tracing` feature the current span is captured and entered around the closure
+    /// so event emitted by the job are attributed to whoever submitted it.
+    pub(crate) fn new<F>(f: F) -> Job
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            // Keep small closure inline when there are no span to carry
+            if !span.is_none() {
+                return Job::store(move || span.in_scope(f));
+            }
+        }
+
+        Job::store(f)
+    }
+
+    fn store<F>(f: F) -> Job
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut storage: Inline = [MaybeUninit::uninit(); INLINE_WORDS];
+
+        let vtable = if InlineFn::<F>::FITS {
+            // SAFETY: `FITS` guarantee the storage is big enough and aligned for `F`
+            unsafe { ptr::write(storage.as_mut_ptr().cast::<F>(), f) };
+            InlineFn::<F>::VTABLE
+        } else if let Some(class) = PooledFn::<F>::CLASS {
+            let block = slab::acquire(class).cast::<F>();
+            // SAFETY: the block is big enough and aligned for `F`, and a pointer always fit in the storage
+            unsafe {
+                ptr::write(block.as_ptr(), f);
+                ptr::write(storage.as_mut_ptr().cast::<NonNull<F>>(), block);
+            }
+            PooledFn::<F>::VTABLE
+        } else {
+            let f = Box::into_raw(Box::new(f));
+            // SAFETY: a pointer always fit in the storage
+            unsafe { ptr::write(storage.as_mut_ptr().cast::<*mut F>(), f) };
+            BoxedFn::<F>::VTABLE
+        };
+
+        Job {
+            storage,
+            vtable,
+            enqueued_at: Instant::now(),
+            name: None,
+            deadline: None,
+            priority: 0,
+            qos: Qos::Default,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Run the job, consuming it
+    pub(crate) fn run(self) {
+        // The closure is moved out by `call`, so the Drop impl must not run
+        let mut job = ManuallyDrop::new(self);
+        drop(job.name.take());
+
+        // SAFETY: the vtable match the closure stored in `storage`, and it's only called once
+        unsafe { (job.vtable.call)(&mut job.storage) }
+    }
+
+    /// When the job was submitted to the [`ThreadPool`](crate::ThreadPool)
+    pub fn enqueued_at(&self) -> Instant {
+        self.enqueued_at
+    }
+
+    /// Give the job a name, shown by [`WorkerHandle::status`](crate::WorkerHandle::status) while it run
+    pub(crate) fn named(mut self, name: Arc<str>) -> Job {
+        self.name = Some(name);
+        self
+    }
+
+    /// Name given with [`ThreadPool::execute_named`](crate::ThreadPool::execute_named)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn shared_name(&self) -> Option<Arc<str>> {
+        self.name.clone()
+    }
+
+    /// Set the deadline used by the earliest deadline first scheduling
+    pub(crate) fn with_deadline(mut self, deadline: Instant) -> Job {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Deadline given with [`ThreadPool::execute_with_deadline`](crate::ThreadPool::execute_with_deadline)
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Set the priority used by the [`PriorityScheduler`](crate::PriorityScheduler)
+    pub(crate) fn with_priority(mut self, priority: i32) -> Job {
+        self.priority = priority;
+        self
+    }
+
+    /// Priority given with [`ThreadPool::execute_with_priority`](crate::ThreadPool::execute_with_priority), `0` by default
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Set the class used by the [`QosScheduler`](crate::QosScheduler)
+    pub(crate) fn with_qos(mut self, qos: Qos) -> Job {
+        self.qos = qos;
+        self
+    }
+
+    /// Class given with [`ThreadPool::execute_with_qos`](crate::ThreadPool::execute_with_qos), [`Qos::Default`] by default
+    pub fn qos(&self) -> Qos {
+        self.qos
+    }
+
+    /// Wrap the job into another one that keep it's name, deadline, priority, class and submission time
+    pub(crate) fn wrap<F>(self, f: F) -> Job
+    where
+        F: FnOnce(Job) + Send + 'static,
+    {
+        let name = self.name.clone();
+        let deadline = self.deadline;
+        let priority = self.priority;
+        let qos = self.qos;
+        let enqueued_at = self.enqueued_at;
+
+        let mut job = Job::new(move || f(self));
+        job.name = name;
+        job.deadline = deadline;
+        job.priority = priority;
+        job.qos = qos;
+        job.enqueued_at = enqueued_at;
+        job
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        // SAFETY: the closure is still inside `storage` since `run` skip this Drop impl
+        unsafe { (self.vtable.drop)(&mut self.storage) }
+    }
+}
+
+impl core::fmt::Debug for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Job")
+            .field("enqueued_at", &self.enqueued_at)
+            .field("name", &self.name)
+            .field("deadline", &self.deadline)
+            .field("priority", &self.priority)
+            .field("qos", &self.qos)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, PoisonError};
+
+use crate::backend::QueueBackend;
+use crate::context;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::oneshot;
+use crate::sync::{Condvar, Mutex};
+use crate::ThreadPool;
+
+/// Collection of [`JobHandle`] that yield their result in completion order
+///
+/// Every handle get an id when it's added, [`JoinSet::join_next`] return it alongside the result
+/// so the caller know which job finished.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::{thread, time::Duration};
+///
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, JoinSet, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(2).unwrap();
+///     let mut set = JoinSet::new();
+///
+///     set.spawn(&pool, || {
+///         thread::sleep(Duration::from_millis(100));
+///         "slow"
+///     })?;
+///     set.spawn(&pool, || "fast")?;
+///
+///     while let Some((id, result)) = set.join_next() {
+///         println!("job {id} finished with {}", result?);
+///     }
+///     assert!(set.is_empty());
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct JoinSet<T> {
+    handles: HashMap<usize, JobHandle<T>>,
+    finished: Arc<Finished>,
+    next_id: usize,
+}
+
+/// Id of the handle that finished, in completion order
+#[derive(Debug, Default)]
+struct Finished {
+    ids: Mutex<VecDeque<usize>>,
+    ready: Condvar,
+}
+
+impl<T> JoinSet<T> {
+    /// Create an empty [`JoinSet`]
+    pub fn new() -> JoinSet<T> {
+        JoinSet {
+            handles: HashMap::new(),
+            finished: Arc::default(),
+            next_id: 0,
+        }
+    }
+
+    /// Add a handle to the set and return it's id
+    pub fn push(&mut self, handle: JobHandle<T>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let finished = Arc::clone(&self.finished);
+        handle.watch(move || {
+            finished
+                .ids
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push_back(id);
+            finished.ready.notify_all();
+        });
+        self.handles.insert(id, handle);
+
+        id
+    }
+
+    /// Execute a job on `pool` and add it's handle to the set, returning it's id
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn spawn<B, F>(&mut self, pool: &ThreadPool<B>, job: F) -> Result<usize, ThreadPoolError>
+    where
+        B: QueueBackend,
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = pool.spawn(job)?;
+
+        Ok(self.push(handle))
+    }
+
+    /// How many job has not been joined yet
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns `true` if every job has been joined
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Block until the next job finish and return it's id and result, [`None`] if the set is empty
+    ///
+    /// When called from a worker of the pool that run the job, the worker run the queued job while waiting.
+    pub fn join_next(&mut self) -> Option<(usize, Result<T, ThreadPoolError>)> {
+        let pool = self.handles.values().next()?.pool();
+        let ids = || {
+            self.finished
+                .ids
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+
+        let id = match context::worker_of(pool) {
+            Some((pool, index)) => {
+                let mut id = None;
+                oneshot::help_until(&*pool, index, || {
+                    id = ids().pop_front();
+                    id.is_some()
+                });
+                id?
+            }
+            None => {
+                let mut ids = ids();
+                loop {
+                    if let Some(id) = ids.pop_front() {
+                        break id;
+                    }
+                    ids = self
+                        .finished
+                        .ready
+                        .wait(ids)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        };
+
+        Some(self.take(id))
+    }
+
+    /// Return the id and result of a job that already finished without blocking,
+    /// [`None`] if no job has finished yet.
+    pub fn try_join_next(&mut self) -> Option<(usize, Result<T, ThreadPoolError>)> {
+        let id = self
+            .finished
+            .ids
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()?;
+
+        Some(self.take(id))
+    }
+
+    /// Cancel every job of the set that is still queued
+    pub(crate) fn cancel_queued(&self) {
+        for handle in self.handles.values() {
+            handle.abort();
+        }
+    }
+
+    fn take(&mut self, id: usize) -> (usize, Result<T, ThreadPoolError>) {
+        let result = self
+            .handles
+            .remove(&id)
+            .and_then(|handle| handle.try_recv())
+            .unwrap_or(Err(ThreadPoolError::QueueClosed));
+
+        (id, result)
+    }
+}
+
+/// Handle are given their position as id
+impl<T> FromIterator<JobHandle<T>> for JoinSet<T> {
+    fn from_iter<I: IntoIterator<Item = JobHandle<T>>>(handles: I) -> Self {
+        let mut set = JoinSet::new();
+        for handle in handles {
+            set.push(handle);
+        }
+
+        set
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        JoinSet::new()
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::{Arc, PoisonError};
+
+use crate::context;
+use crate::oneshot;
+use crate::sync::{Condvar, Mutex, MutexGuard};
+
+/// Block until `count` reach zero, a count down that happen on another thread
+///
+/// Every clone share the same count so it can be moved into the job that count down.
+/// When waiting from a worker thread the worker run the queued job of it's pool meanwhile,
+/// so a small pool where job wait for each other cannot deadlock.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{CountDownLatch, ThreadPool};
+///
+/// let pool = ThreadPool::new(4).unwrap();
+/// let loaded = CountDownLatch::new(3);
+///
+/// for shard in 0..3 {
+///     let loaded = loaded.clone();
+///     pool.execute(move || {
+///         println!("loading shard {shard}");
+///         loaded.count_down();
+///     })
+///     .unwrap();
+/// }
+///
+/// loaded.wait();
+/// println!("every shard is loaded");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CountDownLatch {
+    inner: Arc<Shared<usize>>,
+}
+
+impl CountDownLatch {
+    /// Creates a new [`CountDownLatch`] released after `count` count down
+    pub fn new(count: usize) -> CountDownLatch {
+        CountDownLatch {
+            inner: Arc::new(Shared::new(count)),
+        }
+    }
+
+    /// Decrement the count, releasing every waiter when it reach zero. It does nothing once it's zero.
+    pub fn count_down(&self) {
+        let mut count = self.inner.lock();
+        if *count > 0 {
+            *count -= 1;
+            if *count == 0 {
+                self.inner.changed.notify_all();
+            }
+        }
+    }
+
+    /// How many count down are left before the waiter are released
+    pub fn count(&self) -> usize {
+        *self.inner.lock()
+    }
+
+    /// Block until the count reach zero
+    pub fn wait(&self) {
+        self.inner.wait_until(|count| *count == 0);
+    }
+}
+
+/// Make `parties` job wait for each other before they all move on to the next phase
+///
+/// It can be reused for every phase of the algorithm. Like [`CountDownLatch`] a worker waiting
+/// on the barrier run the queued job of it's pool meanwhile, so the barrier can be used
+/// by more job than the pool has worker.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{Barrier, ThreadPool};
+///
+/// let pool = ThreadPool::new(2).unwrap();
+/// let phase = Barrier::new(4);
+///
+/// for part in 0..4 {
+///     let phase = phase.clone();
+///     pool.execute(move || {
+///         println!("part {part} compute the first phase");
+///         if phase.wait() {
+///             println!("every part is done with the first phase");
+///         }
+///         println!("part {part} compute the second phase");
+///     })
+///     .unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Barrier {
+    parties: usize,
+    inner: Arc<Shared<Phase>>,
+}
+
+#[derive(Debug, Default)]
+struct Phase {
+    arrived: usize,
+    generation: u64,
+}
+
+impl Barrier {
+    /// Creates a new [`Barrier`] releasing the waiter once `parties` of them are waiting
+    pub fn new(parties: usize) -> Barrier {
+        Barrier {
+            parties,
+            inner: Arc::new(Shared::new(Phase::default())),
+        }
+    }
+
+    /// Block until `parties` job are waiting, returns `true` for the last one to arrive
+    /// so exactly one job of every phase can do the follow up work
+    pub fn wait(&self) -> bool {
+        let mut phase = self.inner.lock();
+        phase.arrived += 1;
+
+        if phase.arrived >= self.parties {
+            phase.arrived = 0;
+            phase.generation += 1;
+            self.inner.changed.notify_all();
+            return true;
+        }
+
+        let generation = phase.generation;
+        drop(phase);
+        self.inner
+            .wait_until(|phase| phase.generation != generation);
+
+        false
+    }
+}
+
+/// State guarded by a lock and notified on every change
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<T>,
+    changed: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn new(state: T) -> Shared<T> {
+        Shared {
+            state: Mutex::new(state),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, T> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Block until `done` return `true`, a worker run the queued job of it's pool meanwhile
+    fn wait_until(&self, done: impl Fn(&T) -> bool) {
+        if let Some((pool, index)) = context::current_worker() {
+            oneshot::help_until(&*pool, index, || done(&self.lock()));
+            return;
+        }
+
+        let mut state = self.lock();
+        while !done(&state) {
+            state = self
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
@@ -1,31 +1,185 @@
+pub mod backend;
 pub mod error;
+#[cfg(feature = "numa")]
+pub mod numa;
 
+mod admission;
+mod batch;
+mod breaker;
+#[cfg(feature = "rayon")]
+mod bridge;
+mod builder;
+mod cache;
+mod cancel;
+mod clock;
+mod config;
+mod consume;
+mod context;
+mod control;
+#[cfg(feature = "cputime")]
+mod cputime;
+mod debounce;
+mod drain;
+mod dual;
+mod events;
+mod executor;
+mod fallible;
+mod graph;
+mod group;
+mod handle;
+mod health;
+mod histogram;
+mod hook;
+mod idle;
+mod inbox;
+mod job;
+mod join_set;
+mod latch;
 mod message;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod middleware;
+mod mock;
+mod named;
+mod oneshot;
+#[cfg(feature = "otel")]
+mod otel;
+mod panic_trace;
+mod permit;
+#[cfg(feature = "priority")]
+mod priority;
+mod propagate;
+#[cfg(feature = "rename")]
+mod rename;
+mod report;
+mod room;
+mod route;
+mod scatter;
+mod scheduler;
+mod scope;
+mod select;
+#[cfg(feature = "sentry")]
+mod sentry;
+mod serve;
+mod shared;
+mod shutdown;
+mod singleflight;
+mod sizing;
+mod slab;
+mod spawner;
+#[cfg(feature = "stacks")]
+mod stacks;
+mod starvation;
+mod stats;
+mod supervisor;
+mod sync;
+mod wait_group;
+mod watch;
+mod watchdog;
 mod worker;
+mod worker_handle;
 
 #[cfg(feature = "crossbeam")]
 pub use crossbeam_channel;
+#[cfg(feature = "flume")]
+pub use flume;
+#[cfg(feature = "rayon")]
+pub use rayon;
+#[cfg(all(feature = "signal", unix))]
+pub use signal_hook;
 
-#[cfg(feature = "crossbeam")]
-use crossbeam_channel::{unbounded, Sender};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use backend::QueueBackend;
+use cancel::Ticket;
+use error::ThreadPoolError;
+use shared::Shared;
 
-#[cfg(feature = "mpsc")]
-use std::sync::mpsc::{channel, Sender};
+pub use admission::{AdmissionController, Load, MaxQueueLength, MaxQueueWait};
+pub use batch::{BatchError, BatchHandle, BatchPolicy};
+pub use breaker::CircuitBreaker;
+pub use builder::{DropPolicy, RejectionPolicy, ThreadPoolBuilder};
+pub use cancel::CancelToken;
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use config::{PoolConfig, ReconfigureReport};
+pub use consume::{Consumer, Source};
+pub use context::JobContext;
+pub use control::ControlHandler;
+pub use dual::DualPool;
+pub use events::PoolEvent;
+pub use executor::Executor;
+pub use fallible::JobError;
+pub use graph::TaskGraph;
+pub use group::TaskGroup;
+pub use handle::{JobHandle, ResultReceiver};
+pub use health::HealthReport;
+pub use histogram::HistogramSummary;
+pub use idle::IdleStrategy;
+pub use job::Job;
+pub use join_set::JoinSet;
+pub use latch::{Barrier, CountDownLatch};
+pub use message::Message;
+pub use middleware::{Middleware, Next};
+pub use mock::MockPool;
+pub use named::NamedQueue;
+#[cfg(feature = "otel")]
+pub use otel::SpanRelation;
+#[cfg(feature = "priority")]
+pub use priority::ThreadPriority;
+pub use propagate::ContextPropagator;
+pub use report::{JobPanic, PanicReporter};
+pub use scatter::{BufferedMap, Gather, MapStream};
+pub use scheduler::{
+    DeadlineScheduler, FifoScheduler, LifoScheduler, PriorityScheduler, Qos, QosScheduler,
+    Scheduler,
+};
+pub use scope::Scope;
+pub use select::{join_all, select, select_cancel, try_join_all, try_join_all_cancel};
+#[cfg(feature = "sentry")]
+pub use sentry::SentryReporter;
+pub use shutdown::ShutdownPhase;
+pub use sizing::{recommended_workers, WorkloadProfile};
+pub use spawner::{StdSpawner, ThreadSpawner};
+#[cfg(feature = "stacks")]
+pub use stacks::{StackFrame, WorkerStack};
+pub use starvation::StarvedJob;
+pub use stats::{JobNameStats, PanicDetails, PoolStats};
+pub use supervisor::{RestartPolicy, WorkerRestart};
+pub use wait_group::WaitGroup;
+pub use watch::{PoolState, StateWatch};
+pub use worker_handle::{WorkerHandle, WorkerStatus};
 
-#[cfg(feature = "mpsc")]
-use std::sync::{Arc, Mutex};
+/// [`QueueBackend`] used by [`ThreadPool::new`]
+#[cfg(feature = "crossbeam")]
+pub type DefaultBackend = backend::Crossbeam;
+
+/// [`QueueBackend`] used by [`ThreadPool::new`]
+///
+/// `crossbeam-channel` is preferred when both `crossbeam` and `flume` feature are enabled.
+#[cfg(all(feature = "flume", not(feature = "crossbeam")))]
+pub type DefaultBackend = backend::Flume;
 
-use error::{FailedToSendJob, FailedToSpawnThread};
-use message::Message;
-use worker::Worker;
+/// [`QueueBackend`] used by [`ThreadPool::new`]
+///
+/// `crossbeam-channel` or `flume` is preferred when they are enabled alongside the `mpsc` feature.
+#[cfg(all(feature = "mpsc", not(any(feature = "crossbeam", feature = "flume"))))]
+pub type DefaultBackend = backend::Mpsc;
 
-type Job = Box<dyn FnOnce() + Send + 'static>;
+#[cfg(not(any(feature = "crossbeam", feature = "flume", feature = "mpsc")))]
+compile_error!("either `crossbeam`, `flume` or `mpsc` feature must be enabled");
 
 /// This is where the thread will be pooled
 ///
 /// It depend on how you add this package on your project
 /// you can either using Rust standard library
 /// or you can use `crossbeam-channel`, the API is the same even on different feature flag.
+/// When both feature are enabled [`ThreadPool::new`] use `crossbeam-channel`,
+/// pick the other one explicitly with [`ThreadPool::with_backend`].
+/// Other queue can be used by implementing [`QueueBackend`] and passing it to [`ThreadPool::with_backend`].
+/// Building with `RUSTFLAGS="--cfg loom"` swap the internal lock and atomic for the `loom` one.
 ///
 /// ## Examples
 ///
@@ -37,7 +191,7 @@ type Job = Box<dyn FnOnce() + Send + 'static>;
 ///     time::Duration,
 /// };
 ///
-/// use unknownrori_simple_thread_pool::{error::FailedToSendJob, ThreadPool};
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
 ///
 /// fn handle_connection(mut stream: TcpStream) {
 ///     thread::sleep(Duration::from_secs(2));
@@ -49,7 +203,7 @@ type Job = Box<dyn FnOnce() + Send + 'static>;
 ///     thread::sleep(Duration::from_secs(2));
 /// }
 ///
-/// fn main() -> Result<(), FailedToSendJob> {
+/// fn main() -> Result<(), ThreadPoolError> {
 ///     let pool = ThreadPool::new(2).unwrap();
 ///
 ///     let socket = TcpListener::bind("127.0.0.1:8000").unwrap();
@@ -67,28 +221,27 @@ type Job = Box<dyn FnOnce() + Send + 'static>;
 /// }
 /// ```
 #[derive(Debug)]
-pub struct ThreadPool {
-    sender: Sender<Message>,
-    workers: Vec<Worker>,
+pub struct ThreadPool<B: QueueBackend = DefaultBackend> {
+    shared: Arc<Shared<B>>,
+    drop_policy: DropPolicy,
 }
 
 impl ThreadPool {
     /// Creates a new [`ThreadPool`], with passed worker args for how many worker thread to be created
     ///
+    /// A pool with `0` worker doesn't spawn any thread and run every job inline on the caller thread,
+    /// so the same code keep working on target without thread like `wasm32-unknown-unknown`.
+    ///
     /// ## Examples
     ///
     /// ```rust,no_run
-    /// use std::{thread, time::Duration};
+    /// use std::sync::mpsc::channel;
     ///
-    /// use unknownrori_simple_thread_pool::{
-    ///     crossbeam_channel::unbounded,
-    ///     error::FailedToSendJob,
-    ///     ThreadPool,
-    /// };
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
     ///
-    /// fn main() -> Result<(), FailedToSendJob> {
+    /// fn main() -> Result<(), ThreadPoolError> {
     ///     let pool = ThreadPool::new(2).unwrap();
-    ///     let (send, recv) = unbounded();
+    ///     let (send, recv) = channel();
     ///
     ///     pool.execute(move || {
     ///         send.send(40).unwrap();
@@ -103,106 +256,331 @@ impl ThreadPool {
     /// ## Error
     ///
     /// It will return an [`Err`] if cannot create thread worker
-    #[cfg(feature = "crossbeam")]
-    pub fn new(worker: usize) -> Result<ThreadPool, FailedToSpawnThread> {
-        let workers = Vec::with_capacity(worker);
+    pub fn new(worker: usize) -> Result<ThreadPool, ThreadPoolError> {
+        ThreadPool::with_backend(worker, DefaultBackend::default())
+    }
+}
 
-        let (sender, receiver) = unbounded();
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Creates a new [`ThreadPool`] that use the passed [`QueueBackend`] to send job to it's worker
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{DefaultBackend, ThreadPool};
+    ///
+    /// let pool = ThreadPool::with_backend(2, DefaultBackend::default()).unwrap();
+    /// ```
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker,
+    /// the worker that already spawned will be shut down before returning.
+    pub fn with_backend(worker: usize, queue: B) -> Result<ThreadPool<B>, ThreadPoolError> {
+        ThreadPoolBuilder::new(worker).build_with_backend(queue)
+    }
 
-        let mut threadpool = ThreadPool { workers, sender };
-        for _ in 0..worker {
-            let thread_builder = std::thread::Builder::new();
+    /// Execute a job to worker thread, it's require Closure with no param and no return
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.submit(Job::new(job))
+    }
 
-            let worker = Worker::new(receiver.clone(), thread_builder)
-                .or_else(|_| Err(FailedToSpawnThread))?;
+    /// Execute a job to worker thread, waiting up to `timeout` for room when the queue is bounded and full
+    ///
+    /// It ignore the [`RejectionPolicy`], a [`ThreadPoolError::Timeout`] tell the pool stayed full for the
+    /// whole duration while [`ThreadPoolError::QueueFull`] from [`ThreadPool::execute`] can be a transient spike.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPoolBuilder};
+    ///
+    /// let pool = ThreadPoolBuilder::new(2).queue_capacity(64).build().unwrap();
+    ///
+    /// match pool.execute_timeout(|| println!("processing"), Duration::from_millis(100)) {
+    ///     Ok(()) => {}
+    ///     Err(ThreadPoolError::Timeout) => eprintln!("the pool is overloaded"),
+    ///     Err(err) => eprintln!("{err}"),
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::Timeout`] if the queue is still full after `timeout`,
+    /// or an [`Err`] if the communication channel between worker thread and main thread is closed.
+    pub fn execute_timeout<F>(&self, job: F, timeout: Duration) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.submit_waiting(Job::new(job), Some(timeout))
+    }
 
-            threadpool.workers.push(worker);
-        }
+    /// Execute a job to worker thread and return a [`JobHandle`] to wait for it's result
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///
+    ///     let handle = pool.spawn(|| 40 + 2)?;
+    ///     assert_eq!(handle.wait()?, 42);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn spawn<F, T>(&self, job: F) -> Result<JobHandle<T>, ThreadPoolError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let ticket = Arc::new(Ticket::default());
+        let job_ticket = Arc::clone(&ticket);
+        self.execute(move || {
+            if job_ticket.start() {
+                sender.send(job());
+            } else {
+                sender.cancel();
+            }
+        })?;
 
-        Ok(threadpool)
+        Ok(JobHandle::new(receiver, &self.shared, ticket))
     }
 
-    /// Creates a new [`ThreadPool`], with passed worker args for how many worker thread to be created
+    /// Execute a job that must finish within `timeout` once a worker start it and return a [`JobHandle`] to it's result
+    ///
+    /// When the timeout elapse the job [`CancelToken`] is cancelled and the handle return
+    /// [`ThreadPoolError::Timeout`] right away, the job has to check the token to actually stop
+    /// and free it's worker.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
-    /// use std::sync::mpsc::channel;
-    /// use std::{thread, time::Duration};
+    /// use std::time::Duration;
     ///
-    /// use unknownrori_simple_thread_pool::{error::FailedToSendJob, ThreadPool};
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
     ///
-    /// fn main() -> Result<(), FailedToSendJob> {
-    ///     let pool = ThreadPool::new(2).unwrap();
-    ///     let (send, recv) = channel();
+    /// let pool = ThreadPool::new(2).unwrap();
     ///
-    ///     pool.execute(move || {
-    ///         send.send(40).unwrap();
-    ///     })?;
+    /// let handle = pool
+    ///     .execute_with_timeout(Duration::from_millis(100), |token| {
+    ///         let mut checked = 0;
+    ///         while !token.is_cancelled() {
+    ///             checked += 1;
+    ///         }
+    ///         checked
+    ///     })
+    ///     .unwrap();
     ///
-    ///     assert_eq!(recv.recv().unwrap(), 40);
+    /// assert!(matches!(handle.wait(), Err(ThreadPoolError::Timeout)));
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_with_timeout<F, T>(
+        &self,
+        timeout: Duration,
+        job: F,
+    ) -> Result<JobHandle<T>, ThreadPoolError>
+    where
+        F: FnOnce(&CancelToken) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let ticket = Arc::new(Ticket::default());
+        let job_ticket = Arc::clone(&ticket);
+        let expiry = receiver.expiry();
+        let watchdog = Arc::clone(&self.shared.watchdog);
+
+        self.execute(move || {
+            if !job_ticket.start() {
+                sender.cancel();
+                return;
+            }
+
+            let token = CancelToken::default();
+            let timer_token = token.clone();
+            watchdog.schedule(Instant::now() + timeout, move || {
+                timer_token.cancel();
+                expiry.expire();
+            });
+
+            sender.send(job(&token));
+        })?;
+
+        Ok(JobHandle::new(receiver, &self.shared, ticket))
+    }
+
+    /// Execute a job to worker thread and return a [`ResultReceiver`] for it's return value
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///
+    ///     assert_eq!(pool.execute_with_result(|| 40).recv()?, 40);
     ///
     ///     Ok(())
     /// }
     /// ```
     ///
-    /// ## Error
-    ///
-    /// It will return an [`Err`] if cannot create thread worker
-    #[cfg(feature = "mpsc")]
-    pub fn new(worker: usize) -> Result<ThreadPool, FailedToSpawnThread> {
-        let workers = Vec::with_capacity(worker);
+    /// If the job cannot be sent to the worker the error is reported by [`ResultReceiver::recv`].
+    pub fn execute_with_result<F, T>(&self, job: F) -> ResultReceiver<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        // The job and it's sender are dropped on failure, which the receiver see as closed
+        let _ = self.execute(move || sender.send(job()));
 
-        let (sender, receiver) = channel();
-        let receiver = Arc::new(Mutex::new(receiver));
+        ResultReceiver::new(receiver)
+    }
 
-        let mut threadpool = ThreadPool { sender, workers };
-        for _ in 0..worker {
-            let thread_builder = std::thread::Builder::new();
+    /// Block until the job of `handle` has finished, running the queued job of this pool
+    /// on the calling thread in the meantime so the waiting thread add capacity instead of idling.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(1).unwrap();
+    ///
+    ///     let handles = (0..4)
+    ///         .map(|x| pool.spawn(move || x * 2))
+    ///         .collect::<Result<Vec<_>, _>>()?;
+    ///
+    ///     for handle in handles {
+    ///         println!("{}", pool.block_on_handle(handle)?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::JobPanicked`] if the job panicked
+    /// and [`ThreadPoolError::QueueClosed`] if the job was discarded without running.
+    pub fn block_on_handle<T>(&self, handle: JobHandle<T>) -> Result<T, ThreadPoolError> {
+        let worker =
+            context::worker_of(context::pool_id(&self.shared)).and_then(|(_, worker)| worker);
 
-            let worker = Worker::new(Arc::clone(&receiver), thread_builder)
-                .or_else(|_| Err(FailedToSpawnThread))?;
+        handle.wait_helping(|| self.shared.help(worker))
+    }
 
-            threadpool.workers.push(worker);
+    /// Take a snapshot of the [`ThreadPool`] statistic
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// let stats = pool.stats();
+    ///
+    /// println!("p99 queue wait: {:?}", stats.queue_wait.p99);
+    /// ```
+    pub fn stats(&self) -> PoolStats {
+        let mut stats = self.shared.stats.snapshot();
+        stats.live_workers = self.shared.live_workers.load(Ordering::SeqCst);
+        stats.idle_workers = self.shared.idle_workers.load(Ordering::SeqCst);
+        stats.queue_depth = self.shared.queue.len();
+        #[cfg(feature = "cputime")]
+        {
+            stats.worker_cpu_time = self.shared.inboxes.cpu_time();
         }
 
-        Ok(threadpool)
+        stats
     }
 
-    /// Execute a job to worker thread, it's require Closure with no param and no return
+    /// Statistic of the job executed with [`ThreadPool::execute_named`] keyed by name,
+    /// to find out which kind of job is hogging the pool
     ///
-    /// ## Errors
+    /// Only the job that already ran are counted, they are cleared by [`ThreadPool::reset_stats`].
     ///
-    /// This function will return an [`Err`] if the communication channel between worker thread
-    /// and main thread is closed.
-    pub fn execute<F>(&self, job: F) -> Result<(), FailedToSendJob>
-    where
-        F: FnOnce() + Send + 'static,
-    {
-        self.sender
-            .send(Message::NewJob(Box::new(job)))
-            .or_else(|_| Err(FailedToSendJob))?;
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// pool.execute_named("resize", || println!("resizing image")).unwrap();
+    ///
+    /// for (name, stats) in pool.stats_by_name() {
+    ///     println!("{name}: {} job, p99 {:?}", stats.completed, stats.duration.p99);
+    /// }
+    /// ```
+    pub fn stats_by_name(&self) -> HashMap<String, JobNameStats> {
+        self.shared.stats.snapshot_by_name()
+    }
 
-        Ok(())
+    /// Name given with [`ThreadPoolBuilder::name`]
+    pub fn name(&self) -> Option<&str> {
+        self.shared.name.as_deref()
+    }
+
+    /// Number of worker thread that are currently running
+    pub fn live_workers(&self) -> usize {
+        self.shared.live_workers.load(Ordering::SeqCst)
+    }
+
+    /// Check whether the [`ThreadPool`] can still run job, see [`HealthReport`]
+    pub fn health(&self) -> HealthReport {
+        self.shared.health()
+    }
+
+    /// Reset every histogram returned by [`ThreadPool::stats`]
+    pub fn reset_stats(&self) {
+        self.shared.stats.reset();
     }
 }
 
-impl Drop for ThreadPool {
+impl<B: QueueBackend> Drop for ThreadPool<B> {
     /// Make sure the [`ThreadPool`] do proper clean up with it's thread workers
+    /// according to the [`DropPolicy`] it's built with.
     ///
-    /// ## Panic
-    ///
-    /// May Panic if communcation between worker thread and main thread is closed
-    /// or there are panic in worker thread.
+    /// Worker that died because of a panicking job is skipped,
+    /// the panic itself has already been reported by the panic hook when it happened.
+    /// No worker is restarted once the pool is dropped.
     fn drop(&mut self) {
-        for _ in &self.workers {
-            self.sender.send(Message::Terminate).unwrap();
+        match self.drop_policy {
+            DropPolicy::WaitForQueued => self.shared.flush_debounced(),
+            DropPolicy::FinishRunningOnly => self.shared.stop(),
+            DropPolicy::Detach => {}
         }
 
-        for worker in &mut self.workers {
-            if let Some(thread) = worker.take_thread() {
-                thread.join().unwrap();
-            }
+        self.shared.close();
+
+        if self.drop_policy == DropPolicy::Detach {
+            return;
         }
+
+        self.shared.join_workers();
     }
 }
@@ -1,6 +1,17 @@
+use std::any::Any;
+use std::sync::Arc;
+
 use crate::Job;
 
+/// Message passed from the [`ThreadPool`](crate::ThreadPool) to it's worker through the queue
+#[derive(Debug)]
 pub enum Message {
     NewJob(Job),
+    /// Run the next job picked by the pool, used when the pool reorder the job itself
+    Scheduled,
+    /// Tell the worker with this index that a job is waiting in it's own queue
+    Wake(usize),
+    /// Command for the [`ControlHandler`](crate::ControlHandler), handled by the worker that receive it
+    Control(Arc<dyn Any + Send + Sync>),
     Terminate,
 }
@@ -0,0 +1,63 @@
+use metrics::{counter, gauge, Counter, Gauge};
+
+/// Gauge and counter of the pool published through the `metrics` crate facade
+///
+/// Every metric carry a `pool` label set to [`ThreadPoolBuilder::name`](crate::ThreadPoolBuilder::name)
+/// so multiple pool in one process can be told apart. The handle are registered when the pool is built,
+/// so the recorder has to be installed before that.
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    workers_busy: Gauge,
+    workers_live: Gauge,
+    queue_depth: Gauge,
+    jobs_submitted: Counter,
+    jobs_completed: Counter,
+    jobs_panicked: Counter,
+}
+
+impl Metrics {
+    pub(crate) fn new(pool: &str) -> Metrics {
+        let pool = pool.to_string();
+
+        Metrics {
+            workers_busy: gauge!("thread_pool_workers_busy", "pool" => pool.clone()),
+            workers_live: gauge!("thread_pool_workers_live", "pool" => pool.clone()),
+            queue_depth: gauge!("thread_pool_queue_depth", "pool" => pool.clone()),
+            jobs_submitted: counter!("thread_pool_jobs_submitted_total", "pool" => pool.clone()),
+            jobs_completed: counter!("thread_pool_jobs_completed_total", "pool" => pool.clone()),
+            jobs_panicked: counter!("thread_pool_jobs_panicked_total", "pool" => pool),
+        }
+    }
+
+    /// A job has been handed to the worker, `queue_depth` is the length of the queue after it
+    pub(crate) fn job_queued(&self, queue_depth: usize) {
+        self.jobs_submitted.increment(1);
+        self.queue_depth.set(queue_depth as f64);
+    }
+
+    pub(crate) fn workers_live(&self, live: usize) {
+        self.workers_live.set(live as f64);
+    }
+
+    /// Count the job as busy, the returned guard count it as completed or panicked once dropped
+    pub(crate) fn job_started(&self, queue_depth: usize) -> Busy<'_> {
+        self.workers_busy.increment(1.0);
+        self.queue_depth.set(queue_depth as f64);
+
+        Busy(self)
+    }
+}
+
+/// Count a running job as completed, or as panicked if it's dropped while unwinding
+pub(crate) struct Busy<'a>(&'a Metrics);
+
+impl Drop for Busy<'_> {
+    fn drop(&mut self) {
+        self.0.workers_busy.decrement(1.0);
+        if std::thread::panicking() {
+            self.0.jobs_panicked.increment(1);
+        } else {
+            self.0.jobs_completed.increment(1);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::hook::Hook;
+use crate::Job;
+
+/// Wrap the execution of every job on the worker, registered with [`ThreadPoolBuilder::layer`](crate::ThreadPoolBuilder::layer)
+///
+/// It's meant for cross-cutting behavior like logging, timing or catching panic without touching
+/// the call site. A middleware call [`Next::run`] to continue the chain, dropping it instead skip the job.
+/// Any `Fn(Next)` closure is a middleware.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::time::Instant;
+///
+/// use unknownrori_simple_thread_pool::{Next, ThreadPoolBuilder};
+///
+/// let pool = ThreadPoolBuilder::new(2)
+///     .layer(|next: Next| {
+///         let name = next.job().name().unwrap_or("anonymous").to_string();
+///         let start = Instant::now();
+///         next.run();
+///         println!("{name} took {:?}", start.elapsed());
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Run the job through the rest of the chain with [`Next::run`]
+    fn call(&self, next: Next<'_>);
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(Next<'_>) + Send + Sync + 'static,
+{
+    fn call(&self, next: Next<'_>) {
+        self(next)
+    }
+}
+
+/// The job and the remaining middleware of the chain, see [`Middleware`]
+#[derive(Debug)]
+pub struct Next<'a> {
+    job: Job,
+    rest: &'a [Hook<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(job: Job, layers: &'a [Hook<dyn Middleware>]) -> Next<'a> {
+        Next { job, rest: layers }
+    }
+
+    /// The job going through the chain
+    pub fn job(&self) -> &Job {
+        &self.job
+    }
+
+    /// Pass the job to the next middleware, or run it if this is the last one
+    pub fn run(self) {
+        match self.rest.split_first() {
+            Some((layer, rest)) => layer.call(Next {
+                job: self.job,
+                rest,
+            }),
+            None => self.job.run(),
+        }
+    }
+}
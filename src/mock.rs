@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::error::ThreadPoolError;
+use crate::{Clock, Executor, Job, ManualClock};
+
+/// [`Executor`] without thread for deterministic test
+///
+/// [`MockPool::inline`] run every job right away on the calling thread,
+/// [`MockPool::manual`] keep them until [`MockPool::run_pending`] is called.
+///
+/// Delayed job submitted with [`MockPool::execute_after`] follow a [`ManualClock`],
+/// they become pending once the clock is advanced past their delay.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// use unknownrori_simple_thread_pool::{Executor, MockPool};
+///
+/// let pool = MockPool::manual();
+/// let counter = Arc::new(AtomicUsize::new(0));
+///
+/// let job_counter = Arc::clone(&counter);
+/// pool.execute(move || {
+///     job_counter.fetch_add(1, Ordering::SeqCst);
+/// })
+/// .unwrap();
+///
+/// assert_eq!(counter.load(Ordering::SeqCst), 0);
+/// assert_eq!(pool.run_pending(), 1);
+/// assert_eq!(counter.load(Ordering::SeqCst), 1);
+/// ```
+#[derive(Debug)]
+pub struct MockPool {
+    inline: bool,
+    pending: Mutex<VecDeque<Job>>,
+    clock: ManualClock,
+    delayed: Mutex<Vec<(Instant, Job)>>,
+}
+
+impl MockPool {
+    /// Creates a [`MockPool`] that run every job on the calling thread during [`Executor::execute`]
+    pub fn inline() -> MockPool {
+        MockPool {
+            inline: true,
+            pending: Mutex::default(),
+            clock: ManualClock::new(),
+            delayed: Mutex::default(),
+        }
+    }
+
+    /// Creates a [`MockPool`] that only run job when [`MockPool::run_pending`] is called
+    pub fn manual() -> MockPool {
+        MockPool {
+            inline: false,
+            pending: Mutex::default(),
+            clock: ManualClock::new(),
+            delayed: Mutex::default(),
+        }
+    }
+
+    /// Run the pending job in submission order on the calling thread, including the job
+    /// they submit, return how many job has been run
+    pub fn run_pending(&self) -> usize {
+        let mut ran = 0;
+
+        while let Some(job) = self.pop() {
+            job.run();
+            ran += 1;
+        }
+
+        ran
+    }
+
+    /// The [`ManualClock`] followed by the delayed job
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    /// Run `job` once the clock has been advanced by `delay`
+    pub fn execute_after<F>(&self, delay: Duration, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let due = self.clock.now() + delay;
+
+        self.delayed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((due, Job::new(job)));
+    }
+
+    /// Advance the clock and submit every delayed job that became due in due order,
+    /// return how many job has been submitted
+    pub fn advance(&self, duration: Duration) -> usize {
+        self.clock.advance(duration);
+        let now = self.clock.now();
+
+        let mut due = {
+            let mut delayed = self.delayed.lock().unwrap_or_else(PoisonError::into_inner);
+            let (due, waiting) = std::mem::take(&mut *delayed)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(at, _)| *at <= now);
+            *delayed = waiting;
+            due
+        };
+        due.sort_by_key(|(at, _)| *at);
+
+        let submitted = due.len();
+        for (_, job) in due {
+            if self.inline {
+                job.run();
+            } else {
+                self.pending
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push_back(job);
+            }
+        }
+
+        submitted
+    }
+
+    /// Number of job waiting for [`MockPool::run_pending`]
+    pub fn pending(&self) -> usize {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    fn pop(&self) -> Option<Job> {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+}
+
+impl Executor for MockPool {
+    fn execute<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if self.inline {
+            job();
+        } else {
+            self.pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push_back(Job::new(job));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+use std::sync::PoisonError;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::sync::{Condvar, Mutex, MutexGuard};
+use crate::{Job, ThreadPool};
+
+/// Named queue declared with [`ThreadPoolBuilder::named_queue`](crate::ThreadPoolBuilder::named_queue)
+#[derive(Debug, Clone)]
+pub(crate) struct NamedQueueConfig {
+    pub(crate) name: String,
+    pub(crate) weight: usize,
+    pub(crate) capacity: Option<usize>,
+}
+
+/// Job of every named queue, the pool queue only carry a [`Message::Scheduled`](crate::Message::Scheduled)
+/// token for each of them and the worker pick the queue with a weighted round-robin.
+#[derive(Debug, Default)]
+pub(crate) struct NamedQueues {
+    configs: Vec<NamedQueueConfig>,
+    state: Mutex<RoundRobin>,
+    /// Notified when a job is taken while a submitter wait for room, or when the pool stop accepting job
+    room: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct RoundRobin {
+    queues: Vec<VecDeque<Job>>,
+    /// Queue currently being serviced
+    current: usize,
+    /// How many job can still be taken from the current queue before moving to the next one
+    credit: usize,
+    /// Number of submitter waiting for room in a full queue
+    waiting: usize,
+}
+
+impl NamedQueues {
+    pub(crate) fn new(configs: Vec<NamedQueueConfig>) -> NamedQueues {
+        let credit = configs.first().map_or(0, |config| config.weight);
+        let queues = configs.iter().map(|_| VecDeque::new()).collect();
+
+        NamedQueues {
+            configs,
+            state: Mutex::new(RoundRobin {
+                queues,
+                current: 0,
+                credit,
+                waiting: 0,
+            }),
+            room: Condvar::new(),
+        }
+    }
+
+    /// Index of the queue with this name
+    pub(crate) fn find(&self, name: &str) -> Option<usize> {
+        self.configs.iter().position(|config| config.name == name)
+    }
+
+    pub(crate) fn name(&self, queue: usize) -> &str {
+        &self.configs[queue].name
+    }
+
+    pub(crate) fn len(&self, queue: usize) -> usize {
+        self.lock().queues[queue].len()
+    }
+
+    /// Push the job unless the queue is at capacity, the job is given back if it is
+    pub(crate) fn push(&self, queue: usize, job: Job) -> Result<(), Job> {
+        let mut state = self.lock();
+        if self.is_full(&state, queue) {
+            return Err(job);
+        }
+
+        state.queues[queue].push_back(job);
+        Ok(())
+    }
+
+    /// Push the job, waiting for a worker to take one while the queue is at capacity
+    ///
+    /// `check` is called under the lock before every wait so [`NamedQueues::wake`] cannot be missed,
+    /// the job is dropped if it fail.
+    pub(crate) fn push_waiting(
+        &self,
+        queue: usize,
+        job: Job,
+        check: impl Fn() -> Result<(), ThreadPoolError>,
+    ) -> Result<(), ThreadPoolError> {
+        let mut state = self.lock();
+        while self.is_full(&state, queue) {
+            check()?;
+
+            state.waiting += 1;
+            state = self
+                .room
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+            state.waiting -= 1;
+        }
+
+        state.queues[queue].push_back(job);
+        Ok(())
+    }
+
+    /// Wake every submitter waiting for room so they check again whether the pool accept job
+    pub(crate) fn wake(&self) {
+        let _state = self.lock();
+        self.room.notify_all();
+    }
+
+    fn is_full(&self, state: &RoundRobin, queue: usize) -> bool {
+        self.configs[queue]
+            .capacity
+            .is_some_and(|capacity| state.queues[queue].len() >= capacity)
+    }
+
+    /// Take the next job following the weighted round-robin
+    pub(crate) fn pop(&self) -> Option<Job> {
+        let mut state = self.lock();
+        let count = state.queues.len();
+        if count == 0 {
+            return None;
+        }
+
+        // Visiting every queue once plus the current one again cover the case where only it has job left
+        for _ in 0..=count {
+            let current = state.current;
+            if state.credit > 0 {
+                if let Some(job) = state.queues[current].pop_front() {
+                    state.credit -= 1;
+                    if state.waiting > 0 {
+                        self.room.notify_all();
+                    }
+                    return Some(job);
+                }
+            }
+
+            state.current = (current + 1) % count;
+            state.credit = self.configs[state.current].weight;
+        }
+
+        None
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RoundRobin> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Handle to a named queue of the pool, see [`ThreadPool::queue`]
+///
+/// Every named queue has it's own capacity and get a share of the worker proportional to it's weight
+/// while they are busy, so slow job of one class cannot starve the other one.
+#[derive(Debug)]
+pub struct NamedQueue<'pool, B: QueueBackend = crate::DefaultBackend> {
+    pool: &'pool ThreadPool<B>,
+    index: usize,
+}
+
+impl<B: QueueBackend> NamedQueue<'_, B> {
+    /// Name of the queue
+    pub fn name(&self) -> &str {
+        self.pool.shared.named.name(self.index)
+    }
+
+    /// Number of job waiting in this queue
+    pub fn len(&self) -> usize {
+        self.pool.shared.named.len(self.index)
+    }
+
+    /// Returns `true` if there are no job waiting in this queue
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Execute a job through this queue
+    ///
+    /// When the queue is at capacity the [`RejectionPolicy`](crate::RejectionPolicy) of the pool decide whether it wait for room
+    /// or fail with [`ThreadPoolError::QueueFull`].
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.pool.shared.submit_named(self.index, Job::new(job))
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Get a handle to the named queue declared with [`ThreadPoolBuilder::named_queue`](crate::ThreadPoolBuilder::named_queue)
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .named_queue("io", 3, Some(1024))
+    ///     .named_queue("cpu", 1, None)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// pool.queue("io").unwrap().execute(|| println!("reading file")).unwrap();
+    /// pool.queue("cpu").unwrap().execute(|| println!("hashing file")).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::NoSuchQueue`] if there are no queue with this name.
+    pub fn queue(&self, name: &str) -> Result<NamedQueue<'_, B>, ThreadPoolError> {
+        match self.shared.named.find(name) {
+            Some(index) => Ok(NamedQueue { pool: self, index }),
+            None => Err(ThreadPoolError::NoSuchQueue {
+                name: String::from(name),
+            }),
+        }
+    }
+}
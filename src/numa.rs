@@ -0,0 +1,241 @@
+//! NUMA topology detection and node-local queue, enabled with the `numa` feature
+//!
+//! On Linux the topology is read from `/sys/devices/system/node`,
+//! on other platform (or when it cannot be read) the machine is treated as a single node.
+
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::{Job, ThreadPool};
+
+/// A NUMA node and the CPU that belong to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumaNode {
+    pub id: usize,
+    pub cpus: Vec<usize>,
+}
+
+/// Detect the NUMA node of the machine, there are always at least one node
+pub fn nodes() -> Vec<NumaNode> {
+    let mut nodes: Vec<NumaNode> = fs::read_dir("/sys/devices/system/node")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let id = name.to_str()?.strip_prefix("node")?.parse().ok()?;
+            let cpulist = fs::read_to_string(entry.path().join("cpulist")).ok()?;
+
+            Some(NumaNode {
+                id,
+                cpus: parse_cpulist(cpulist.trim()),
+            })
+        })
+        .filter(|node| !node.cpus.is_empty())
+        .collect();
+
+    nodes.sort_by_key(|node| node.id);
+
+    if nodes.is_empty() {
+        let cpus = std::thread::available_parallelism().map_or(1, |cpus| cpus.get());
+        nodes.push(NumaNode {
+            id: 0,
+            cpus: (0..cpus).collect(),
+        });
+    }
+
+    nodes
+}
+
+/// Parse list like `0-3,8-11`
+fn parse_cpulist(cpulist: &str) -> Vec<usize> {
+    cpulist
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+            None => {
+                let cpu = range.parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+/// Restrict the current thread to the CPU of the node
+#[cfg(target_os = "linux")]
+pub(crate) fn pin_current_thread(node: &NumaNode) {
+    // SAFETY: `cpu_set_t` is plain data, and the set is only read by `sched_setaffinity`
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for &cpu in &node.cpus {
+            if cpu < libc::CPU_SETSIZE as usize {
+                libc::CPU_SET(cpu, &mut set);
+            }
+        }
+
+        // Pinning is best effort, the worker still work if it's not allowed
+        let _ = libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn pin_current_thread(_node: &NumaNode) {}
+
+/// How an idle worker pick the node it steal job from, see
+/// [`ThreadPoolBuilder::steal_strategy`](crate::ThreadPoolBuilder::steal_strategy)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StealStrategy {
+    /// Try every other node in turn, starting from the one after the previous steal
+    #[default]
+    RoundRobin,
+    /// Try every other node starting from a random one
+    Random,
+    /// Try the node with the most queued job first
+    MostLoaded,
+}
+
+/// Node-local queue of the pool, worker take job from their own node first
+/// and steal from the other node as a fallback
+#[derive(Debug)]
+pub(crate) struct NodeQueues {
+    pub(crate) nodes: Vec<NumaNode>,
+    queues: Vec<Mutex<VecDeque<Job>>>,
+    strategy: StealStrategy,
+    /// Most job taken from a victim at once, the extra one are moved to the thief node
+    batch: usize,
+    cursor: AtomicUsize,
+}
+
+impl NodeQueues {
+    pub(crate) fn new(nodes: Vec<NumaNode>, strategy: StealStrategy, batch: usize) -> NodeQueues {
+        let queues = nodes.iter().map(|_| Mutex::default()).collect();
+
+        NodeQueues {
+            nodes,
+            queues,
+            strategy,
+            batch: batch.max(1),
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    /// Node that a worker belong to, worker are spread evenly across node
+    pub(crate) fn node_of(&self, worker: usize) -> usize {
+        worker % self.nodes.len()
+    }
+
+    pub(crate) fn push(&self, node: usize, job: Job) {
+        self.queues[node]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(job);
+    }
+
+    /// Take a job from the node queue
+    pub(crate) fn pop(&self, node: usize) -> Option<Job> {
+        self.queues[node]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+
+    /// Take a job from the first node that has one, for thread that doesn't belong to any node
+    pub(crate) fn pop_any(&self) -> Option<Job> {
+        (0..self.queues.len()).find_map(|node| self.pop(node))
+    }
+
+    fn len(&self, node: usize) -> usize {
+        self.queues[node]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Other node in the order they should be stolen from
+    fn victims(&self, node: usize) -> Vec<usize> {
+        let count = self.queues.len();
+        let start = match self.strategy {
+            StealStrategy::RoundRobin => self.cursor.fetch_add(1, Ordering::Relaxed),
+            StealStrategy::Random => RandomState::new().build_hasher().finish() as usize,
+            StealStrategy::MostLoaded => 0,
+        };
+
+        let mut victims: Vec<usize> = (0..count)
+            .map(|offset| (start + offset) % count)
+            .filter(|victim| *victim != node)
+            .collect();
+
+        if self.strategy == StealStrategy::MostLoaded {
+            victims.sort_by_cached_key(|victim| std::cmp::Reverse(self.len(*victim)));
+        }
+
+        victims
+    }
+
+    /// Take a job from any node other than `node`, up to the steal batch is taken
+    /// and the rest is moved to `node` queue
+    pub(crate) fn steal(&self, node: usize) -> Option<Job> {
+        self.victims(node).into_iter().find_map(|victim| {
+            let mut stolen: VecDeque<Job> = {
+                let mut queue = self.queues[victim]
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let count = queue.len().min(self.batch);
+                queue.drain(..count).collect()
+            };
+
+            let job = stolen.pop_front()?;
+            if !stolen.is_empty() {
+                self.queues[node]
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .extend(stolen);
+            }
+
+            Some(job)
+        })
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a job preferably on a worker of the NUMA node,
+    /// worker of other node only pick it up when they have nothing else to do.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(8).numa(true).build().unwrap();
+    ///
+    /// for node in pool.numa_nodes() {
+    ///     pool.execute_on_node(node.id, || println!("hello from a close worker")).unwrap();
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the node doesn't exist, the pool isn't built with
+    /// [`ThreadPoolBuilder::numa`](crate::ThreadPoolBuilder::numa) or the queue is closed.
+    pub fn execute_on_node<F>(&self, node: usize, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.submit_to_node(node, Job::new(job))
+    }
+
+    /// NUMA node used by the pool, empty if the pool isn't NUMA aware
+    pub fn numa_nodes(&self) -> &[NumaNode] {
+        self.shared
+            .numa
+            .as_ref()
+            .map_or(&[], |numa| numa.nodes.as_slice())
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::{Arc, PoisonError, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::context::Helper;
+use crate::error::ThreadPoolError;
+use crate::sync::{Condvar, Mutex, MutexGuard};
+
+/// How long a helping thread sleep when there are no queued job to run
+const HELP_POLL: Duration = Duration::from_millis(1);
+
+/// Create a channel that carry the result of a single job
+pub(crate) fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let slot = Arc::new(Slot {
+        state: Mutex::new(State::Pending),
+        ready: Condvar::new(),
+        watcher: Mutex::new(None),
+    });
+
+    (
+        Sender {
+            slot: Some(Arc::clone(&slot)),
+        },
+        Receiver { slot },
+    )
+}
+
+#[derive(Debug)]
+enum State<T> {
+    Pending,
+    Ready(T),
+    /// The job panicked before producing a result
+    Panicked,
+    /// The job was dropped without being run
+    Discarded,
+    /// The job was cancelled before it started
+    Cancelled,
+    /// The job didn't finish before it's deadline
+    TimedOut,
+    Taken,
+}
+
+/// Called once the result is available
+type Watcher = Box<dyn FnOnce() + Send>;
+
+struct Slot<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+    watcher: Mutex<Option<Watcher>>,
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Slot<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Slot")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Slot<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Set the final state, only the first one is kept
+    fn complete(&self, state: State<T>) {
+        {
+            let mut current = self.lock();
+            if !matches!(*current, State::Pending) {
+                return;
+            }
+            *current = state;
+        }
+        self.ready.notify_all();
+
+        let watcher = self
+            .watcher
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(watcher) = watcher {
+            watcher();
+        }
+    }
+}
+
+/// Sending half, held by the job
+#[derive(Debug)]
+pub(crate) struct Sender<T> {
+    slot: Option<Arc<Slot<T>>>,
+}
+
+impl<T> Sender<T> {
+    pub(crate) fn send(mut self, value: T) {
+        if let Some(slot) = self.slot.take() {
+            slot.complete(State::Ready(value));
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    pub(crate) fn cancel(mut self) {
+        if let Some(slot) = self.slot.take() {
+            slot.complete(State::Cancelled);
+        }
+    }
+}
+
+/// Complete the result as timed out if the job didn't finish yet, see [`Receiver::expiry`]
+pub(crate) struct Expiry<T> {
+    slot: Weak<Slot<T>>,
+}
+
+impl<T> Expiry<T> {
+    pub(crate) fn expire(self) {
+        if let Some(slot) = self.slot.upgrade() {
+            slot.complete(State::TimedOut);
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot.take() {
+            slot.complete(if thread::panicking() {
+                State::Panicked
+            } else {
+                State::Discarded
+            });
+        }
+    }
+}
+
+/// Receiving half, held by the caller
+#[derive(Debug)]
+pub(crate) struct Receiver<T> {
+    slot: Arc<Slot<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Returns `true` if the result is available
+    pub(crate) fn is_ready(&self) -> bool {
+        !matches!(*self.slot.lock(), State::Pending)
+    }
+
+    /// Allow the result to be completed as timed out from another thread
+    pub(crate) fn expiry(&self) -> Expiry<T> {
+        Expiry {
+            slot: Arc::downgrade(&self.slot),
+        }
+    }
+
+    /// Call `watcher` once the result is available, right away if it already is
+    pub(crate) fn watch(&self, watcher: impl FnOnce() + Send + 'static) {
+        let state = self.slot.lock();
+        if matches!(*state, State::Pending) {
+            // Registered while holding the state so `complete` cannot miss it
+            *self
+                .slot
+                .watcher
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(Box::new(watcher));
+            return;
+        }
+
+        drop(state);
+        watcher();
+    }
+
+    /// Call `f` with the result once it's available, right away if it already is
+    pub(crate) fn on_complete(self, f: impl FnOnce(Result<T, ThreadPoolError>) + Send + 'static)
+    where
+        T: Send + 'static,
+    {
+        // The watcher keep the slot alive until it's called, then it's dropped with the result
+        let slot = Arc::clone(&self.slot);
+        self.watch(move || {
+            let result = Self::take(&mut slot.lock()).unwrap_or(Err(ThreadPoolError::QueueClosed));
+            f(result)
+        });
+    }
+
+    /// Take the result without blocking, [`None`] if the job has not finished yet
+    pub(crate) fn try_recv(&self) -> Option<Result<T, ThreadPoolError>> {
+        Self::take(&mut self.slot.lock())
+    }
+
+    /// Block until the job finished
+    pub(crate) fn recv(&self) -> Result<T, ThreadPoolError> {
+        let mut state = self.slot.lock();
+        loop {
+            if let Some(result) = Self::take(&mut state) {
+                return result;
+            }
+
+            state = self
+                .slot
+                .ready
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Block until the job finished or the timeout elapsed
+    pub(crate) fn recv_timeout(&self, timeout: Duration) -> Result<T, ThreadPoolError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.slot.lock();
+        loop {
+            if let Some(result) = Self::take(&mut state) {
+                return result;
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ThreadPoolError::Timeout);
+            }
+
+            state = self
+                .slot
+                .ready
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Wait until the job finished, calling `help` to run other job in the meantime
+    ///
+    /// `help` return `false` when there are nothing to run, the thread then sleep for a short while.
+    pub(crate) fn recv_helping(
+        &self,
+        mut help: impl FnMut() -> bool,
+    ) -> Result<T, ThreadPoolError> {
+        loop {
+            if let Some(result) = self.try_recv() {
+                return result;
+            }
+
+            if !help() {
+                let state = self.slot.lock();
+                if matches!(*state, State::Pending) {
+                    let _ = self.slot.ready.wait_timeout(state, HELP_POLL);
+                }
+            }
+        }
+    }
+
+    fn take(state: &mut State<T>) -> Option<Result<T, ThreadPoolError>> {
+        match std::mem::replace(state, State::Taken) {
+            State::Pending => {
+                *state = State::Pending;
+                None
+            }
+            State::Ready(value) => Some(Ok(value)),
+            State::Panicked => Some(Err(ThreadPoolError::JobPanicked)),
+            State::Cancelled => Some(Err(ThreadPoolError::Cancelled)),
+            State::TimedOut => Some(Err(ThreadPoolError::Timeout)),
+            State::Discarded | State::Taken => Some(Err(ThreadPoolError::QueueClosed)),
+        }
+    }
+}
+
+/// Run the queued job of `pool` on the calling thread until `done` return `true`,
+/// the thread sleep for a short while when there are nothing to run
+///
+/// It's the waiting loop of every primitive that let a worker help instead of blocking,
+/// [`Receiver::recv_helping`] wait on the result itself so it's woken as soon as it's ready.
+pub(crate) fn help_until(pool: &dyn Helper, worker: Option<usize>, mut done: impl FnMut() -> bool) {
+    while !done() {
+        if !pool.help(worker) {
+            thread::sleep(HELP_POLL);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use opentelemetry::trace::{Link, Status, TraceContextExt, Tracer};
+use opentelemetry::{global, Context};
+
+use crate::propagate::Propagate;
+use crate::Job;
+
+/// Name of the tracer the job span are created with
+const TRACER: &str = "unknownrori-simple-thread-pool";
+
+/// How the span of a job relate to the span that was current when it's submitted,
+/// see [`ThreadPoolBuilder::otel_spans`](crate::ThreadPoolBuilder::otel_spans)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpanRelation {
+    /// The job span start a new trace with a link to the submitting span, the submitter
+    /// usually return before the job finish so it's trace doesn't wait for the pool
+    #[default]
+    Link,
+    /// The job span is a child of the submitting span, it's shown nested under it in the same trace
+    Parent,
+}
+
+/// Create an OpenTelemetry span around every job with the global tracer
+#[derive(Debug)]
+pub(crate) struct OtelSpans {
+    pub(crate) relation: SpanRelation,
+}
+
+impl Propagate for OtelSpans {
+    fn wrap(&self, job: Job) -> Job {
+        let submitter = Context::current();
+        let relation = self.relation;
+        let name = job
+            .name()
+            .map_or_else(|| String::from("thread_pool.job"), String::from);
+
+        job.wrap(move |job| {
+            let tracer = global::tracer(TRACER);
+            let span = tracer.span_builder(name);
+            let span = match relation {
+                SpanRelation::Parent => span.start_with_context(&tracer, &submitter),
+                SpanRelation::Link => {
+                    let submitter = submitter.span().span_context().clone();
+                    let span = if submitter.is_valid() {
+                        span.with_links(vec![Link::with_context(submitter)])
+                    } else {
+                        span
+                    };
+                    // Start from an empty context so the job doesn't end up under the worker's context
+                    span.start_with_context(&tracer, &Context::new())
+                }
+            };
+
+            let context = Context::current_with_span(span);
+            let _attached = context.clone().attach();
+            let _status = PanicStatus(context);
+            job.run();
+        })
+    }
+}
+
+/// Mark the span as failed if the job panicked, the span end once the last context holding it is dropped
+struct PanicStatus(Context);
+
+impl Drop for PanicStatus {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.span().set_status(Status::error("job panicked"));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Backtrace of panicking job, see [`ThreadPoolBuilder::capture_panic_backtrace`](crate::ThreadPoolBuilder::capture_panic_backtrace)
+//!
+//! The stack is gone once the panic is caught, so it's captured by a panic hook
+//! installed on top of the existing one and handed over through a thread local.
+
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::panic;
+use std::sync::Once;
+
+static INSTALL: Once = Once::new();
+
+thread_local! {
+    /// The job running on this thread want it's backtrace captured
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static CAPTURED: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+/// Install the capturing panic hook, the previous hook is still called after
+pub(crate) fn install() {
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if ENABLED.get() {
+                CAPTURED.set(Some(Backtrace::force_capture()));
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Take the backtrace of the last panic on this thread
+pub(crate) fn take() -> Option<Backtrace> {
+    CAPTURED.take()
+}
+
+/// Capture the backtrace of a panic on this thread until dropped
+pub(crate) struct Capture(bool);
+
+impl Capture {
+    pub(crate) fn enter() -> Capture {
+        // Don't hand over a backtrace left by a panic the job caught itself
+        CAPTURED.take();
+        Capture(ENABLED.replace(true))
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        // A job run while helping restore the capture of the job it's waiting in
+        ENABLED.set(self.0);
+    }
+}
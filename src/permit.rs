@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, PoisonError, Weak};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::shared::Shared;
+use crate::sync::{Mutex, RwLock};
+use crate::{Job, ThreadPool};
+
+/// Every permit set of the pool, keyed by name
+#[derive(Debug, Default)]
+pub(crate) struct Permits {
+    sets: RwLock<HashMap<String, Arc<PermitSet>>>,
+}
+
+/// Limited resource shared by the job executed with [`ThreadPool::execute_with_permit`]
+#[derive(Debug, Default)]
+pub(crate) struct PermitSet {
+    state: Mutex<PermitState>,
+}
+
+#[derive(Debug, Default)]
+struct PermitState {
+    limit: usize,
+    in_use: usize,
+    /// Job waiting for a permit, they don't occupy a worker until they get one
+    waiting: VecDeque<Job>,
+}
+
+impl Permits {
+    fn get(&self, name: &str) -> Option<Arc<PermitSet>> {
+        self.sets
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned()
+    }
+
+    fn get_or_create(&self, name: &str) -> Arc<PermitSet> {
+        if let Some(set) = self.get(name) {
+            return set;
+        }
+
+        let mut sets = self.sets.write().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(sets.entry(String::from(name)).or_default())
+    }
+}
+
+impl PermitSet {
+    /// Take a permit for the job, or keep it waiting if there are none left
+    fn acquire(&self, job: Job) -> Option<Job> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if state.in_use < state.limit {
+            state.in_use += 1;
+            Some(job)
+        } else {
+            state.waiting.push_back(job);
+            None
+        }
+    }
+
+    /// Give a permit back, it's handed straight to the next waiting job if there is one
+    fn release(&self) -> Option<Job> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if state.in_use <= state.limit {
+            if let Some(job) = state.waiting.pop_front() {
+                return Some(job);
+            }
+        }
+
+        state.in_use -= 1;
+        None
+    }
+
+    /// Change the number of permit, returning the waiting job that got one
+    fn set_limit(&self, limit: usize) -> Vec<Job> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.limit = limit;
+
+        let granted = limit.saturating_sub(state.in_use).min(state.waiting.len());
+        state.in_use += granted;
+        state.waiting.drain(..granted).collect()
+    }
+
+    /// Drop every waiting job, used once the pool stop accepting job
+    fn discard_waiting(&self) {
+        let waiting = std::mem::take(
+            &mut self
+                .state
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .waiting,
+        );
+        drop(waiting);
+    }
+}
+
+/// Permit held by a running job, it's released when the job finish or is discarded
+struct Permit<B: QueueBackend> {
+    shared: Weak<Shared<B>>,
+    set: Arc<PermitSet>,
+}
+
+impl<B: QueueBackend> Drop for Permit<B> {
+    fn drop(&mut self) {
+        let Some(job) = self.set.release() else {
+            return;
+        };
+
+        match self.shared.upgrade() {
+            // A failed grant drop the job and release it's permit again
+            Some(shared) if shared.check_accepting().is_ok() => {
+                let _ = grant(&shared, Arc::clone(&self.set), job);
+            }
+            _ => {
+                drop(job);
+                self.set.discard_waiting();
+            }
+        }
+    }
+}
+
+/// Submit a job that got a permit, the permit is held until it finish
+fn grant<B: QueueBackend>(
+    shared: &Arc<Shared<B>>,
+    set: Arc<PermitSet>,
+    job: Job,
+) -> Result<(), ThreadPoolError> {
+    let permit = Permit {
+        shared: Arc::downgrade(shared),
+        set,
+    };
+
+    shared.enqueue(
+        Job::new(move || {
+            let _permit = permit;
+            job.run();
+        }),
+        shared.rejection_wait(),
+    )
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Set how many job executed with [`ThreadPool::execute_with_permit`] for `name` can run at the same time,
+    /// the permit set is created if it doesn't exist yet.
+    ///
+    /// Lowering the count doesn't interrupt running job, new job simply wait until enough of them finished.
+    pub fn permit_set(&self, name: &str, permits: usize) {
+        let set = self.shared.permits.get_or_create(name);
+
+        for job in set.set_limit(permits) {
+            let _ = grant(&self.shared, Arc::clone(&set), job);
+        }
+    }
+
+    /// Execute a job once a permit of the set `name` is available, the job wait in the pool
+    /// instead of occupying a worker while the resource is busy
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(8).unwrap();
+    /// pool.permit_set("gpu", 2);
+    ///
+    /// for frame in 0..16 {
+    ///     // At most two frame are rendered at once, the other worker stay free for other job
+    ///     pool.execute_with_permit("gpu", move || println!("rendering frame {frame}"))
+    ///         .unwrap();
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::NoSuchPermit`] if the permit set doesn't exist,
+    /// or an [`Err`] if the communication channel between worker thread and main thread is closed.
+    pub fn execute_with_permit<F>(&self, name: &str, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let set = self
+            .shared
+            .permits
+            .get(name)
+            .ok_or_else(|| ThreadPoolError::NoSuchPermit {
+                name: String::from(name),
+            })?;
+
+        self.shared.check_accepting()?;
+        match set.acquire(self.shared.propagate(Job::new(job))) {
+            Some(job) => grant(&self.shared, set, job),
+            None => Ok(()),
+        }
+    }
+}
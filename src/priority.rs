@@ -0,0 +1,61 @@
+/// Scheduling priority of the worker thread, enabled with the `priority` feature
+///
+/// On Linux it's mapped to the thread niceness, on Windows to the thread priority class.
+/// Setting the priority is best effort, raising it above [`ThreadPriority::Normal`]
+/// usually need elevated privilege and is silently ignored without it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThreadPriority {
+    Lowest,
+    BelowNormal,
+    #[default]
+    Normal,
+    AboveNormal,
+    Highest,
+}
+
+impl ThreadPriority {
+    /// Apply the priority to the current thread
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn apply(self) {
+        let nice = match self {
+            ThreadPriority::Lowest => 19,
+            ThreadPriority::BelowNormal => 10,
+            ThreadPriority::Normal => 0,
+            ThreadPriority::AboveNormal => -5,
+            ThreadPriority::Highest => -10,
+        };
+
+        // SAFETY: on Linux `PRIO_PROCESS` with a thread id only affect that thread
+        unsafe {
+            let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+            let _ = libc::setpriority(libc::PRIO_PROCESS, tid, nice);
+        }
+    }
+
+    /// Apply the priority to the current thread
+    #[cfg(windows)]
+    pub(crate) fn apply(self) {
+        use windows_sys::Win32::System::Threading::{
+            GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_ABOVE_NORMAL,
+            THREAD_PRIORITY_BELOW_NORMAL, THREAD_PRIORITY_HIGHEST, THREAD_PRIORITY_LOWEST,
+            THREAD_PRIORITY_NORMAL,
+        };
+
+        let priority = match self {
+            ThreadPriority::Lowest => THREAD_PRIORITY_LOWEST,
+            ThreadPriority::BelowNormal => THREAD_PRIORITY_BELOW_NORMAL,
+            ThreadPriority::Normal => THREAD_PRIORITY_NORMAL,
+            ThreadPriority::AboveNormal => THREAD_PRIORITY_ABOVE_NORMAL,
+            ThreadPriority::Highest => THREAD_PRIORITY_HIGHEST,
+        };
+
+        // SAFETY: the pseudo handle of the current thread is always valid
+        unsafe {
+            let _ = SetThreadPriority(GetCurrentThread(), priority);
+        }
+    }
+
+    /// Apply the priority to the current thread, not supported on this platform
+    #[cfg(not(any(target_os = "linux", target_os = "android", windows)))]
+    pub(crate) fn apply(self) {}
+}
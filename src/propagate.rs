@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::Job;
+
+/// Carry a thread local context from the thread that submit a job to the worker that run it,
+/// configured with [`ThreadPoolBuilder::context_propagator`](crate::ThreadPoolBuilder::context_propagator)
+///
+/// It's useful for request ID, log MDC value, or OpenTelemetry context that would otherwise be lost
+/// at the pool boundary.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::cell::RefCell;
+///
+/// use unknownrori_simple_thread_pool::{ContextPropagator, ThreadPoolBuilder};
+///
+/// thread_local! {
+///     static REQUEST_ID: RefCell<Option<u64>> = const { RefCell::new(None) };
+/// }
+///
+/// struct RequestId;
+///
+/// impl ContextPropagator for RequestId {
+///     type Context = Option<u64>;
+///
+///     fn capture(&self) -> Option<u64> {
+///         REQUEST_ID.with_borrow(|id| *id)
+///     }
+///
+///     fn install(&self, context: &Option<u64>) {
+///         REQUEST_ID.set(*context);
+///     }
+///
+///     fn uninstall(&self, _context: Option<u64>) {
+///         REQUEST_ID.set(None);
+///     }
+/// }
+///
+/// let pool = ThreadPoolBuilder::new(2)
+///     .context_propagator(RequestId)
+///     .build()
+///     .unwrap();
+/// ```
+pub trait ContextPropagator: Send + Sync + 'static {
+    /// Context captured from the submitting thread
+    type Context: Send + 'static;
+
+    /// Called on the submitting thread when a job is submitted
+    fn capture(&self) -> Self::Context;
+
+    /// Called on the worker thread right before the job run
+    fn install(&self, context: &Self::Context);
+
+    /// Called on the worker thread after the job run, even if it panicked
+    fn uninstall(&self, context: Self::Context);
+}
+
+/// Type erased [`ContextPropagator`] so the pool can hold any number of them
+pub(crate) trait Propagate: Send + Sync {
+    /// Capture the context now and wrap the job so it's installed around it
+    fn wrap(&self, job: Job) -> Job;
+}
+
+impl<P: ContextPropagator> Propagate for Arc<P> {
+    fn wrap(&self, job: Job) -> Job {
+        let captured = Captured {
+            propagator: Arc::clone(self),
+            context: self.capture(),
+        };
+
+        job.wrap(move |job| {
+            let _installed = captured.install();
+            job.run();
+        })
+    }
+}
+
+/// Context waiting for it's job to run, it's simply dropped if the job is discarded
+struct Captured<P: ContextPropagator> {
+    propagator: Arc<P>,
+    context: P::Context,
+}
+
+impl<P: ContextPropagator> Captured<P> {
+    fn install(self) -> Installed<P> {
+        self.propagator.install(&self.context);
+
+        Installed {
+            propagator: self.propagator,
+            context: Some(self.context),
+        }
+    }
+}
+
+/// Uninstall the context when the job is done, even if it panicked
+struct Installed<P: ContextPropagator> {
+    propagator: Arc<P>,
+    context: Option<P::Context>,
+}
+
+impl<P: ContextPropagator> Drop for Installed<P> {
+    fn drop(&mut self) {
+        if let Some(context) = self.context.take() {
+            self.propagator.uninstall(context);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Rename the OS thread of a worker while it run a named job, enabled with the `rename` feature
+//!
+//! Only the OS name seen by profiler and `top -H` change, [`std::thread::Thread::name`] keep the worker name.
+//! It's supported on Linux, Android and macOS, the name is truncated to 15 byte on Linux and Android.
+
+/// Restore the previous OS thread name when dropped
+pub(crate) struct Renamed {
+    #[cfg_attr(
+        not(any(target_os = "linux", target_os = "android", target_os = "macos")),
+        allow(dead_code)
+    )]
+    previous: Option<Vec<u8>>,
+}
+
+impl Drop for Renamed {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            set_current(&previous);
+        }
+    }
+}
+
+/// Rename the current thread until the returned guard is dropped
+pub(crate) fn rename_current(name: &str) -> Renamed {
+    let previous = current();
+    if previous.is_some() {
+        set_current(name.as_bytes());
+    }
+
+    Renamed { previous }
+}
+
+/// Longest name accepted by the OS, excluding the nul terminator
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const MAX_LEN: usize = 15;
+
+#[cfg(target_os = "macos")]
+const MAX_LEN: usize = 63;
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn current() -> Option<Vec<u8>> {
+    let mut buffer = [0 as libc::c_char; MAX_LEN + 1];
+
+    // SAFETY: the buffer is big enough for the longest name and it's nul terminator
+    let result = unsafe {
+        libc::pthread_getname_np(libc::pthread_self(), buffer.as_mut_ptr(), buffer.len())
+    };
+    if result != 0 {
+        return None;
+    }
+
+    Some(
+        buffer
+            .iter()
+            .take_while(|byte| **byte != 0)
+            .map(|byte| *byte as u8)
+            .collect(),
+    )
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn set_current(name: &[u8]) {
+    let mut buffer = [0 as libc::c_char; MAX_LEN + 1];
+    let name = name.iter().take_while(|byte| **byte != 0).take(MAX_LEN);
+    for (slot, byte) in buffer.iter_mut().zip(name) {
+        *slot = *byte as libc::c_char;
+    }
+
+    // SAFETY: the buffer is nul terminated and no longer than the OS limit
+    unsafe {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let _ = libc::pthread_setname_np(libc::pthread_self(), buffer.as_ptr());
+        #[cfg(target_os = "macos")]
+        let _ = libc::pthread_setname_np(buffer.as_ptr());
+    }
+}
+
+/// Renaming is not supported on this platform
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn current() -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn set_current(_name: &[u8]) {}
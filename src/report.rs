@@ -0,0 +1,49 @@
+use std::any::Any;
+
+use crate::stats::PanicDetails;
+
+/// A job that panicked, see [`PanicReporter`]
+#[derive(Debug)]
+pub struct JobPanic<'a> {
+    /// Index of the worker that ran the job, [`None`] when the job ran on the caller thread
+    pub worker: Option<usize>,
+    /// Detail of the panic, the same one kept in [`PoolStats::last_panic`](crate::PoolStats::last_panic)
+    pub details: &'a PanicDetails,
+    /// Payload the job panicked with
+    pub payload: &'a (dyn Any + Send),
+}
+
+/// Report every panicking job to an error tracker, installed with
+/// [`ThreadPoolBuilder::panic_reporter`](crate::ThreadPoolBuilder::panic_reporter)
+///
+/// It's called on the worker thread right after the job panicked, before the worker die.
+/// Any `Fn(&JobPanic)` closure is a reporter.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{JobPanic, PanicReporter, ThreadPoolBuilder};
+///
+/// struct Stderr;
+///
+/// impl PanicReporter for Stderr {
+///     fn report(&self, panic: &JobPanic<'_>) {
+///         eprintln!("{:?} panicked: {:?}", panic.details.job, panic.details.message);
+///     }
+/// }
+///
+/// let pool = ThreadPoolBuilder::new(4).panic_reporter(Stderr).build().unwrap();
+/// ```
+pub trait PanicReporter: Send + Sync + 'static {
+    /// Report the panic of a job
+    fn report(&self, panic: &JobPanic<'_>);
+}
+
+impl<F> PanicReporter for F
+where
+    F: Fn(&JobPanic<'_>) + Send + Sync + 'static,
+{
+    fn report(&self, panic: &JobPanic<'_>) {
+        self(panic)
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::Ordering;
+use std::sync::PoisonError;
+use std::time::Instant;
+
+use crate::sync::{AtomicUsize, Condvar, Mutex};
+
+/// Wake the submitter waiting for room in the bounded pool queue whenever a worker take a message
+#[derive(Debug, Default)]
+pub(crate) struct Room {
+    /// Number of submitter waiting, the worker only take the lock when it's not zero
+    waiting: AtomicUsize,
+    lock: Mutex<()>,
+    freed: Condvar,
+}
+
+impl Room {
+    /// Called by a worker after taking a message from the queue
+    pub(crate) fn notify(&self) {
+        if self.waiting.load(Ordering::SeqCst) > 0 {
+            let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+            self.freed.notify_all();
+        }
+    }
+
+    /// Block until `is_full` return `false` or the deadline is reached, returns `false` if it's still full
+    pub(crate) fn wait(&self, deadline: Instant, is_full: impl Fn() -> bool) -> bool {
+        // Counted before checking so a worker taking a message after the check always notify
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        let mut lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let has_room = loop {
+            if !is_full() {
+                break true;
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                break false;
+            }
+
+            lock = self
+                .freed
+                .wait_timeout(lock, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        };
+
+        drop(lock);
+        self.waiting.fetch_sub(1, Ordering::SeqCst);
+        has_room
+    }
+}
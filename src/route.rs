@@ -0,0 +1,135 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::{Job, ThreadPool};
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Run `job` once on every running worker thread, useful to flush or reset thread local state
+    ///
+    /// Every worker run it after the job it's currently running, busy worker doesn't delay the other one.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::cell::Cell;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// thread_local! {
+    ///     static CACHE_HIT: Cell<usize> = const { Cell::new(0) };
+    /// }
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    /// pool.broadcast(|| CACHE_HIT.set(0)).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn broadcast<F>(&self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        if self.shared.is_inline() {
+            return self.shared.submit(Job::new(job));
+        }
+
+        let job = Arc::new(job);
+
+        for worker in self.shared.inboxes.occupied() {
+            let job = Arc::clone(&job);
+            self.shared.submit_to(worker, Job::new(move || job()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a job on the worker with this index, the index goes from `0` to the maximum worker count
+    ///
+    /// Every job sent to the same index run on the same thread, one after another in submission order,
+    /// so a worker can own a shard of state. A worker that replace a dead or retired worker
+    /// take over it's index and the job waiting for it, a new worker is spawned for the index
+    /// if it's owner died without replacement.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    ///
+    /// for shard in 0..16 {
+    ///     pool.execute_on(shard % 4, move || println!("processing shard {shard}"))
+    ///         .unwrap();
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::NoSuchWorker`] if the index is not lower than
+    /// the maximum worker count, [`ThreadPoolError::SpawnFailed`] if the index has no worker and
+    /// none can be spawned, or an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_on<F>(&self, worker: usize, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        // The caller thread act as the only worker of a pool without any
+        if worker >= self.shared.max_workers().max(1) {
+            return Err(ThreadPoolError::NoSuchWorker { worker });
+        }
+
+        self.shared.submit_to(worker, Job::new(job))?;
+        if self.shared.is_inline() {
+            return Ok(());
+        }
+
+        // A job nobody can run is given back instead of being reported as queued
+        self.shared.spawn_owner(worker).inspect_err(|_| {
+            drop(self.shared.inboxes.get(worker).take_last());
+        })
+    }
+
+    /// Execute a job on the worker picked by hashing `key`, every job with the same key
+    /// run on the same worker so per-entity state stay warm in that worker cache.
+    ///
+    /// Only the core worker are picked, job with the same key run in submission order
+    /// as a side effect of [`ThreadPool::execute_on`] but different key can share a worker.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    ///
+    /// for user in ["alice", "bob", "alice"] {
+    ///     pool.execute_sticky(user, move || println!("updating {user}"))
+    ///         .unwrap();
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::SpawnFailed`] if the picked worker is dead and
+    /// no replacement can be spawned, or an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_sticky<K, F>(&self, key: K, job: F) -> Result<(), ThreadPoolError>
+    where
+        K: Hash,
+        F: FnOnce() + Send + 'static,
+    {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        let workers = match self.shared.core_workers() {
+            0 => self.shared.max_workers().max(1),
+            core_workers => core_workers,
+        };
+
+        self.execute_on((hasher.finish() % workers as u64) as usize, job)
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::ThreadPool;
+
+/// Receiving end of [`ThreadPool::scatter`], yields `(index, result)` pairs as the jobs complete
+///
+/// The `index` is the position of the input that produced the result,
+/// the iteration ends once every job has reported back.
+#[derive(Debug)]
+pub struct Gather<R> {
+    receiver: Receiver<(usize, R)>,
+    remaining: usize,
+}
+
+impl<R> Gather<R> {
+    /// Number of results that has not been received yet
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<R> Iterator for Gather<R> {
+    type Item = (usize, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // When a job panic it's sender is dropped, so the receiver hang up
+        // once every other job has finished instead of blocking forever.
+        let item = self.receiver.recv().ok()?;
+        self.remaining -= 1;
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+/// Iterator returned by [`ThreadPool::map_stream`], yields the results as the jobs complete
+///
+/// Input are pulled lazily, at most [`MapStream::in_flight`] job are submitted at once
+/// so a huge input doesn't flood the queue.
+pub struct MapStream<'pool, I, R, F, B: QueueBackend = crate::DefaultBackend> {
+    pool: &'pool ThreadPool<B>,
+    inputs: I,
+    f: Arc<F>,
+    sender: Sender<Result<R, ThreadPoolError>>,
+    receiver: Receiver<Result<R, ThreadPoolError>>,
+    in_flight: usize,
+    max_in_flight: usize,
+}
+
+impl<I, R, F, B: QueueBackend> std::fmt::Debug for MapStream<'_, I, R, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapStream")
+            .field("in_flight", &self.in_flight)
+            .field("max_in_flight", &self.max_in_flight)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Report back to the [`MapStream`] even if the job panicked or got dropped
+struct Reply<R> {
+    sender: Option<Sender<Result<R, ThreadPoolError>>>,
+}
+
+impl<R> Reply<R> {
+    fn send(mut self, result: R) {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Ok(result));
+        }
+    }
+}
+
+impl<R> Drop for Reply<R> {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Err(if thread::panicking() {
+                ThreadPoolError::JobPanicked
+            } else {
+                ThreadPoolError::QueueClosed
+            }));
+        }
+    }
+}
+
+impl<I, T, R, F, B> MapStream<'_, I, R, F, B>
+where
+    I: Iterator<Item = T>,
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+    B: QueueBackend,
+{
+    /// Set how many job can be submitted without their result being consumed, `0` is treated as `1`
+    pub fn in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Submit job until there are enough in flight, a job that cannot be submitted report it's error as a result
+    fn fill(&mut self) {
+        while self.in_flight < self.max_in_flight {
+            let Some(input) = self.inputs.next() else {
+                return;
+            };
+
+            let f = Arc::clone(&self.f);
+            let reply = Reply {
+                sender: Some(self.sender.clone()),
+            };
+            // The reply is dropped with the job when it cannot be submitted, which report the error
+            let _ = self.pool.execute(move || reply.send(f(input)));
+            self.in_flight += 1;
+        }
+    }
+}
+
+impl<I, T, R, F, B> Iterator for MapStream<'_, I, R, F, B>
+where
+    I: Iterator<Item = T>,
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+    B: QueueBackend,
+{
+    type Item = Result<R, ThreadPoolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill();
+        if self.in_flight == 0 {
+            return None;
+        }
+
+        // Every job send exactly one reply, and `self` keep a sender alive
+        let result = self.receiver.recv().ok()?;
+        self.in_flight -= 1;
+
+        Some(result)
+    }
+}
+
+/// Iterator returned by [`ThreadPool::buffered_map`], yields the results in input order
+///
+/// At most `in_flight` job are outstanding, the next input is only submitted once
+/// the oldest result has been yielded.
+pub struct BufferedMap<'pool, I, R, F, B: QueueBackend = crate::DefaultBackend> {
+    pool: &'pool ThreadPool<B>,
+    inputs: I,
+    f: Arc<F>,
+    pending: VecDeque<Result<JobHandle<R>, ThreadPoolError>>,
+    in_flight: usize,
+}
+
+impl<I, R, F, B: QueueBackend> std::fmt::Debug for BufferedMap<'_, I, R, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedMap")
+            .field("pending", &self.pending.len())
+            .field("in_flight", &self.in_flight)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, T, R, F, B> Iterator for BufferedMap<'_, I, R, F, B>
+where
+    I: Iterator<Item = T>,
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+    B: QueueBackend,
+{
+    type Item = Result<R, ThreadPoolError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.len() < self.in_flight {
+            let Some(input) = self.inputs.next() else {
+                break;
+            };
+
+            let f = Arc::clone(&self.f);
+            self.pending.push_back(self.pool.spawn(move || f(input)));
+        }
+
+        Some(self.pending.pop_front()?.and_then(JobHandle::wait))
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Lazily map every input on the worker thread and return a [`MapStream`] that yields
+    /// the results in completion order, with at most twice the maximum worker count in flight.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    ///
+    /// let lines = (0..1_000_000).map(|i| format!("line {i}"));
+    /// for length in pool.map_stream(lines, |line| line.len()).in_flight(64) {
+    ///     println!("{}", length.unwrap());
+    /// }
+    /// ```
+    ///
+    /// A job that panicked or could not be submitted yield an [`Err`].
+    pub fn map_stream<I, T, R, F>(&self, inputs: I, f: F) -> MapStream<'_, I::IntoIter, R, F, B>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let (sender, receiver) = channel();
+
+        MapStream {
+            pool: self,
+            inputs: inputs.into_iter(),
+            f: Arc::new(f),
+            sender,
+            receiver,
+            in_flight: 0,
+            max_in_flight: (self.shared.max_workers() * 2).max(1),
+        }
+    }
+
+    /// Lazily map every input on the worker thread and return a [`BufferedMap`] that yields
+    /// the results in input order, with at most `in_flight` job outstanding (`0` is treated as `1`)
+    ///
+    /// Unlike [`ThreadPool::map_stream`] a slow job hold back the results after it,
+    /// but the memory used doesn't depend on the input length.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    ///
+    /// let lines = (0..10_000_000).map(|i| format!("line {i}"));
+    /// for (i, length) in pool.buffered_map(lines, |line| line.len(), 64).enumerate() {
+    ///     println!("line {i} is {} byte long", length.unwrap());
+    /// }
+    /// ```
+    ///
+    /// A job that panicked or could not be submitted yield an [`Err`].
+    pub fn buffered_map<I, T, R, F>(
+        &self,
+        inputs: I,
+        f: F,
+        in_flight: usize,
+    ) -> BufferedMap<'_, I::IntoIter, R, F, B>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        BufferedMap {
+            pool: self,
+            inputs: inputs.into_iter(),
+            f: Arc::new(f),
+            pending: VecDeque::new(),
+            in_flight: in_flight.max(1),
+        }
+    }
+
+    /// Distribute every input across the worker thread and return a [`Gather`]
+    /// that yields `(index, result)` pairs in completion order.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///
+    ///     for (index, result) in pool.scatter(vec![1, 2, 3], |x| x * 2)? {
+    ///         println!("input {index} produce {result}");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn scatter<I, T, R, F>(&self, inputs: I, f: F) -> Result<Gather<R>, ThreadPoolError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let (sender, receiver) = channel();
+
+        let mut remaining = 0;
+        for (index, input) in inputs.into_iter().enumerate() {
+            let f = Arc::clone(&f);
+            let sender = sender.clone();
+
+            self.execute(move || {
+                let _ = sender.send((index, f(input)));
+            })?;
+
+            remaining += 1;
+        }
+
+        Ok(Gather {
+            receiver,
+            remaining,
+        })
+    }
+
+    /// Distribute every input across the worker thread and gather the results,
+    /// the returned [`Vec`] is in the same order as the inputs.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///
+    ///     let result = pool.scatter_gather(vec![1, 2, 3], |x| x * 2)?;
+    ///     assert_eq!(result, vec![2, 4, 6]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed, or [`ThreadPoolError::JobPanicked`] if one of the job panicked.
+    pub fn scatter_gather<I, T, R, F>(&self, inputs: I, f: F) -> Result<Vec<R>, ThreadPoolError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let gather = self.scatter(inputs, f)?;
+
+        let mut results: Vec<Option<R>> = (0..gather.remaining()).map(|_| None).collect();
+        for (index, result) in gather {
+            results[index] = Some(result);
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.ok_or(ThreadPoolError::JobPanicked))
+            .collect()
+    }
+
+    /// Map every item in parallel and combine the results with `reduce`
+    ///
+    /// The items are split in one chunk per worker, every chunk is reduced on it's worker
+    /// starting from `identity` then the partial results are combined in input order on the calling thread.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///
+    ///     let words = vec!["lorem", "ipsum", "dolor"];
+    ///     let letters = pool.map_reduce(words, |word| word.len(), 0, |a, b| a + b)?;
+    ///     assert_eq!(letters, 15);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed, or [`ThreadPoolError::JobPanicked`] if `map` or `reduce` panicked.
+    pub fn map_reduce<I, T, R, M, F>(
+        &self,
+        items: I,
+        map: M,
+        identity: R,
+        reduce: F,
+    ) -> Result<R, ThreadPoolError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send + 'static,
+        R: Clone + Send + Sync + 'static,
+        M: Fn(T) -> R + Send + Sync + 'static,
+        F: Fn(R, R) -> R + Send + Sync + 'static,
+    {
+        let mut items: Vec<T> = items.into_iter().collect();
+        let chunk_size = items
+            .len()
+            .div_ceil(self.shared.core_workers().max(1))
+            .max(1);
+
+        let mut chunks = Vec::new();
+        while items.len() > chunk_size {
+            chunks.push(items.split_off(items.len() - chunk_size));
+        }
+        chunks.push(items);
+        chunks.reverse();
+
+        let reduce = Arc::new(reduce);
+        let chunk_identity = identity.clone();
+        let chunk_reduce = Arc::clone(&reduce);
+        let gather = self.scatter(chunks, move |chunk| {
+            chunk
+                .into_iter()
+                .map(&map)
+                .fold(chunk_identity.clone(), |acc, item| chunk_reduce(acc, item))
+        })?;
+
+        let mut partials: Vec<Option<R>> = (0..gather.remaining()).map(|_| None).collect();
+        for (index, partial) in gather {
+            partials[index] = Some(partial);
+        }
+
+        partials.into_iter().try_fold(identity, |acc, partial| {
+            partial
+                .map(|partial| reduce(acc, partial))
+                .ok_or(ThreadPoolError::JobPanicked)
+        })
+    }
+}
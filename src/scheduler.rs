@@ -0,0 +1,414 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::sync::Mutex;
+use crate::{Job, ThreadPool};
+
+/// Decide which waiting job run next, register it with [`ThreadPoolBuilder::scheduler`](crate::ThreadPoolBuilder::scheduler)
+///
+/// Job handed to a scheduler are held by the pool instead of the queue, the queue only carry a
+/// [`Message::Scheduled`](crate::Message::Scheduled) token for each of them so the job that run next
+/// is decided when a worker is free rather than at submission.
+/// [`Job::enqueued_at`], [`Job::name`], [`Job::deadline`], [`Job::priority`] and [`Job::qos`] can be used to order them.
+///
+/// Every job pushed has to be returned by [`Scheduler::pop`] eventually, a job that is never popped never run.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::sync::Mutex;
+///
+/// use unknownrori_simple_thread_pool::{Job, Scheduler, ThreadPoolBuilder};
+///
+/// /// Run named job before the anonymous one
+/// #[derive(Default)]
+/// struct NamedFirst {
+///     named: Mutex<Vec<Job>>,
+///     anonymous: Mutex<Vec<Job>>,
+/// }
+///
+/// impl Scheduler for NamedFirst {
+///     fn push(&self, job: Job) {
+///         match job.name() {
+///             Some(_) => self.named.lock().unwrap().push(job),
+///             None => self.anonymous.lock().unwrap().push(job),
+///         }
+///     }
+///
+///     fn pop(&self) -> Option<Job> {
+///         self.named
+///             .lock()
+///             .unwrap()
+///             .pop()
+///             .or_else(|| self.anonymous.lock().unwrap().pop())
+///     }
+/// }
+///
+/// let pool = ThreadPoolBuilder::new(4)
+///     .scheduler(NamedFirst::default())
+///     .build()
+///     .unwrap();
+/// ```
+pub trait Scheduler: Send + Sync + 'static {
+    /// Hold a job submitted to the pool until it's picked by [`Scheduler::pop`]
+    fn push(&self, job: Job);
+
+    /// Take the job that should run next, called once for every pushed job
+    fn pop(&self) -> Option<Job>;
+}
+
+/// The oldest job run first, the order a pool without scheduler already use
+#[derive(Debug, Default)]
+pub struct FifoScheduler {
+    jobs: Mutex<VecDeque<Job>>,
+}
+
+impl Scheduler for FifoScheduler {
+    fn push(&self, job: Job) {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back(job);
+    }
+
+    fn pop(&self) -> Option<Job> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+}
+
+/// The most recently submitted job run first, used by [`ThreadPoolBuilder::lifo`](crate::ThreadPoolBuilder::lifo)
+#[derive(Debug, Default)]
+pub struct LifoScheduler {
+    jobs: Mutex<Vec<Job>>,
+}
+
+impl Scheduler for LifoScheduler {
+    fn push(&self, job: Job) {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(job);
+    }
+
+    fn pop(&self) -> Option<Job> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+    }
+}
+
+/// The job with the highest [`Job::priority`] run first, job with the same priority stay FIFO
+///
+/// With aging a waiting job gain one priority level every aging period, so a low priority job
+/// is eventually run even under a constant stream of higher priority job.
+#[derive(Debug)]
+pub struct PriorityScheduler {
+    aging: Option<Duration>,
+    /// Reference point of the submission time used by the aging
+    start: Instant,
+    queue: Mutex<Ordered<i128>>,
+}
+
+impl PriorityScheduler {
+    /// Create a scheduler where a waiting job gain one priority level every `aging` period,
+    /// used by [`ThreadPoolBuilder::priority_aging`](crate::ThreadPoolBuilder::priority_aging)
+    pub fn with_aging(aging: Duration) -> PriorityScheduler {
+        PriorityScheduler {
+            aging: Some(aging).filter(|aging| !aging.is_zero()),
+            ..PriorityScheduler::default()
+        }
+    }
+
+    /// Order key of the job, it never change while the job wait
+    ///
+    /// The aged priority at time `t` is `priority + (t - enqueued_at) / aging`, every job share the same `t`
+    /// so comparing `priority * aging - enqueued_at` give the same order without updating the queue.
+    fn key(&self, job: &Job) -> i128 {
+        let priority = i128::from(job.priority());
+        match self.aging {
+            Some(aging) => {
+                let enqueued_at = job.enqueued_at().saturating_duration_since(self.start);
+                priority * aging.as_nanos() as i128 - enqueued_at.as_nanos() as i128
+            }
+            None => priority,
+        }
+    }
+}
+
+impl Default for PriorityScheduler {
+    fn default() -> PriorityScheduler {
+        PriorityScheduler {
+            aging: None,
+            start: Instant::now(),
+            queue: Mutex::default(),
+        }
+    }
+}
+
+impl Scheduler for PriorityScheduler {
+    fn push(&self, job: Job) {
+        let key = self.key(&job);
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(key, job);
+    }
+
+    fn pop(&self) -> Option<Job> {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+    }
+}
+
+/// The job with the nearest [`Job::deadline`] run first, used by
+/// [`ThreadPoolBuilder::earliest_deadline_first`](crate::ThreadPoolBuilder::earliest_deadline_first)
+///
+/// Job without a deadline run once no job with one is waiting, job with the same deadline stay FIFO.
+#[derive(Debug, Default)]
+pub struct DeadlineScheduler {
+    queue: Mutex<Ordered<EarliestDeadline>>,
+}
+
+impl Scheduler for DeadlineScheduler {
+    fn push(&self, job: Job) {
+        let deadline = EarliestDeadline(job.deadline());
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(deadline, job);
+    }
+
+    fn pop(&self) -> Option<Job> {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+    }
+}
+
+/// Quality of service class of a job, used by [`QosScheduler`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Qos {
+    /// Latency sensitive job, like the one a user is waiting for, it run before any other class
+    Interactive,
+    /// Regular job
+    #[default]
+    Default,
+    /// Job nobody is waiting for, it only run when no job of a higher class is pending
+    Background,
+}
+
+/// Run [`Qos::Interactive`] job first, then [`Qos::Default`] one and [`Qos::Background`] job last,
+/// job of the same class stay FIFO
+///
+/// A background job that waited longer than the aging period is run next regardless of the other class,
+/// so background work still make progress under a constant stream of higher class job.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use unknownrori_simple_thread_pool::{Qos, QosScheduler, ThreadPoolBuilder};
+///
+/// let pool = ThreadPoolBuilder::new(4)
+///     .scheduler(QosScheduler::new(Duration::from_secs(5)))
+///     .build()
+///     .unwrap();
+///
+/// pool.execute_with_qos(Qos::Background, || println!("compacting")).unwrap();
+/// pool.execute_with_qos(Qos::Interactive, || println!("rendering")).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct QosScheduler {
+    aging: Duration,
+    /// Pending job of every class, indexed by the [`Qos`] discriminant
+    queues: Mutex<[VecDeque<Job>; 3]>,
+}
+
+impl QosScheduler {
+    /// Create a scheduler that promote a background job once it waited for `aging`
+    pub fn new(aging: Duration) -> QosScheduler {
+        QosScheduler {
+            aging,
+            queues: Mutex::default(),
+        }
+    }
+}
+
+impl Default for QosScheduler {
+    /// Background job are promoted after waiting one second
+    fn default() -> QosScheduler {
+        QosScheduler::new(Duration::from_secs(1))
+    }
+}
+
+impl Scheduler for QosScheduler {
+    fn push(&self, job: Job) {
+        let class = job.qos() as usize;
+        self.queues.lock().unwrap_or_else(PoisonError::into_inner)[class].push_back(job);
+    }
+
+    fn pop(&self) -> Option<Job> {
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let background = Qos::Background as usize;
+        let aged = queues[background]
+            .front()
+            .is_some_and(|job| job.enqueued_at().elapsed() >= self.aging);
+        if aged {
+            return queues[background].pop_front();
+        }
+
+        queues.iter_mut().find_map(VecDeque::pop_front)
+    }
+}
+
+/// Greater for an earlier deadline, a missing deadline being the least
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EarliestDeadline(Option<Instant>);
+
+impl Ord for EarliestDeadline {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0, other.0) {
+            (Some(deadline), Some(other)) => other.cmp(&deadline),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for EarliestDeadline {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Job popped greatest key first, and in submission order for an equal key
+#[derive(Debug)]
+struct Ordered<K> {
+    jobs: BinaryHeap<Entry<K>>,
+    next: u64,
+}
+
+impl<K> Default for Ordered<K> {
+    fn default() -> Self {
+        Ordered {
+            jobs: BinaryHeap::new(),
+            next: 0,
+        }
+    }
+}
+
+impl<K: Ord> Ordered<K> {
+    fn push(&mut self, key: K, job: Job) {
+        let seq = self.next;
+        self.next += 1;
+        self.jobs.push(Entry { key, seq, job });
+    }
+
+    fn pop(&mut self) -> Option<Job> {
+        self.jobs.pop().map(|entry| entry.job)
+    }
+}
+
+#[derive(Debug)]
+struct Entry<K> {
+    key: K,
+    seq: u64,
+    job: Job,
+}
+
+impl<K: Ord> Ord for Entry<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key
+            .cmp(&other.key)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl<K: Ord> PartialOrd for Entry<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> PartialEq for Entry<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord> Eq for Entry<K> {}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a job that should run before `deadline`, with [`ThreadPoolBuilder::earliest_deadline_first`](crate::ThreadPoolBuilder::earliest_deadline_first)
+    /// the waiting job with the nearest deadline is always picked first. The deadline is only a scheduling hint,
+    /// a job that miss it still run, and it's ignored by a pool using another scheduling.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .earliest_deadline_first(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let frame = Instant::now() + Duration::from_millis(16);
+    /// pool.execute_with_deadline(frame, || println!("preparing frame")).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_with_deadline<F>(&self, deadline: Instant, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.submit(Job::new(job).with_deadline(deadline))
+    }
+
+    /// Execute a job with a priority used by [`PriorityScheduler`], a higher priority run first.
+    /// It's ignored by a pool using another scheduling, job executed without one have priority `0`.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_with_priority<F>(&self, priority: i32, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.submit(Job::new(job).with_priority(priority))
+    }
+
+    /// Execute a job in a quality of service class used by [`QosScheduler`].
+    /// It's ignored by a pool using another scheduling, job executed without one are [`Qos::Default`].
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_with_qos<F>(&self, qos: Qos, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.submit(Job::new(job).with_qos(qos))
+    }
+}
@@ -0,0 +1,234 @@
+use std::any::Any;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, PoisonError};
+
+use crate::backend::QueueBackend;
+use crate::cancel::CancelToken;
+use crate::context;
+use crate::error::ThreadPoolError;
+use crate::oneshot;
+use crate::shared::Shared;
+use crate::sync::{Condvar, Mutex};
+use crate::{Job, ThreadPool};
+
+/// Scope created by [`ThreadPool::scope`], job executed through it can borrow from the enclosing stack frame
+#[derive(Debug)]
+pub struct Scope<'scope, 'env: 'scope, B: QueueBackend = crate::DefaultBackend> {
+    shared: Arc<Shared<B>>,
+    state: Arc<ScopeState>,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+#[derive(Debug, Default)]
+struct ScopeState {
+    pending: Mutex<usize>,
+    done: Condvar,
+    /// Cancelled once a job panicked, the job that has not started yet are skipped
+    cancelled: CancelToken,
+    /// Payload of the first job that panicked, resumed by [`ThreadPool::scope`]
+    panic: Mutex<Option<Box<dyn Any + Send>>>,
+}
+
+impl ScopeState {
+    fn panicked(&self, payload: Box<dyn Any + Send>) {
+        self.cancelled.cancel();
+        self.panic
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert(payload);
+    }
+}
+
+/// Held by every scoped job, count it as finished even if it's dropped without running
+struct Pending(Arc<ScopeState>);
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        let mut pending = self
+            .0
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *pending -= 1;
+        if *pending == 0 {
+            self.0.done.notify_all();
+        }
+    }
+}
+
+impl<'scope, B: QueueBackend> Scope<'scope, '_, B> {
+    /// Execute a job to worker thread, the job can borrow anything that outlive the scope
+    ///
+    /// The job is skipped if another job of the scope panicked before it started.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::Cancelled`] if a job of the scope already panicked,
+    /// or an [`Err`] if the communication channel between worker thread and main thread is closed.
+    pub fn execute<F>(&'scope self, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'scope,
+    {
+        if self.state.cancelled.is_cancelled() {
+            return Err(ThreadPoolError::Cancelled);
+        }
+
+        *self
+            .state
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) += 1;
+        let pending = Pending(Arc::clone(&self.state));
+
+        let job: Box<dyn FnOnce() + Send + 'scope> = Box::new(move || {
+            let state = Arc::clone(&pending.0);
+            let _pending = pending;
+            if state.cancelled.is_cancelled() {
+                return;
+            }
+
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(job)) {
+                state.panicked(payload);
+            }
+        });
+        // SAFETY: `ThreadPool::scope` doesn't return before every job is finished or dropped,
+        // so the job never outlive what it borrow.
+        let job: Box<dyn FnOnce() + Send + 'static> = unsafe { std::mem::transmute(job) };
+
+        self.shared.submit(Job::new(job))
+    }
+
+    /// Token cancelled once a job of the scope panicked, so a long running job can stop early
+    pub fn cancel_token(&self) -> CancelToken {
+        self.state.cancelled.clone()
+    }
+
+    /// Block until every job of the scope is finished, a worker of the same pool run the queued job meanwhile
+    fn wait(&self) {
+        let pending = || {
+            self.state
+                .pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+
+        if let Some((pool, index)) = context::worker_of(context::pool_id(&self.shared)) {
+            oneshot::help_until(&*pool, index, || *pending() == 0);
+            return;
+        }
+
+        let mut pending = pending();
+        while *pending > 0 {
+            pending = self
+                .state
+                .done
+                .wait(pending)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Create a [`Scope`] where job can borrow non-`'static` data, it return once every job
+    /// executed through the scope has finished.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// let counter = AtomicUsize::new(0);
+    ///
+    /// pool.scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         scope.execute(|| {
+    ///             counter.fetch_add(1, Ordering::SeqCst);
+    ///         }).unwrap();
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(counter.load(Ordering::SeqCst), 4);
+    /// ```
+    ///
+    /// ## Panic
+    ///
+    /// Will resume the panic of the first job that panicked, the job that has not started yet are skipped
+    /// and the scope return once the running one has finished.
+    pub fn scope<'env, F, R>(&self, f: F) -> R
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env, B>) -> R,
+    {
+        let scope = Scope {
+            shared: Arc::clone(&self.shared),
+            state: Arc::default(),
+            scope: PhantomData,
+            env: PhantomData,
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+        scope.wait();
+
+        let panic = scope
+            .state
+            .panic
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        match (result, panic) {
+            (Err(payload), _) | (Ok(_), Some(payload)) => panic::resume_unwind(payload),
+            (Ok(result), None) => result,
+        }
+    }
+
+    /// Split `slice` into chunk of `chunk_size` element and call `f` on every chunk in parallel,
+    /// it return once every chunk has been processed.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///     let mut pixels = vec![0u8; 1024];
+    ///
+    ///     pool.for_each_chunk(&mut pixels, 256, |chunk| chunk.fill(255))?;
+    ///     assert!(pixels.iter().all(|pixel| *pixel == 255));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed, the chunk that were already sent are still processed.
+    ///
+    /// ## Panic
+    ///
+    /// Will panic if `chunk_size` is 0 or if `f` panicked.
+    pub fn for_each_chunk<T, F>(
+        &self,
+        slice: &mut [T],
+        chunk_size: usize,
+        f: F,
+    ) -> Result<(), ThreadPoolError>
+    where
+        T: Send,
+        F: Fn(&mut [T]) + Sync,
+    {
+        let f = &f;
+
+        self.scope(|scope| {
+            slice
+                .chunks_mut(chunk_size)
+                .try_for_each(|chunk| scope.execute(move || f(chunk)))
+        })
+    }
+}
@@ -0,0 +1,133 @@
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::join_set::JoinSet;
+
+/// Block until the first of `handles` finish and return it's position and result,
+/// [`None`] if there are no handle. The other job keep running in the background.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, select, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(2).unwrap();
+///
+///     let replicas = ["replica-a", "replica-b"]
+///         .into_iter()
+///         .map(|replica| pool.spawn(move || replica))
+///         .collect::<Result<Vec<_>, _>>()?;
+///
+///     let (index, result) = select(replicas).unwrap();
+///     println!("replica {index} answered first with {}", result?);
+///
+///     Ok(())
+/// }
+/// ```
+pub fn select<T>(
+    handles: impl IntoIterator<Item = JobHandle<T>>,
+) -> Option<(usize, Result<T, ThreadPoolError>)> {
+    JoinSet::from_iter(handles).join_next()
+}
+
+/// Same as [`select`] but the job that are still queued once the first one finish are cancelled,
+/// they never run. Job that already started keep running in the background.
+///
+/// Useful for hedged request where only the fastest answer matter.
+pub fn select_cancel<T>(
+    handles: impl IntoIterator<Item = JobHandle<T>>,
+) -> Option<(usize, Result<T, ThreadPoolError>)> {
+    let mut set = JoinSet::from_iter(handles);
+    let first = set.join_next();
+    set.cancel_queued();
+
+    first
+}
+
+/// Block until every job of `handles` finish and return their result in the same order as `handles`
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{join_all, ThreadPool};
+///
+/// let pool = ThreadPool::new(4).unwrap();
+///
+/// let pages = (1..=4)
+///     .map(|page| pool.spawn(move || format!("page {page}")))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// for page in join_all(pages) {
+///     println!("{}", page.unwrap());
+/// }
+/// ```
+pub fn join_all<T>(
+    handles: impl IntoIterator<Item = JobHandle<T>>,
+) -> Vec<Result<T, ThreadPoolError>> {
+    handles.into_iter().map(JobHandle::wait).collect()
+}
+
+/// Block until every job of `handles` finish and return their value in the same order as `handles`,
+/// or return the first error as soon as a job fail. The other job keep running in the background.
+///
+/// ## Errors
+///
+/// This function will return the error of the first job that finish with one, like
+/// [`ThreadPoolError::JobPanicked`] if it panicked or [`ThreadPoolError::Cancelled`] if it was aborted.
+pub fn try_join_all<T>(
+    handles: impl IntoIterator<Item = JobHandle<T>>,
+) -> Result<Vec<T>, ThreadPoolError> {
+    try_join(JoinSet::from_iter(handles), false)
+}
+
+/// Same as [`try_join_all`] but the job that are still queued once one fail are cancelled,
+/// they never run. Job that already started keep running in the background.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{error::ThreadPoolError, try_join_all_cancel, ThreadPool};
+///
+/// fn main() -> Result<(), ThreadPoolError> {
+///     let pool = ThreadPool::new(2).unwrap();
+///
+///     let chunks = (0..16)
+///         .map(|chunk| pool.spawn(move || chunk * 2))
+///         .collect::<Result<Vec<_>, _>>()?;
+///
+///     // Nothing is left running for an upload that already failed
+///     let uploaded = try_join_all_cancel(chunks)?;
+///     assert_eq!(uploaded.len(), 16);
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// ## Errors
+///
+/// Same as [`try_join_all`].
+pub fn try_join_all_cancel<T>(
+    handles: impl IntoIterator<Item = JobHandle<T>>,
+) -> Result<Vec<T>, ThreadPoolError> {
+    try_join(JoinSet::from_iter(handles), true)
+}
+
+fn try_join<T>(mut set: JoinSet<T>, cancel: bool) -> Result<Vec<T>, ThreadPoolError> {
+    let mut values: Vec<Option<T>> = (0..set.len()).map(|_| None).collect();
+
+    while let Some((id, result)) = set.join_next() {
+        match result {
+            Ok(value) => values[id] = Some(value),
+            Err(err) => {
+                if cancel {
+                    set.cancel_queued();
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    // Every handle has been joined with a value
+    Ok(values.into_iter().flatten().collect())
+}
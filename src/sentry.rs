@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use sentry_core::protocol::{Event, Exception, Level, Mechanism, Value};
+use sentry_core::Hub;
+
+use crate::report::{JobPanic, PanicReporter};
+
+/// [`PanicReporter`] sending every panicking job to Sentry as an unhandled exception
+///
+/// The event is captured on the hub that was current when the reporter is created,
+/// since the worker thread doesn't share the hub of the thread that configured Sentry.
+/// The job name and worker index are attached as the `job` and `worker` tag.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{SentryReporter, ThreadPoolBuilder};
+///
+/// // With the guard returned by `sentry::init` alive
+/// let pool = ThreadPoolBuilder::new(4)
+///     .panic_reporter(SentryReporter::new())
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SentryReporter {
+    hub: Arc<Hub>,
+}
+
+impl SentryReporter {
+    /// Creates a new [`SentryReporter`] capturing on the current hub
+    pub fn new() -> SentryReporter {
+        SentryReporter::with_hub(Hub::current())
+    }
+
+    /// Creates a new [`SentryReporter`] capturing on `hub`
+    pub fn with_hub(hub: Arc<Hub>) -> SentryReporter {
+        SentryReporter { hub }
+    }
+}
+
+impl Default for SentryReporter {
+    fn default() -> SentryReporter {
+        SentryReporter::new()
+    }
+}
+
+impl PanicReporter for SentryReporter {
+    fn report(&self, panic: &JobPanic<'_>) {
+        let details = panic.details;
+        let mut event = Event {
+            level: Level::Fatal,
+            timestamp: details.at,
+            exception: vec![Exception {
+                ty: String::from("panic"),
+                value: details.message.clone(),
+                mechanism: Some(Mechanism {
+                    ty: String::from("panic"),
+                    handled: Some(false),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        if let Some(job) = &details.job {
+            event.tags.insert(String::from("job"), job.clone());
+        }
+        if let Some(worker) = panic.worker {
+            event
+                .tags
+                .insert(String::from("worker"), worker.to_string());
+        }
+        if let Some(backtrace) = &details.backtrace {
+            event
+                .extra
+                .insert(String::from("backtrace"), Value::from(backtrace.as_str()));
+        }
+
+        self.hub.capture_event(event);
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{self, ErrorKind};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::backend::QueueBackend;
+use crate::cancel::CancelToken;
+use crate::error::ThreadPoolError;
+use crate::wait_group::WaitGroup;
+use crate::ThreadPool;
+
+/// How often the accept loop check if the pool started draining while there are no connection
+const ACCEPT_POLL: Duration = Duration::from_millis(10);
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Run the accept loop of `listener` on the calling thread, every connection is handled by `handler` on a worker
+    ///
+    /// Once the pool is put in drain mode, by [`ThreadPool::drain`] or a signal, the loop stop accepting
+    /// and return after every connection in flight has been handled. A connection that cannot be
+    /// submitted, for example when the queue is full, is closed right away.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::io::Write;
+    /// use std::net::TcpListener;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///     let listener = TcpListener::bind("127.0.0.1:8000")?;
+    ///
+    ///     pool.serve(listener, |mut stream| {
+    ///         let response = "HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nHi!";
+    ///         let _ = stream.write_all(response.as_bytes());
+    ///     })?;
+    ///
+    ///     pool.shutdown();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the listener cannot be switched to non blocking mode
+    /// or accepting fail for another reason than an aborted connection.
+    pub fn serve<F>(&self, listener: TcpListener, handler: F) -> io::Result<()>
+    where
+        F: Fn(TcpStream) + Send + Sync + 'static,
+    {
+        let in_flight = WaitGroup::new();
+        let result = self.accept_loop(listener, &in_flight, handler);
+        in_flight.wait();
+
+        result
+    }
+
+    /// Same as [`ThreadPool::serve`] but the connection still running `grace` after the pool
+    /// started draining get their [`CancelToken`] cancelled and are abandoned, so a stuck client
+    /// cannot hold the shutdown forever.
+    ///
+    /// The abandoned handler keep running on their worker until they notice the token.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::io::Read;
+    /// use std::net::TcpListener;
+    /// use std::time::Duration;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let pool = ThreadPool::new(2).unwrap();
+    ///     let listener = TcpListener::bind("127.0.0.1:8000")?;
+    ///
+    ///     pool.serve_with_grace(listener, Duration::from_secs(5), |mut stream, token| {
+    ///         let _ = stream.set_read_timeout(Some(Duration::from_millis(100)));
+    ///         let mut buffer = [0; 1024];
+    ///         while !token.is_cancelled() {
+    ///             match stream.read(&mut buffer) {
+    ///                 Ok(0) => break,
+    ///                 Ok(read) => println!("received {read} byte"),
+    ///                 Err(_) => continue,
+    ///             }
+    ///         }
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the listener cannot be switched to non blocking mode
+    /// or accepting fail for another reason than an aborted connection.
+    pub fn serve_with_grace<F>(
+        &self,
+        listener: TcpListener,
+        grace: Duration,
+        handler: F,
+    ) -> io::Result<()>
+    where
+        F: Fn(TcpStream, &CancelToken) + Send + Sync + 'static,
+    {
+        let in_flight = WaitGroup::new();
+        let token = CancelToken::default();
+
+        let connection_token = token.clone();
+        let result = self.accept_loop(listener, &in_flight, move |stream| {
+            handler(stream, &connection_token)
+        });
+
+        // The grace period start once the loop stopped accepting
+        if !in_flight.wait_timeout(grace) {
+            token.cancel();
+        }
+
+        result
+    }
+
+    /// Accept connection until the pool start draining, the handling job are tracked by `in_flight`
+    fn accept_loop<F>(
+        &self,
+        listener: TcpListener,
+        in_flight: &WaitGroup,
+        handler: F,
+    ) -> io::Result<()>
+    where
+        F: Fn(TcpStream) + Send + Sync + 'static,
+    {
+        // Accept doesn't block so the loop notice the drain even when no client connect
+        listener.set_nonblocking(true)?;
+
+        let handler = Arc::new(handler);
+
+        loop {
+            if self.is_draining() {
+                return Ok(());
+            }
+
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_POLL);
+                    continue;
+                }
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        ErrorKind::Interrupted
+                            | ErrorKind::ConnectionAborted
+                            | ErrorKind::ConnectionReset
+                    ) =>
+                {
+                    continue
+                }
+                Err(err) => return Err(err),
+            };
+
+            // Some platform pass the non blocking mode of the listener to the connection
+            if stream.set_nonblocking(false).is_err() {
+                continue;
+            }
+
+            // On any other error the connection is closed as it's dropped with the job
+            let handler = Arc::clone(&handler);
+            let submitted = self.execute_tracked(in_flight, move || handler(stream));
+            if let Err(ThreadPoolError::Draining) = submitted {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,785 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+use crossbeam_utils::CachePadded;
+
+use crate::admission::Admission;
+use crate::backend::QueueBackend;
+use crate::backend::TrySendError;
+use crate::breaker::Breakers;
+use crate::cache::Cache;
+use crate::context::{self, Helper};
+use crate::control::{self, Control};
+use crate::debounce::Debounces;
+use crate::drain::Drain;
+use crate::error::ThreadPoolError;
+use crate::events::{Events, PoolEvent};
+use crate::health::HealthReport;
+use crate::hook::Hook;
+use crate::idle::IdleStrategy;
+use crate::inbox::Inboxes;
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::middleware::Middleware;
+use crate::named::NamedQueues;
+#[cfg(feature = "numa")]
+use crate::numa::{self, NodeQueues};
+use crate::permit::Permits;
+#[cfg(feature = "priority")]
+use crate::priority::ThreadPriority;
+use crate::propagate::Propagate;
+use crate::report::PanicReporter;
+use crate::room::Room;
+use crate::scheduler::Scheduler;
+use crate::singleflight::Flights;
+use crate::spawner::ThreadSpawner;
+use crate::starvation::Starvation;
+use crate::stats::Stats;
+use crate::supervisor::Supervisor;
+use crate::sync::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Mutex};
+use crate::watch::Watch;
+use crate::watchdog::Watchdog;
+use crate::worker::{self, Worker};
+use crate::{Job, Message, RejectionPolicy, ThreadPoolBuilder};
+
+/// State shared between the [`ThreadPool`](crate::ThreadPool) and it's workers
+#[derive(Debug)]
+pub(crate) struct Shared<B: QueueBackend> {
+    pub(crate) queue: B,
+    /// Submitter waiting for room in the queue, see [`Shared::wait_for_room`]
+    pub(crate) room: Room,
+    pub(crate) stats: Stats,
+    pub(crate) idle: IdleStrategy,
+    pub(crate) batch_size: usize,
+    pub(crate) max_jobs_per_worker: Option<usize>,
+    pub(crate) run_queue: Option<Hook<dyn Scheduler>>,
+    pub(crate) named: NamedQueues,
+    #[cfg(feature = "numa")]
+    pub(crate) numa: Option<NodeQueues>,
+    #[cfg(feature = "priority")]
+    pub(crate) priority: Option<ThreadPriority>,
+    #[cfg(feature = "rename")]
+    pub(crate) rename: bool,
+    pub(crate) workers: Mutex<Vec<Worker>>,
+    /// Job queue of every worker, indexed by the worker index
+    pub(crate) inboxes: Inboxes,
+    /// Worker thread that are still running
+    pub(crate) live_workers: CachePadded<AtomicUsize>,
+    /// Worker thread that are waiting for a job, updated whenever a worker go idle so it get it's own cache line
+    pub(crate) idle_workers: CachePadded<AtomicUsize>,
+    /// How many worker has been spawned since the pool is created, used as the worker index
+    pub(crate) spawned_workers: AtomicUsize,
+    core_workers: AtomicUsize,
+    max_workers: AtomicUsize,
+    /// Keep alive duration in nanosecond
+    keep_alive: AtomicU64,
+    pub(crate) lazy: bool,
+    rejection: AtomicU8,
+    /// Queue length from which the submitting thread run the job itself, see [`ThreadPoolBuilder::caller_runs`]
+    caller_runs: Option<usize>,
+    pub(crate) name: Option<String>,
+    pub(crate) thread_name: Mutex<Option<String>>,
+    pub(crate) spawner: Hook<dyn ThreadSpawner>,
+    propagators: Vec<Hook<dyn Propagate>>,
+    pub(crate) controls: Vec<Hook<dyn Control>>,
+    pub(crate) layers: Vec<Hook<dyn Middleware>>,
+    pub(crate) breakers: Breakers,
+    pub(crate) permits: Permits,
+    pub(crate) flights: Arc<Flights>,
+    pub(crate) debounces: Debounces,
+    pub(crate) cache: Arc<Cache>,
+    pub(crate) watch: Arc<Watch>,
+    pub(crate) admission: Admission,
+    pub(crate) supervisor: Supervisor,
+    /// When a job last killed it's worker
+    pub(crate) last_panic: Mutex<Option<SystemTime>>,
+    pub(crate) panic_reporter: Option<Hook<dyn PanicReporter>>,
+    on_idle: Option<Hook<dyn Fn() + Send + Sync>>,
+    /// A job has run since the pool was last idle, see [`ThreadPoolBuilder::on_idle`]
+    busy: AtomicBool,
+    pub(crate) drain: Drain,
+    pub(crate) events: Arc<Events>,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: Metrics,
+    starvation: Option<Arc<Starvation>>,
+    pub(crate) watchdog: Arc<Watchdog>,
+    #[cfg(all(feature = "signal", unix))]
+    signal_handles: Mutex<Vec<signal_hook::iterator::Handle>>,
+    stopping: AtomicBool,
+    closed: AtomicBool,
+}
+
+impl<B: QueueBackend> Shared<B> {
+    pub(crate) fn new(queue: B, builder: &ThreadPoolBuilder) -> Shared<B> {
+        let events = Arc::<Events>::default();
+        let watchdog = Arc::<Watchdog>::default();
+        let starvation = builder.starvation.clone().map(|(threshold, hook)| {
+            Arc::new(Starvation::new(
+                threshold,
+                hook,
+                Arc::clone(&events),
+                Arc::clone(&watchdog),
+            ))
+        });
+
+        Shared {
+            queue,
+            room: Room::default(),
+            stats: Stats::new(builder.job_duration, builder.panic_backtrace),
+            idle: builder.idle,
+            batch_size: builder.batch_size,
+            max_jobs_per_worker: builder.max_jobs_per_worker,
+            run_queue: builder.run_queue(),
+            named: NamedQueues::new(builder.named_queues.clone()),
+            #[cfg(feature = "numa")]
+            numa: builder.numa.then(|| {
+                NodeQueues::new(numa::nodes(), builder.steal_strategy, builder.steal_batch)
+            }),
+            #[cfg(feature = "priority")]
+            priority: builder.priority,
+            #[cfg(feature = "rename")]
+            rename: builder.rename,
+            workers: Mutex::new(Vec::with_capacity(builder.core_workers)),
+            inboxes: Inboxes::default(),
+            live_workers: CachePadded::new(AtomicUsize::new(0)),
+            idle_workers: CachePadded::new(AtomicUsize::new(0)),
+            spawned_workers: AtomicUsize::new(0),
+            core_workers: AtomicUsize::new(builder.core_workers),
+            max_workers: AtomicUsize::new(builder.max_workers.max(builder.core_workers)),
+            keep_alive: AtomicU64::new(duration_to_nanos(builder.keep_alive)),
+            lazy: builder.lazy,
+            rejection: AtomicU8::new(builder.rejection as u8),
+            caller_runs: builder.caller_runs,
+            name: builder.name.clone(),
+            thread_name: Mutex::new(builder.thread_name.clone()),
+            spawner: builder.spawner.clone(),
+            propagators: builder.propagators.clone(),
+            controls: builder.controls.clone(),
+            layers: builder.layers.clone(),
+            breakers: Breakers::new(&builder.circuit_breakers),
+            permits: Permits::default(),
+            flights: Arc::default(),
+            debounces: Debounces::default(),
+            cache: Arc::default(),
+            watch: Arc::new(Watch::new(builder.watermarks.clone())),
+            admission: Admission::default(),
+            supervisor: Supervisor::new(builder.restart_policy, builder.on_restart.clone()),
+            last_panic: Mutex::new(None),
+            panic_reporter: builder.panic_reporter.clone(),
+            on_idle: builder.on_idle.clone(),
+            busy: AtomicBool::new(false),
+            drain: Drain::default(),
+            events,
+            #[cfg(feature = "metrics")]
+            metrics: Metrics::new(builder.name.as_deref().unwrap_or("default")),
+            starvation,
+            watchdog,
+            #[cfg(all(feature = "signal", unix))]
+            signal_handles: Mutex::default(),
+            stopping: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn core_workers(&self) -> usize {
+        self.core_workers.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn max_workers(&self) -> usize {
+        self.max_workers.load(Ordering::SeqCst)
+    }
+
+    /// Change the worker count, `max_workers` is never lower than `core_workers`
+    pub(crate) fn set_workers(&self, core_workers: usize, max_workers: usize) {
+        self.core_workers.store(core_workers, Ordering::SeqCst);
+        self.max_workers
+            .store(max_workers.max(core_workers), Ordering::SeqCst);
+    }
+
+    pub(crate) fn keep_alive(&self) -> Duration {
+        Duration::from_nanos(self.keep_alive.load(Ordering::SeqCst))
+    }
+
+    pub(crate) fn set_keep_alive(&self, keep_alive: Duration) {
+        self.keep_alive
+            .store(duration_to_nanos(keep_alive), Ordering::SeqCst);
+    }
+
+    pub(crate) fn rejection(&self) -> RejectionPolicy {
+        match self.rejection.load(Ordering::SeqCst) {
+            policy if policy == RejectionPolicy::Reject as u8 => RejectionPolicy::Reject,
+            _ => RejectionPolicy::Block,
+        }
+    }
+
+    pub(crate) fn set_rejection(&self, policy: RejectionPolicy) {
+        self.rejection.store(policy as u8, Ordering::SeqCst);
+    }
+
+    /// Hand a job to the worker, either directly through the queue
+    /// or through the [`Scheduler`] when the pool reorder the job
+    pub(crate) fn submit(self: &Arc<Self>, job: Job) -> Result<(), ThreadPoolError> {
+        self.submit_waiting(job, self.rejection_wait())
+    }
+
+    /// How long [`Shared::submit`] wait for room according to the [`RejectionPolicy`]
+    pub(crate) fn rejection_wait(&self) -> Option<Duration> {
+        match self.rejection() {
+            RejectionPolicy::Block => None,
+            RejectionPolicy::Reject => Some(Duration::ZERO),
+        }
+    }
+
+    /// Same as [`Shared::submit`] but wait up to `wait` for room in a bounded queue, [`None`] wait as long as needed
+    ///
+    /// A zero `wait` fail with [`ThreadPoolError::QueueFull`], otherwise with [`ThreadPoolError::Timeout`].
+    pub(crate) fn submit_waiting(
+        self: &Arc<Self>,
+        job: Job,
+        wait: Option<Duration>,
+    ) -> Result<(), ThreadPoolError> {
+        self.check_accepting()?;
+        self.enqueue(self.propagate(job), wait)
+    }
+
+    /// Same as [`Shared::submit_waiting`] for a job already wrapped by [`Shared::propagate`]
+    pub(crate) fn enqueue(
+        self: &Arc<Self>,
+        job: Job,
+        wait: Option<Duration>,
+    ) -> Result<(), ThreadPoolError> {
+        if self.is_inline() {
+            self.run_inline(job);
+            return Ok(());
+        }
+        self.spawn_on_demand()?;
+        if self.is_saturated() {
+            self.run_inline(job);
+            return Ok(());
+        }
+
+        if let Some(capacity) = self.queue.capacity() {
+            let len = self.queue.len();
+            if len >= capacity {
+                self.events
+                    .emit(|| PoolEvent::QueueSaturated { len, capacity });
+            }
+        }
+
+        let queued = self.events.job_queued(&job);
+        let full = match wait {
+            Some(wait) if wait.is_zero() => ThreadPoolError::QueueFull,
+            _ => ThreadPoolError::Timeout,
+        };
+
+        let message = match &self.run_queue {
+            Some(run_queue) => {
+                // The job cannot be taken back once it's in the run queue, wait for room first
+                if let Some(wait) = wait {
+                    if !self.wait_for_room(wait) {
+                        return Err(full);
+                    }
+                }
+
+                run_queue.push(job);
+                Message::Scheduled
+            }
+            None => Message::NewJob(job),
+        };
+
+        let sent = match (wait, message) {
+            (Some(wait), message @ Message::NewJob(_)) if wait.is_zero() => {
+                self.queue.try_send(message)
+            }
+            (Some(wait), message @ Message::NewJob(_)) => self.queue.send_timeout(message, wait),
+            (_, message) => self.queue.send(message).map_err(TrySendError::Closed),
+        };
+
+        sent.map_err(|err| match err {
+            TrySendError::Full(_) => full,
+            TrySendError::Closed(_) => ThreadPoolError::QueueClosed,
+        })?;
+        self.queued(queued);
+
+        Ok(())
+    }
+
+    /// Hand a job to a named queue, waiting for room according to the [`RejectionPolicy`]
+    pub(crate) fn submit_named(
+        self: &Arc<Self>,
+        queue: usize,
+        job: Job,
+    ) -> Result<(), ThreadPoolError> {
+        self.check_accepting()?;
+        let job = self.propagate(job);
+        if self.is_inline() {
+            self.run_inline(job);
+            return Ok(());
+        }
+        self.spawn_on_demand()?;
+
+        let queued = self.events.job_queued(&job);
+        match self.rejection() {
+            RejectionPolicy::Reject => self
+                .named
+                .push(queue, job)
+                .map_err(|_| ThreadPoolError::QueueFull)?,
+            // Don't wait forever for a pool that is shutting down
+            RejectionPolicy::Block => self
+                .named
+                .push_waiting(queue, job, || self.check_accepting())?,
+        }
+
+        self.queue
+            .send(Message::Scheduled)
+            .map_err(|_| ThreadPoolError::QueueClosed)?;
+        self.queued(queued);
+
+        Ok(())
+    }
+
+    /// Report a job that has been handed to the worker
+    fn queued(&self, event: Option<PoolEvent>) {
+        self.events.send(event);
+        self.watch.queue_depth(|| self.queue.len());
+        #[cfg(feature = "metrics")]
+        self.metrics.job_queued(self.queue.len());
+    }
+
+    /// Wait up to `wait` until the queue has room, returns `false` if it's still full
+    fn wait_for_room(&self, wait: Duration) -> bool {
+        self.room.wait(Instant::now() + wait, || self.is_full())
+    }
+
+    /// Wrap the job so the context of every [`ContextPropagator`](crate::ContextPropagator) follow it,
+    /// and so it's reported if it starve when [`ThreadPoolBuilder::on_starvation`] is set
+    pub(crate) fn propagate(&self, job: Job) -> Job {
+        // The first propagator end up as the outermost wrapper so it's installed first
+        let job = self
+            .propagators
+            .iter()
+            .rev()
+            .fold(job, |job, propagator| propagator.wrap(job));
+
+        match &self.starvation {
+            Some(starvation) => starvation.watch(job),
+            None => job,
+        }
+    }
+
+    /// Returns `true` if the pool has no worker and run every job on the caller thread
+    pub(crate) fn is_inline(&self) -> bool {
+        self.max_workers() == 0
+    }
+
+    /// Returns `true` if the caller should run the job itself according to [`ThreadPoolBuilder::caller_runs`]
+    fn is_saturated(&self) -> bool {
+        self.caller_runs.is_some_and(|threshold| {
+            self.queue.len() >= threshold
+                && self.idle_workers.load(Ordering::SeqCst) == 0
+                && self.live_workers.load(Ordering::SeqCst) >= self.max_workers()
+        })
+    }
+
+    /// Run the job on the caller thread, a panic is recorded like a worker that died from it
+    ///
+    /// The caller act as a worker meanwhile, the job it spawn with
+    /// [`JobContext::spawn_local`](crate::JobContext::spawn_local) run right after it.
+    fn run_inline(self: &Arc<Self>, job: Job) {
+        let _context = context::enter_inline(Arc::clone(self) as _);
+        let mut job = Some(job);
+
+        while let Some(job) = job.take().or_else(context::pop_local) {
+            if panic::catch_unwind(AssertUnwindSafe(|| worker::run_job(self, None, job))).is_err() {
+                *self
+                    .last_panic
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some(SystemTime::now());
+            }
+        }
+    }
+
+    /// Refuse new job once the pool is closed or draining, or when the [`AdmissionController`](crate::AdmissionController) reject it
+    pub(crate) fn check_accepting(&self) -> Result<(), ThreadPoolError> {
+        if self.is_closed() {
+            return Err(ThreadPoolError::QueueClosed);
+        }
+
+        if self.drain.is_draining() {
+            return Err(ThreadPoolError::Draining);
+        }
+
+        if self.admission.is_enabled()
+            && !self.admission.admit(
+                self.queue.len(),
+                self.queue.capacity(),
+                self.live_workers.load(Ordering::SeqCst),
+                self.idle_workers.load(Ordering::SeqCst),
+            )
+        {
+            return Err(ThreadPoolError::Overloaded);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if there are no job left and every worker is waiting
+    pub(crate) fn is_drained(&self) -> bool {
+        self.queue.is_empty()
+            && self.inboxes.is_empty()
+            && self.idle_workers.load(Ordering::SeqCst) >= self.live_workers.load(Ordering::SeqCst)
+    }
+
+    /// Mark the pool as busy so it's next idle transition is reported
+    pub(crate) fn job_started(&self) {
+        if self.on_idle.is_some() {
+            self.busy.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Called by a worker that ran out of job, the first one to see the pool drained after a job ran
+    /// call [`ThreadPoolBuilder::on_idle`]
+    pub(crate) fn worker_idle(&self) {
+        self.drain.notify();
+
+        if let Some(on_idle) = &self.on_idle {
+            if self.is_drained() && self.busy.swap(false, Ordering::SeqCst) {
+                on_idle();
+            }
+        }
+    }
+
+    /// Returns `true` if the queue is bounded and has no room left
+    fn is_full(&self) -> bool {
+        self.queue
+            .capacity()
+            .is_some_and(|capacity| self.queue.len() >= capacity)
+    }
+
+    /// Hand a job to the worker of a NUMA node
+    #[cfg(feature = "numa")]
+    pub(crate) fn submit_to_node(
+        self: &Arc<Self>,
+        node: usize,
+        job: Job,
+    ) -> Result<(), ThreadPoolError> {
+        self.check_accepting()?;
+        let job = self.propagate(job);
+        self.spawn_on_demand()?;
+
+        let numa = match &self.numa {
+            Some(numa) if node < numa.nodes.len() => numa,
+            _ => return Err(ThreadPoolError::NoSuchNode { node }),
+        };
+        if self.is_inline() {
+            self.run_inline(job);
+            return Ok(());
+        }
+        let queued = self.events.job_queued(&job);
+        numa.push(node, job);
+
+        self.queue
+            .send(Message::Scheduled)
+            .map_err(|_| ThreadPoolError::QueueClosed)?;
+        self.queued(queued);
+
+        Ok(())
+    }
+
+    /// Hand a job to the worker with this index, the job wait in it's inbox
+    /// if the worker is busy or not running
+    pub(crate) fn submit_to(
+        self: &Arc<Self>,
+        worker: usize,
+        job: Job,
+    ) -> Result<(), ThreadPoolError> {
+        self.check_accepting()?;
+        let job = self.propagate(job);
+        if self.is_inline() {
+            self.run_inline(job);
+            return Ok(());
+        }
+
+        let inbox = self.inboxes.get(worker);
+        let queued = self.events.job_queued(&job);
+        inbox.push(job);
+        self.queued(queued);
+
+        if inbox.is_idle() {
+            self.queue
+                .send(Message::Wake(worker))
+                .map_err(|_| ThreadPoolError::QueueClosed)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pass on a [`Message::Wake`] received by another worker if it's target is still waiting
+    pub(crate) fn forward_wake(&self, worker: usize) {
+        let inbox = self.inboxes.get(worker);
+
+        if inbox.is_idle() && (!inbox.is_empty() || inbox.is_exiting()) {
+            let _ = self.queue.send(Message::Wake(worker));
+            // Give the target a chance to receive it instead of picking it up again
+            std::thread::yield_now();
+        }
+    }
+
+    /// Returns `true` if worker are spawned on demand
+    pub(crate) fn is_elastic(&self) -> bool {
+        self.lazy || self.max_workers() > self.core_workers()
+    }
+
+    /// Retire the calling worker if there are more live worker than the core count
+    ///
+    /// Only the worker with the highest index retire and only once it's inbox is empty,
+    /// so the index below it keep their worker.
+    /// Return `true` if the worker is retired and should exit, it's then no longer counted as live.
+    pub(crate) fn try_retire(&self, worker: usize) -> bool {
+        if !self.inboxes.get(worker).is_empty() || !self.inboxes.is_highest_occupied(worker) {
+            return false;
+        }
+
+        self.live_workers
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| {
+                (live > self.core_workers()).then(|| live - 1)
+            })
+            .is_ok()
+    }
+
+    /// Spawn a worker for the incoming job when the pool is elastic and no worker is idle
+    ///
+    /// Failing to spawn is only reported when there are no worker left to run the job.
+    pub(crate) fn spawn_on_demand(self: &Arc<Self>) -> Result<(), ThreadPoolError> {
+        if !self.is_elastic() || self.idle_workers.load(Ordering::SeqCst) > 0 {
+            return Ok(());
+        }
+
+        match worker::spawn_below(self, self.max_workers()) {
+            Some(Err(source)) if self.live_workers.load(Ordering::SeqCst) == 0 => {
+                Err(ThreadPoolError::SpawnFailed {
+                    index: self.spawned_workers.load(Ordering::SeqCst),
+                    source,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Spawn worker until one own the inbox of `worker`, the job waiting in it would never run otherwise
+    ///
+    /// It's done even on a fixed size pool, a worker that died without replacement leave it's index free.
+    ///
+    /// ## Errors
+    ///
+    /// Return [`ThreadPoolError::SpawnFailed`] if the owner cannot be spawned,
+    /// or [`ThreadPoolError::NoSuchWorker`] if the pool shrunk below the index meanwhile.
+    pub(crate) fn spawn_owner(self: &Arc<Self>, worker: usize) -> Result<(), ThreadPoolError> {
+        // A new worker take the lowest free index, which may not be this one
+        let inbox = self.inboxes.get(worker);
+        while !inbox.is_occupied() {
+            if worker >= self.max_workers() {
+                return Err(ThreadPoolError::NoSuchWorker { worker });
+            }
+
+            match worker::spawn_below(self, self.max_workers()) {
+                Some(Ok(_)) => {}
+                Some(Err(source)) => {
+                    return Err(ThreadPoolError::SpawnFailed {
+                        index: self.spawned_workers.load(Ordering::SeqCst),
+                        source,
+                    });
+                }
+                // Another worker is being spawned or is exiting, it's index is about to be claimed or freed
+                None => std::thread::yield_now(),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pick the job to run for a [`Message::Scheduled`] token received by the worker
+    ///
+    /// `worker` is [`None`] when a thread outside of the pool is helping, it doesn't belong to any node.
+    #[cfg_attr(not(feature = "numa"), allow(unused_variables))]
+    pub(crate) fn next_scheduled(&self, worker: Option<usize>) -> Option<Job> {
+        #[cfg(feature = "numa")]
+        if let Some(job) = self.numa.as_ref().and_then(|numa| match worker {
+            Some(worker) => numa.pop(numa.node_of(worker)),
+            None => numa.pop_any(),
+        }) {
+            return Some(job);
+        }
+
+        if let Some(job) = self
+            .run_queue
+            .as_ref()
+            .and_then(|run_queue| run_queue.pop())
+        {
+            return Some(job);
+        }
+
+        if let Some(job) = self.named.pop() {
+            return Some(job);
+        }
+
+        #[cfg(feature = "numa")]
+        if let Some(job) = self
+            .numa
+            .as_ref()
+            .zip(worker)
+            .and_then(|(numa, worker)| numa.steal(numa.node_of(worker)))
+        {
+            return Some(job);
+        }
+
+        None
+    }
+
+    /// Wait for every worker thread to exit
+    pub(crate) fn join_workers(&self) {
+        let workers =
+            std::mem::take(&mut *self.workers.lock().unwrap_or_else(PoisonError::into_inner));
+
+        for mut worker in workers {
+            if let Some(thread) = worker.take_thread() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    /// Close the queue, no worker can be spawned after this
+    pub(crate) fn close(&self) {
+        {
+            let _workers = self.workers.lock().unwrap_or_else(PoisonError::into_inner);
+            self.closed.store(true, Ordering::SeqCst);
+        }
+
+        #[cfg(all(feature = "signal", unix))]
+        for handle in self
+            .signal_handles
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain(..)
+        {
+            handle.close();
+        }
+
+        self.queue.close();
+        self.named.wake();
+        self.watch.close();
+        self.supervisor.close();
+    }
+
+    /// Put the pool in drain mode, see [`Drain`]
+    pub(crate) fn start_drain(&self) {
+        self.drain.start();
+        self.named.wake();
+        self.watch.notify();
+    }
+
+    /// Keep the handle of a signal listener to stop it when the pool is closed
+    #[cfg(all(feature = "signal", unix))]
+    pub(crate) fn add_signal_handle(&self, handle: signal_hook::iterator::Handle) {
+        self.signal_handles
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(handle);
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn health(&self) -> HealthReport {
+        HealthReport {
+            live_workers: self.live_workers.load(Ordering::SeqCst),
+            core_workers: self.core_workers(),
+            max_workers: self.max_workers(),
+            queue_depth: self.queue.len(),
+            queue_capacity: self.queue.capacity(),
+            last_panic: *self
+                .last_panic
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+            panics: self.stats.panics(),
+            last_panic_details: self.stats.last_panic(),
+            queue_open: !self.is_closed(),
+        }
+    }
+
+    /// Run one queued job on a thread that is waiting for another job, see [`Helper`]
+    ///
+    /// `worker` is the index of the calling worker, [`None`] if it's not a worker of this pool.
+    pub(crate) fn help(&self, worker: Option<usize>) -> bool {
+        if self.is_stopping() {
+            return false;
+        }
+
+        let inbox = worker.map(|worker| self.inboxes.get(worker));
+        if let Some(job) = context::pop_local().or_else(|| inbox?.pop()) {
+            worker::run_job(self, worker, job);
+            return true;
+        }
+
+        let message = self.queue.try_recv();
+        if message.is_ok() {
+            self.room.notify();
+        }
+
+        match message {
+            Ok(Message::NewJob(job)) => worker::run_job(self, worker, job),
+            Ok(Message::Scheduled) => {
+                if let Some(job) = self.next_scheduled(worker) {
+                    worker::run_job(self, worker, job);
+                }
+            }
+            Ok(Message::Wake(target)) if Some(target) == worker => {}
+            Ok(Message::Wake(target)) => {
+                self.forward_wake(target);
+                return false;
+            }
+            Ok(Message::Control(command)) => match worker {
+                Some(worker) => control::dispatch(&self.controls, worker, &*command),
+                // Handler expect to run on a worker, leave it to one
+                None => {
+                    let _ = self.queue.send(Message::Control(command));
+                    return false;
+                }
+            },
+            Ok(Message::Terminate) => {
+                // Leave it to a worker that is not busy waiting
+                let _ = self.queue.send(Message::Terminate);
+                return false;
+            }
+            Err(_) => return false,
+        }
+
+        true
+    }
+
+    /// Tell the worker to stop picking up queued job
+    pub(crate) fn stop(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+}
+
+fn duration_to_nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+impl<B: QueueBackend> Helper for Shared<B> {
+    fn help(&self, worker: Option<usize>) -> bool {
+        Shared::help(self, worker)
+    }
+
+    fn submit_job(self: Arc<Self>, job: Job) -> Result<(), ThreadPoolError> {
+        self.submit(job)
+    }
+}
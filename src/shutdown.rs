@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use crate::backend::QueueBackend;
+use crate::events::PoolEvent;
+use crate::ThreadPool;
+
+/// Step reached by [`ThreadPool::shutdown`], they always happen in this order
+///
+/// Each phase is sent to subscriber as [`PoolEvent::Shutdown`] once it's reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    /// New job are refused with [`ThreadPoolError::Draining`](crate::error::ThreadPoolError::Draining),
+    /// the queued job keep running
+    Draining,
+    /// Every queued job has finished, the worker are still alive
+    Drained,
+    /// Every worker thread has exited
+    Stopped,
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Shut the pool down in order: refuse new job, wait for the queued job to finish, then stop the worker.
+    /// The [`DropPolicy`](crate::DropPolicy) is not used, every queued job run.
+    ///
+    /// Same as [`ThreadPool::shutdown_with`] without a callback.
+    pub fn shutdown(self) {
+        self.shutdown_with(|_| {});
+    }
+
+    /// Same as [`ThreadPool::shutdown`] but `callback` is called on the calling thread as soon as
+    /// every [`ShutdownPhase`] is reached, before moving to the next one.
+    /// It's where an embedder flush what depend on the job before the worker are gone.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{ShutdownPhase, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    /// pool.execute(|| println!("writing the report")).unwrap();
+    ///
+    /// pool.shutdown_with(|phase| {
+    ///     if phase == ShutdownPhase::Drained {
+    ///         println!("every report is written, uploading them");
+    ///     }
+    /// });
+    /// ```
+    pub fn shutdown_with(self, mut callback: impl FnMut(ShutdownPhase)) {
+        // The event are still sent once the pool is gone
+        let events = Arc::clone(&self.shared.events);
+        let mut reached = |phase| {
+            events.emit(|| PoolEvent::Shutdown { phase });
+            callback(phase);
+        };
+
+        // Debounced job are queued before the pool stop accepting job
+        self.shared.flush_debounced();
+        self.shared.start_drain();
+        reached(ShutdownPhase::Draining);
+
+        self.wait_for_drain();
+        reached(ShutdownPhase::Drained);
+
+        self.shared.close();
+        self.shared.join_workers();
+        drop(self);
+        reached(ShutdownPhase::Stopped);
+    }
+}
@@ -0,0 +1,190 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError};
+
+use crate::backend::QueueBackend;
+use crate::cancel::Ticket;
+use crate::error::ThreadPoolError;
+use crate::handle::JobHandle;
+use crate::oneshot::{self, Sender};
+use crate::sync::Mutex;
+use crate::ThreadPool;
+
+/// Job of [`ThreadPool::execute_singleflight`] that has not finished yet, keyed by result type and key
+#[derive(Debug, Default)]
+pub(crate) struct Flights {
+    flights: Mutex<HashMap<(TypeId, String), Box<dyn Any + Send>>>,
+}
+
+/// Every handle waiting for the same execution
+struct Flight<T> {
+    waiters: Vec<(Sender<T>, Arc<Ticket>)>,
+    started: bool,
+}
+
+impl Flights {
+    /// Join the flight of `key` if there are one, otherwise start a new one and return `true`
+    fn join<T: Send + 'static>(
+        &self,
+        key: &(TypeId, String),
+        waiter: (Sender<T>, Arc<Ticket>),
+    ) -> bool {
+        let mut flights = self.flights.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(flight) = flights
+            .get_mut(key)
+            .and_then(|flight| flight.downcast_mut::<Flight<T>>())
+        {
+            // Joining a running flight is like joining a job that already started
+            if flight.started {
+                waiter.1.start();
+            }
+            flight.waiters.push(waiter);
+            return false;
+        }
+
+        flights.insert(
+            key.clone(),
+            Box::new(Flight {
+                waiters: vec![waiter],
+                started: false,
+            }),
+        );
+
+        true
+    }
+
+    /// Mark the flight as started, returns `false` if every handle has been aborted and the job shouldn't run
+    fn start<T: Send + 'static>(&self, key: &(TypeId, String)) -> bool {
+        let mut flights = self.flights.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(flight) = flights
+            .get_mut(key)
+            .and_then(|flight| flight.downcast_mut::<Flight<T>>())
+        else {
+            return false;
+        };
+
+        flight.started = true;
+        let (started, aborted) = std::mem::take(&mut flight.waiters)
+            .into_iter()
+            .partition(|(_, ticket)| ticket.start());
+        flight.waiters = started;
+        for (sender, _) in aborted {
+            sender.cancel();
+        }
+
+        if flight.waiters.is_empty() {
+            flights.remove(key);
+            return false;
+        }
+
+        true
+    }
+
+    fn land<T: Send + 'static>(&self, key: &(TypeId, String)) -> Option<Flight<T>> {
+        let flight = self
+            .flights
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key)?;
+
+        flight.downcast::<Flight<T>>().ok().map(|flight| *flight)
+    }
+}
+
+/// Remove the flight if the job panic, every waiter then see [`ThreadPoolError::JobPanicked`]
+struct Landing<'a, T: Send + 'static> {
+    flights: &'a Flights,
+    key: &'a (TypeId, String),
+    _result: std::marker::PhantomData<T>,
+}
+
+impl<T: Send + 'static> Drop for Landing<'_, T> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            drop(self.flights.land::<T>(self.key));
+        }
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a job unless a job with the same `key` and result type is already queued or running,
+    /// in which case the returned [`JobHandle`] share it's execution and receive a clone of it's result
+    ///
+    /// Useful to refill a cache entry once when many request miss it at the same time.
+    /// Aborting a handle only detach it, the job still run for the other handle.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+    ///
+    /// fn main() -> Result<(), ThreadPoolError> {
+    ///     let pool = ThreadPool::new(4).unwrap();
+    ///
+    ///     let fetch = || String::from("fetched user 42");
+    ///     let first = pool.execute_singleflight("user:42", fetch)?;
+    ///     // Doesn't fetch it again if the first one is still queued or running
+    ///     let second = pool.execute_singleflight("user:42", fetch)?;
+    ///
+    ///     assert_eq!(first.wait()?, second.wait()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_singleflight<F, T>(
+        &self,
+        key: &str,
+        job: F,
+    ) -> Result<JobHandle<T>, ThreadPoolError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Clone + Send + 'static,
+    {
+        let key = (TypeId::of::<T>(), String::from(key));
+        let (sender, receiver) = oneshot::channel();
+        let ticket = Arc::new(Ticket::default());
+        let handle = JobHandle::new(receiver, &self.shared, Arc::clone(&ticket));
+
+        if !self.shared.flights.join(&key, (sender, ticket)) {
+            return Ok(handle);
+        }
+
+        let flights = Arc::clone(&self.shared.flights);
+        let job_key = key.clone();
+        let submitted = self.execute(move || {
+            let key = job_key;
+            if !flights.start::<T>(&key) {
+                return;
+            }
+
+            let landing = Landing::<T> {
+                flights: &flights,
+                key: &key,
+                _result: std::marker::PhantomData,
+            };
+            let value = job();
+            drop(landing);
+
+            let Some(flight) = flights.land::<T>(&key) else {
+                return;
+            };
+            for (sender, _) in flight.waiters {
+                sender.send(value.clone());
+            }
+        });
+
+        // The job is gone, so are the handle that joined it in the meantime
+        if let Err(err) = submitted {
+            drop(self.shared.flights.land::<T>(&key));
+            return Err(err);
+        }
+
+        Ok(handle)
+    }
+}
@@ -0,0 +1,259 @@
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::num::NonZeroUsize;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::error::ThreadPoolError;
+use crate::{DefaultBackend, ThreadPool, ThreadPoolBuilder};
+
+/// How many IO worker are recommended for every CPU
+pub(crate) const IO_WORKERS_PER_CPU: usize = 8;
+
+/// Kind of job a pool is sized for, see [`recommended_workers`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkloadProfile {
+    /// Job keep the CPU busy, one worker per CPU
+    Cpu,
+    /// Job spend most of their time blocked on IO, several worker per CPU
+    Io,
+}
+
+/// Number of CPU the process can use, it respect the cgroup CPU quota on Linux
+pub(crate) fn available_workers() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Recommended number of worker for `profile`, based on the CPU the process is actually allowed to use
+///
+/// On Linux the cgroup v2 `cpu.max` or cgroup v1 `cpu.cfs_quota_us` quota of the process cgroup, or of
+/// it's nearest ancestor that has one, is read so a container limited to 2 CPU on a 64 core host get
+/// 2 CPU worth of worker, elsewhere it fall back to [`std::thread::available_parallelism`].
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{recommended_workers, ThreadPool, WorkloadProfile};
+///
+/// let compute = ThreadPool::new(recommended_workers(WorkloadProfile::Cpu)).unwrap();
+/// let io = ThreadPool::new(recommended_workers(WorkloadProfile::Io)).unwrap();
+/// ```
+pub fn recommended_workers(profile: WorkloadProfile) -> usize {
+    let cpus = match cgroup_cpus() {
+        Some(quota) => quota.min(available_workers()),
+        None => available_workers(),
+    };
+
+    match profile {
+        WorkloadProfile::Cpu => cpus,
+        WorkloadProfile::Io => cpus * IO_WORKERS_PER_CPU,
+    }
+}
+
+/// CPU allowed by the cgroup quota rounded up, [`None`] if there are no quota or it cannot be read
+#[cfg(target_os = "linux")]
+fn cgroup_cpus() -> Option<usize> {
+    let cgroup = fs::read_to_string("/proc/self/cgroup").ok()?;
+
+    cgroup_v2_quota(&cgroup)
+        .or_else(|| cgroup_v1_quota(&cgroup))
+        .and_then(quota_cpus)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cgroup_cpus() -> Option<usize> {
+    None
+}
+
+/// Round a `(quota, period)` pair up to whole CPU, a zero quota or period is ignored
+#[cfg(target_os = "linux")]
+fn quota_cpus((quota, period): (u64, u64)) -> Option<usize> {
+    if quota == 0 || period == 0 {
+        return None;
+    }
+
+    usize::try_from(quota.div_ceil(period)).ok()
+}
+
+/// Path of the process cgroup in the hierarchy holding `controller`, the cgroup v2 one if it's [`None`]
+///
+/// Every line of `/proc/self/cgroup` is formatted as `$ID:$CONTROLLERS:$PATH`, the cgroup v2 line
+/// has the ID `0` and no controller.
+#[cfg(target_os = "linux")]
+fn cgroup_path<'a>(cgroup: &'a str, controller: Option<&str>) -> Option<&'a str> {
+    cgroup.lines().find_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let (id, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+        let found = match controller {
+            Some(controller) => controllers.split(',').any(|name| name == controller),
+            None => id == "0" && controllers.is_empty(),
+        };
+
+        found.then_some(path)
+    })
+}
+
+/// Find the nearest cgroup from `path` up to `root` where `read` find a quota
+///
+/// The quota can be set on any ancestor. Inside a container the path from `/proc/self/cgroup`
+/// may not exist under the mount point, the root of the mount is the container cgroup then.
+#[cfg(target_os = "linux")]
+fn nearest_quota(
+    root: &str,
+    path: &str,
+    read: impl Fn(&Path) -> Option<(u64, u64)>,
+) -> Option<(u64, u64)> {
+    let mut dir = PathBuf::from(root).join(path.trim_start_matches('/'));
+    loop {
+        if let Some(quota) = read(&dir) {
+            return Some(quota);
+        }
+
+        if !dir.pop() || !dir.starts_with(root) {
+            return None;
+        }
+    }
+}
+
+/// Read `cpu.max` of the process cgroup v2
+#[cfg(target_os = "linux")]
+fn cgroup_v2_quota(cgroup: &str) -> Option<(u64, u64)> {
+    let path = cgroup_path(cgroup, None)?;
+
+    nearest_quota("/sys/fs/cgroup", path, |dir| {
+        parse_cpu_max(&fs::read_to_string(dir.join("cpu.max")).ok()?)
+    })
+}
+
+/// Read `cpu.cfs_quota_us` and `cpu.cfs_period_us` of the process cgroup v1 `cpu` controller
+#[cfg(target_os = "linux")]
+fn cgroup_v1_quota(cgroup: &str) -> Option<(u64, u64)> {
+    let path = cgroup_path(cgroup, Some("cpu"))?;
+
+    ["/sys/fs/cgroup/cpu", "/sys/fs/cgroup/cpu,cpuacct"]
+        .iter()
+        .find_map(|root| {
+            nearest_quota(root, path, |dir| {
+                let read = |file: &str| fs::read_to_string(dir.join(file)).ok();
+                parse_cfs(&read("cpu.cfs_quota_us")?, &read("cpu.cfs_period_us")?)
+            })
+        })
+}
+
+/// Parse `cpu.max`, formatted as `$MAX $PERIOD` where `$MAX` is `max` when there are no quota
+#[cfg(target_os = "linux")]
+fn parse_cpu_max(max: &str) -> Option<(u64, u64)> {
+    let mut fields = max.split_whitespace();
+    let quota = fields.next()?.parse().ok()?;
+    let period = fields.next()?.parse().ok()?;
+
+    Some((quota, period))
+}
+
+/// Parse `cpu.cfs_quota_us` and `cpu.cfs_period_us`, a quota of `-1` mean unlimited
+#[cfg(target_os = "linux")]
+fn parse_cfs(quota: &str, period: &str) -> Option<(u64, u64)> {
+    let quota: i64 = quota.trim().parse().ok()?;
+    let period = period.trim().parse().ok()?;
+
+    Some((u64::try_from(quota).ok()?, period))
+}
+
+/// Scale the available CPU by `multiplier` then add `offset`, there are always at least one worker
+fn scaled_workers(multiplier: f64, offset: isize) -> usize {
+    let scaled = (available_workers() as f64 * multiplier).round() as isize;
+
+    scaled.saturating_add(offset).max(1) as usize
+}
+
+impl ThreadPool<DefaultBackend> {
+    /// Creates a new [`ThreadPool`] with one worker per CPU available to the process
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::with_default_size().unwrap();
+    /// println!("running with {} worker", pool.live_workers());
+    /// ```
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker
+    pub fn with_default_size() -> Result<ThreadPool, ThreadPoolError> {
+        ThreadPool::new(available_workers())
+    }
+
+    /// Creates a new [`ThreadPool`] with `multiplier` worker per available CPU plus `offset`,
+    /// there are always at least one worker.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// // Leave one CPU for the main thread
+    /// let compute = ThreadPool::with_scaled_size(1.0, -1).unwrap();
+    /// // Worker mostly wait on IO
+    /// let io = ThreadPool::with_scaled_size(4.0, 0).unwrap();
+    /// ```
+    ///
+    /// ## Error
+    ///
+    /// It will return an [`Err`] if cannot create thread worker
+    pub fn with_scaled_size(multiplier: f64, offset: isize) -> Result<ThreadPool, ThreadPoolError> {
+        ThreadPool::new(scaled_workers(multiplier, offset))
+    }
+}
+
+/// Builder with one worker per CPU available to the process
+impl Default for ThreadPoolBuilder {
+    fn default() -> Self {
+        ThreadPoolBuilder::new(available_workers())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::{cgroup_path, parse_cfs, parse_cpu_max, quota_cpus};
+
+    #[test]
+    fn cpu_max_quota() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("150000 100000\n"), Some((150_000, 100_000)));
+        assert_eq!(parse_cpu_max("150000"), None);
+        assert_eq!(parse_cpu_max("lorem ipsum"), None);
+        assert_eq!(parse_cpu_max(""), None);
+    }
+
+    #[test]
+    fn cfs_quota() {
+        assert_eq!(parse_cfs("-1\n", "100000\n"), None);
+        assert_eq!(parse_cfs("50000\n", "100000\n"), Some((50_000, 100_000)));
+        assert_eq!(parse_cfs("50000", "lorem"), None);
+        assert_eq!(parse_cfs("", "100000"), None);
+    }
+
+    #[test]
+    fn quota_round_up() {
+        assert_eq!(quota_cpus((150_000, 100_000)), Some(2));
+        assert_eq!(quota_cpus((200_000, 100_000)), Some(2));
+        assert_eq!(quota_cpus((10_000, 100_000)), Some(1));
+        assert_eq!(quota_cpus((0, 100_000)), None);
+        assert_eq!(quota_cpus((100_000, 0)), None);
+    }
+
+    #[test]
+    fn process_cgroup_path() {
+        let v1 = "12:memory:/docker/abc\n4:cpu,cpuacct:/docker/abc\n1:name=systemd:/init.scope\n";
+        assert_eq!(cgroup_path(v1, Some("cpu")), Some("/docker/abc"));
+        assert_eq!(cgroup_path(v1, Some("cpuset")), None);
+        assert_eq!(cgroup_path(v1, None), None);
+
+        let v2 = "0::/user.slice/session-1.scope\n";
+        assert_eq!(cgroup_path(v2, None), Some("/user.slice/session-1.scope"));
+        assert_eq!(cgroup_path(v2, Some("cpu")), None);
+    }
+}
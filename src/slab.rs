@@ -0,0 +1,145 @@
+use std::alloc::{self, Layout};
+use std::cell::RefCell;
+use std::iter;
+use std::ptr::NonNull;
+use std::sync::PoisonError;
+
+use crate::sync::{thread_local, Mutex};
+
+/// Size of every size class, closure bigger than the last class use a plain [`Box`]
+const CLASSES: [usize; 5] = [64, 128, 256, 512, 1024];
+
+/// Alignment of every block, closure with bigger alignment use a plain [`Box`]
+const ALIGN: usize = 16;
+
+/// How many free block a thread keep per size class before handing some to the overflow
+const LOCAL_CACHED: usize = 64;
+
+/// How many free block move between a thread and the overflow at once
+const BATCH: usize = 32;
+
+/// How many free block are kept in the overflow per size class
+const MAX_CACHED: usize = 256;
+
+struct Block(NonNull<u8>);
+
+// SAFETY: a free block is just memory, it doesn't point to anything
+unsafe impl Send for Block {}
+
+/// Free block shared by every thread
+///
+/// Job are usually allocated by the submitting thread and freed by a worker,
+/// so block travel from one thread cache to another through it in batch.
+#[cfg(not(loom))]
+static OVERFLOW: [Mutex<Vec<Block>>; CLASSES.len()] =
+    [const { Mutex::new(Vec::new()) }; CLASSES.len()];
+
+#[cfg(loom)]
+loom::lazy_static! {
+    static ref OVERFLOW: [Mutex<Vec<Block>>; CLASSES.len()] =
+        std::array::from_fn(|_| Mutex::new(Vec::new()));
+}
+
+thread_local! {
+    static LOCAL: RefCell<LocalCache> = RefCell::new(LocalCache::default());
+}
+
+/// Free block of the current thread, given to the overflow when the thread exit
+#[derive(Default)]
+struct LocalCache([Vec<Block>; CLASSES.len()]);
+
+impl Drop for LocalCache {
+    fn drop(&mut self) {
+        for (class, free_list) in self.0.iter_mut().enumerate() {
+            spill(class, free_list.drain(..));
+        }
+    }
+}
+
+/// Find the smallest size class that can hold a value with the given size and alignment
+pub(crate) const fn class_of(size: usize, align: usize) -> Option<usize> {
+    if align > ALIGN {
+        return None;
+    }
+
+    let mut class = 0;
+    while class < CLASSES.len() {
+        if size <= CLASSES[class] {
+            return Some(class);
+        }
+        class += 1;
+    }
+
+    None
+}
+
+fn layout_of(class: usize) -> Layout {
+    Layout::from_size_align(CLASSES[class], ALIGN).unwrap()
+}
+
+/// Move free block to the overflow, the one that doesn't fit are deallocated
+fn spill(class: usize, mut blocks: impl Iterator<Item = Block>) {
+    {
+        let mut overflow = OVERFLOW[class]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let room = MAX_CACHED.saturating_sub(overflow.len());
+        overflow.extend(blocks.by_ref().take(room));
+    }
+
+    for Block(ptr) in blocks {
+        // SAFETY: every block is allocated by `acquire` with this layout
+        unsafe { alloc::dealloc(ptr.as_ptr(), layout_of(class)) }
+    }
+}
+
+/// Take a block from the size class, allocating a new one if no free block is left
+pub(crate) fn acquire(class: usize) -> NonNull<u8> {
+    // The thread local is gone while the thread is exiting, a fresh block is allocated then
+    let cached = LOCAL.try_with(|local| {
+        let free_list = &mut local.borrow_mut().0[class];
+        if free_list.is_empty() {
+            let mut overflow = OVERFLOW[class]
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let start = overflow.len().saturating_sub(BATCH);
+            free_list.extend(overflow.drain(start..));
+        }
+
+        free_list.pop()
+    });
+
+    if let Ok(Some(Block(ptr))) = cached {
+        return ptr;
+    }
+
+    let layout = layout_of(class);
+    // SAFETY: the layout is never zero sized
+    let ptr = unsafe { alloc::alloc(layout) };
+
+    NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+}
+
+/// Give back a block to it's size class
+///
+/// ## Safety
+///
+/// `ptr` must come from [`acquire`] with the same `class` and not be used afterward
+pub(crate) unsafe fn release(ptr: NonNull<u8>, class: usize) {
+    let mut block = Some(Block(ptr));
+
+    let _ = LOCAL.try_with(|local| {
+        let free_list = &mut local.borrow_mut().0[class];
+        free_list.extend(block.take());
+
+        if free_list.len() > LOCAL_CACHED {
+            let start = free_list.len() - BATCH;
+            spill(class, free_list.drain(start..));
+        }
+    });
+
+    // The thread local is gone while the thread is exiting
+    if let Some(block) = block {
+        spill(class, iter::once(block));
+    }
+}
@@ -0,0 +1,62 @@
+use std::io;
+use std::thread::{self, JoinHandle};
+
+/// Create the worker thread of a [`ThreadPool`](crate::ThreadPool), see [`ThreadPoolBuilder::spawner`](crate::ThreadPoolBuilder::spawner)
+///
+/// Implement it to spawn the worker through your own mechanism, for example to instrument the thread
+/// or set platform specific attribute before it start running job.
+///
+/// ## Examples
+///
+/// ```rust
+/// use std::{io, thread::{self, JoinHandle}};
+///
+/// use unknownrori_simple_thread_pool::{ThreadPoolBuilder, ThreadSpawner};
+///
+/// struct SmallStack;
+///
+/// impl ThreadSpawner for SmallStack {
+///     fn spawn(
+///         &self,
+///         name: Option<String>,
+///         worker: Box<dyn FnOnce() + Send>,
+///     ) -> io::Result<JoinHandle<()>> {
+///         let builder = thread::Builder::new().stack_size(64 * 1024);
+///         match name {
+///             Some(name) => builder.name(name).spawn(worker),
+///             None => builder.spawn(worker),
+///         }
+///     }
+/// }
+///
+/// let pool = ThreadPoolBuilder::new(2).spawner(SmallStack).build().unwrap();
+/// ```
+pub trait ThreadSpawner: Send + Sync + 'static {
+    /// Start a thread that run `worker` until it return, `name` is set when [`ThreadPoolBuilder::thread_name`](crate::ThreadPoolBuilder::thread_name) is used
+    ///
+    /// ## Error
+    ///
+    /// Return [`Err`] if the thread cannot be created
+    fn spawn(
+        &self,
+        name: Option<String>,
+        worker: Box<dyn FnOnce() + Send>,
+    ) -> io::Result<JoinHandle<()>>;
+}
+
+/// [`ThreadSpawner`] used by default, it spawn the worker using [`std::thread::Builder`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdSpawner;
+
+impl ThreadSpawner for StdSpawner {
+    fn spawn(
+        &self,
+        name: Option<String>,
+        worker: Box<dyn FnOnce() + Send>,
+    ) -> io::Result<JoinHandle<()>> {
+        match name {
+            Some(name) => thread::Builder::new().name(name).spawn(worker),
+            None => thread::Builder::new().spawn(worker),
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! On demand stack capture of the worker, enabled with the `stacks` feature
+//!
+//! On unix the worker is interrupted with `SIGUSR2`, it's signal handler walk it's own stack
+//! and hand the instruction pointer back to the thread that asked for it. The symbol are resolved
+//! afterward outside of the signal handler. Other platform doesn't support it yet.
+//!
+//! Walking the stack is not async-signal-safe, the unwinder may take the dynamic loader lock
+//! or allocate the first time it look at a module. A worker interrupted while it hold one of those
+//! lock, in the middle of loading a library or of an allocation, can deadlock in the handler.
+//! The capture then time out but the worker stay stuck, so it's only meant as a last resort debugging aid.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::backend::QueueBackend;
+use crate::ThreadPool;
+
+/// Stack of a single worker, see [`ThreadPool::dump_stacks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerStack {
+    /// Index of the worker, the same one used by [`ThreadPool::execute_on`]
+    pub index: usize,
+    /// Thread name of the worker
+    pub name: Option<String>,
+    /// Name of the job the worker was running, if it was executed with [`ThreadPool::execute_named`]
+    pub job: Option<String>,
+    /// Frame of the stack from the innermost one, [`None`] if the worker didn't respond in time
+    /// or the platform doesn't support stack capture.
+    /// The first few frame belong to the signal handler that captured it.
+    pub frames: Option<Vec<StackFrame>>,
+}
+
+/// Single frame of a [`WorkerStack`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Instruction pointer of the frame
+    pub ip: usize,
+    /// Demangled name of the function
+    pub symbol: Option<String>,
+    pub file: Option<PathBuf>,
+    pub line: Option<u32>,
+}
+
+impl Display for WorkerStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "worker {} ({})",
+            self.index,
+            self.name.as_deref().unwrap_or("unnamed")
+        ))?;
+        if let Some(job) = &self.job {
+            f.write_fmt(format_args!(" running {job}"))?;
+        }
+
+        let Some(frames) = &self.frames else {
+            return f.write_str(": stack unavailable");
+        };
+
+        for (depth, frame) in frames.iter().enumerate() {
+            f.write_fmt(format_args!(
+                "\n  {depth:>3}: {}",
+                frame.symbol.as_deref().unwrap_or("<unknown>")
+            ))?;
+            if let Some(file) = &frame.file {
+                f.write_fmt(format_args!("\n       at {}", file.display()))?;
+                if let Some(line) = frame.line {
+                    f.write_fmt(format_args!(":{line}"))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Capture the current stack of every running worker, ordered by their index
+    ///
+    /// It's meant for debugging a hang in production, the worker are interrupted one by one
+    /// and a worker that doesn't respond within a second is reported without it's frame.
+    /// On unix it install a `SIGUSR2` handler the first time it's called, replacing any existing one.
+    /// The stack is walked from that handler which is not async-signal-safe, a worker interrupted
+    /// while it's loading a library or allocating may deadlock.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(4).unwrap();
+    ///
+    /// for stack in pool.dump_stacks() {
+    ///     eprintln!("{stack}");
+    /// }
+    /// ```
+    pub fn dump_stacks(&self) -> Vec<WorkerStack> {
+        self.shared
+            .inboxes
+            .occupied()
+            .into_iter()
+            .map(|index| {
+                let inbox = self.shared.inboxes.get(index);
+                WorkerStack {
+                    index,
+                    name: inbox.name(),
+                    job: inbox.job().map(|job| job.to_string()),
+                    frames: inbox.capture_stack(),
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) use os::{capture, OsThread};
+
+#[cfg(unix)]
+mod os {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Mutex, Once, PoisonError};
+    use std::time::{Duration, Instant};
+
+    use super::StackFrame;
+
+    const SIGNAL: libc::c_int = libc::SIGUSR2;
+    const MAX_FRAMES: usize = 128;
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Only one stack is captured at a time since the signal handler share the frame buffer
+    static CAPTURE: Mutex<()> = Mutex::new(());
+    static INSTALL: Once = Once::new();
+    /// Thread the signal is meant for, a late signal from a timed out capture is ignored
+    static TARGET: AtomicUsize = AtomicUsize::new(0);
+    static FRAMES: [AtomicUsize; MAX_FRAMES] = [const { AtomicUsize::new(0) }; MAX_FRAMES];
+    static LEN: AtomicUsize = AtomicUsize::new(0);
+    static DONE: AtomicBool = AtomicBool::new(false);
+
+    /// OS handle of a worker thread
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct OsThread(libc::pthread_t);
+
+    // SAFETY: pthread_t is an opaque identifier, it's only passed back to `pthread_kill`
+    unsafe impl Send for OsThread {}
+    unsafe impl Sync for OsThread {}
+
+    impl OsThread {
+        pub(crate) fn current() -> OsThread {
+            // SAFETY: pthread_self has no precondition
+            OsThread(unsafe { libc::pthread_self() })
+        }
+
+        fn id(self) -> usize {
+            self.0 as usize
+        }
+    }
+
+    extern "C" fn handler(_: libc::c_int) {
+        if TARGET.load(Ordering::Acquire) != OsThread::current().id() {
+            return;
+        }
+
+        let mut len = 0;
+        // SAFETY: only the targeted worker walk it's stack and the capture lock keep other capture out,
+        // the unwinder itself is not async-signal-safe, see the module documentation
+        unsafe {
+            backtrace::trace_unsynchronized(|frame| {
+                FRAMES[len].store(frame.ip() as usize, Ordering::Relaxed);
+                len += 1;
+                len < MAX_FRAMES
+            });
+        }
+
+        LEN.store(len, Ordering::Relaxed);
+        DONE.store(true, Ordering::Release);
+    }
+
+    fn install() {
+        // SAFETY: the sigaction is zeroed then filled with a valid handler and an empty mask
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(SIGNAL, &action, std::ptr::null_mut());
+        }
+    }
+
+    /// Interrupt the thread and walk it's stack, [`None`] if it didn't respond in time
+    ///
+    /// The thread must not exit before this return, see [`Inbox::capture_stack`](crate::inbox::Inbox::capture_stack).
+    pub(crate) fn capture(thread: OsThread) -> Option<Vec<StackFrame>> {
+        let _capture = CAPTURE.lock().unwrap_or_else(PoisonError::into_inner);
+        INSTALL.call_once(install);
+
+        DONE.store(false, Ordering::SeqCst);
+        TARGET.store(thread.id(), Ordering::Release);
+
+        // SAFETY: the caller hold the inbox handle lock which the worker need to clear it before exiting,
+        // so it refer to a running thread until the capture is over
+        let responded = unsafe { libc::pthread_kill(thread.0, SIGNAL) } == 0 && wait();
+        TARGET.store(0, Ordering::Release);
+        if !responded {
+            return None;
+        }
+
+        let len = LEN.load(Ordering::Relaxed);
+        let frames = FRAMES[..len]
+            .iter()
+            .enumerate()
+            .map(|(depth, ip)| resolve(depth, ip.load(Ordering::Relaxed)))
+            .collect();
+
+        Some(frames)
+    }
+
+    fn wait() -> bool {
+        let deadline = Instant::now() + TIMEOUT;
+        while !DONE.load(Ordering::Acquire) {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        true
+    }
+
+    fn resolve(depth: usize, ip: usize) -> StackFrame {
+        let mut frame = StackFrame {
+            ip,
+            symbol: None,
+            file: None,
+            line: None,
+        };
+
+        // Every frame but the innermost one point at the return address, right after the call
+        let address = if depth == 0 { ip } else { ip.saturating_sub(1) };
+        backtrace::resolve(address as *mut std::ffi::c_void, |symbol| {
+            if frame.symbol.is_none() {
+                frame.symbol = symbol.name().map(|name| name.to_string());
+                frame.file = symbol.filename().map(|file| file.to_path_buf());
+                frame.line = symbol.lineno();
+            }
+        });
+
+        frame
+    }
+}
+
+#[cfg(not(unix))]
+mod os {
+    use super::StackFrame;
+
+    /// OS handle of a worker thread, stack capture is not supported on this platform
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) struct OsThread;
+
+    impl OsThread {
+        pub(crate) fn current() -> OsThread {
+            OsThread
+        }
+    }
+
+    pub(crate) fn capture(_: OsThread) -> Option<Vec<StackFrame>> {
+        None
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use crate::events::{Events, PoolEvent};
+use crate::hook::Hook;
+use crate::sync::Mutex;
+use crate::watchdog::Watchdog;
+use crate::Job;
+
+/// Reported to [`ThreadPoolBuilder::on_starvation`](crate::ThreadPoolBuilder::on_starvation)
+/// when a job has been waiting longer than the threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarvedJob {
+    /// Name of the job if it was executed with [`ThreadPool::execute_named`](crate::ThreadPool::execute_named)
+    pub job: Option<String>,
+    /// How long the job has been waiting so far
+    pub waited: Duration,
+}
+
+/// Callback type of [`ThreadPoolBuilder::on_starvation`](crate::ThreadPoolBuilder::on_starvation)
+pub(crate) type StarvationHook = Hook<dyn Fn(&StarvedJob) + Send + Sync>;
+
+/// Keep track of the job that has not started yet and report the one waiting longer than the threshold
+///
+/// Only the oldest waiting job has a timer on the watchdog, so a busy pool doesn't pile up timer.
+#[derive(Debug)]
+pub(crate) struct Starvation {
+    threshold: Duration,
+    hook: StarvationHook,
+    events: Arc<Events>,
+    watchdog: Arc<Watchdog>,
+    waiting: Mutex<Waiting>,
+}
+
+#[derive(Debug, Default)]
+struct Waiting {
+    /// Job that has not started yet keyed by submission order, a reported job is removed
+    jobs: BTreeMap<u64, (Instant, Option<Arc<str>>)>,
+    next_seq: u64,
+    /// A check is scheduled on the watchdog
+    armed: bool,
+}
+
+impl Starvation {
+    pub(crate) fn new(
+        threshold: Duration,
+        hook: StarvationHook,
+        events: Arc<Events>,
+        watchdog: Arc<Watchdog>,
+    ) -> Starvation {
+        Starvation {
+            threshold,
+            hook,
+            events,
+            watchdog,
+            waiting: Mutex::default(),
+        }
+    }
+
+    /// Track the job until it start
+    pub(crate) fn watch(self: &Arc<Self>, job: Job) -> Job {
+        let mut waiting = self.waiting.lock().unwrap_or_else(PoisonError::into_inner);
+        // Taken under the lock so the submission order is also the waiting order
+        let enqueued_at = Instant::now();
+        let seq = waiting.next_seq;
+        waiting.next_seq += 1;
+        waiting.jobs.insert(seq, (enqueued_at, job.shared_name()));
+
+        if !waiting.armed {
+            waiting.armed = true;
+            self.arm(enqueued_at + self.threshold);
+        }
+        drop(waiting);
+
+        let starvation = Arc::downgrade(self);
+        job.wrap(move |job| {
+            if let Some(starvation) = starvation.upgrade() {
+                starvation
+                    .waiting
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .jobs
+                    .remove(&seq);
+            }
+            job.run();
+        })
+    }
+
+    fn arm(self: &Arc<Self>, deadline: Instant) {
+        let starvation: Weak<Starvation> = Arc::downgrade(self);
+        self.watchdog.schedule(deadline, move || {
+            if let Some(starvation) = starvation.upgrade() {
+                starvation.check();
+            }
+        });
+    }
+
+    /// Report every job waiting longer than the threshold and schedule the next check for the oldest remaining one
+    fn check(self: &Arc<Self>) {
+        let now = Instant::now();
+        let mut waiting = self.waiting.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut starved = Vec::new();
+        while let Some(entry) = waiting.jobs.first_entry() {
+            let enqueued_at = entry.get().0;
+            if now.duration_since(enqueued_at) < self.threshold {
+                break;
+            }
+
+            let (_, job) = entry.remove();
+            starved.push(StarvedJob {
+                job: job.map(|job| job.to_string()),
+                waited: now.duration_since(enqueued_at),
+            });
+        }
+
+        match waiting.jobs.first_key_value() {
+            Some((_, (enqueued_at, _))) => self.arm(*enqueued_at + self.threshold),
+            None => waiting.armed = false,
+        }
+        drop(waiting);
+
+        for job in starved {
+            (self.hook)(&job);
+            self.events.emit(|| PoolEvent::JobStarved {
+                job: job.job,
+                waited: job.waited,
+            });
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use crate::histogram::{Histogram, HistogramSummary};
+use crate::sync::{AtomicUsize, Mutex};
+
+/// Counter updated by the worker, read through [`ThreadPool::stats`](crate::ThreadPool::stats)
+#[derive(Debug)]
+pub(crate) struct Stats {
+    pub(crate) queue_wait: Histogram,
+    pub(crate) job_duration: Option<Histogram>,
+    /// Capture the backtrace of panicking job
+    pub(crate) panic_backtrace: bool,
+    /// How many job has panicked since the pool is created, it's not cleared by a reset
+    panics: AtomicUsize,
+    last_panic: Mutex<Option<PanicDetails>>,
+    /// Statistic of the named job, keyed by job name
+    by_name: Mutex<HashMap<Arc<str>, Arc<NamedStats>>>,
+}
+
+/// Counter of every job sharing the same name, see [`ThreadPool::stats_by_name`](crate::ThreadPool::stats_by_name)
+#[derive(Debug)]
+pub(crate) struct NamedStats {
+    completed: AtomicUsize,
+    panicked: AtomicUsize,
+    duration: Histogram,
+}
+
+impl NamedStats {
+    /// Count a job that has finished, the duration of a job that panicked is not recorded
+    pub(crate) fn record(&self, completed: bool, duration: Duration) {
+        if completed {
+            self.completed.fetch_add(1, Ordering::Relaxed);
+            self.duration.record(duration);
+        } else {
+            self.panicked.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self) -> JobNameStats {
+        JobNameStats {
+            completed: self.completed.load(Ordering::Relaxed),
+            panicked: self.panicked.load(Ordering::Relaxed),
+            duration: self.duration.summary(),
+        }
+    }
+}
+
+impl Stats {
+    pub(crate) fn new(job_duration: bool, panic_backtrace: bool) -> Stats {
+        if panic_backtrace {
+            crate::panic_trace::install();
+        }
+
+        Stats {
+            queue_wait: Histogram::new(),
+            job_duration: job_duration.then(Histogram::new),
+            panic_backtrace,
+            panics: AtomicUsize::new(0),
+            last_panic: Mutex::new(None),
+            by_name: Mutex::default(),
+        }
+    }
+
+    /// Count a job that panicked and keep it's detail, returning it
+    pub(crate) fn record_panic(
+        &self,
+        job: Option<&str>,
+        payload: &(dyn Any + Send),
+    ) -> PanicDetails {
+        self.panics.fetch_add(1, Ordering::SeqCst);
+
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned());
+        let details = PanicDetails {
+            message,
+            job: job.map(String::from),
+            at: SystemTime::now(),
+            backtrace: self
+                .panic_backtrace
+                .then(crate::panic_trace::take)
+                .flatten()
+                .map(|backtrace| backtrace.to_string()),
+        };
+        *self
+            .last_panic
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(details.clone());
+
+        details
+    }
+
+    pub(crate) fn panics(&self) -> usize {
+        self.panics.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn last_panic(&self) -> Option<PanicDetails> {
+        self.last_panic
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn snapshot(&self) -> PoolStats {
+        PoolStats {
+            queue_wait: self.queue_wait.summary(),
+            job_duration: self.job_duration.as_ref().map(Histogram::summary),
+            panics: self.panics(),
+            last_panic: self.last_panic(),
+            live_workers: 0,
+            idle_workers: 0,
+            queue_depth: 0,
+            #[cfg(feature = "cputime")]
+            worker_cpu_time: Vec::new(),
+        }
+    }
+
+    /// Statistic of the job named `name`, created on it's first run
+    pub(crate) fn named(&self, name: &Arc<str>) -> Arc<NamedStats> {
+        let mut by_name = self.by_name.lock().unwrap_or_else(PoisonError::into_inner);
+
+        Arc::clone(by_name.entry(Arc::clone(name)).or_insert_with(|| {
+            Arc::new(NamedStats {
+                completed: AtomicUsize::new(0),
+                panicked: AtomicUsize::new(0),
+                duration: Histogram::new(),
+            })
+        }))
+    }
+
+    pub(crate) fn snapshot_by_name(&self) -> HashMap<String, JobNameStats> {
+        self.by_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(name, stats)| (name.to_string(), stats.snapshot()))
+            .collect()
+    }
+
+    pub(crate) fn reset(&self) {
+        self.queue_wait.reset();
+        if let Some(job_duration) = &self.job_duration {
+            job_duration.reset();
+        }
+        self.by_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// Snapshot of the [`ThreadPool`](crate::ThreadPool) statistic
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Time spent by job in the queue between submission and the start of execution
+    pub queue_wait: HistogramSummary,
+    /// Time spent executing the job, only available when enabled with
+    /// [`ThreadPoolBuilder::record_job_duration`](crate::ThreadPoolBuilder::record_job_duration)
+    pub job_duration: Option<HistogramSummary>,
+    /// Number of job that panicked since the pool is created
+    pub panics: usize,
+    /// Detail of the most recent job panic
+    pub last_panic: Option<PanicDetails>,
+    /// Number of worker thread running when the snapshot was taken
+    pub live_workers: usize,
+    /// Number of worker thread waiting for a job when the snapshot was taken
+    pub idle_workers: usize,
+    /// Number of job waiting in the queue when the snapshot was taken
+    pub queue_depth: usize,
+    /// CPU time consumed by every worker indexed by the worker index, as of it's last finished job.
+    /// Comparing it with the time the worker has been alive tell whether it's compute bound or mostly blocked.
+    #[cfg(feature = "cputime")]
+    pub worker_cpu_time: Vec<std::time::Duration>,
+}
+
+/// Statistic of every job executed under the same name, see [`ThreadPool::stats_by_name`](crate::ThreadPool::stats_by_name)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobNameStats {
+    /// Number of job that returned
+    pub completed: usize,
+    /// Number of job that panicked
+    pub panicked: usize,
+    /// Time spent executing the job that returned
+    pub duration: HistogramSummary,
+}
+
+/// Detail of a job that panicked, see [`PoolStats::last_panic`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicDetails {
+    /// Panic message, [`None`] if the panic payload is not a string
+    pub message: Option<String>,
+    /// Name of the job if it was executed with [`ThreadPool::execute_named`](crate::ThreadPool::execute_named)
+    pub job: Option<String>,
+    /// When the job panicked
+    pub at: SystemTime,
+    /// Backtrace of the panic, only captured when enabled with
+    /// [`ThreadPoolBuilder::capture_panic_backtrace`](crate::ThreadPoolBuilder::capture_panic_backtrace)
+    pub backtrace: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl PoolStats {
+    /// Serialize the snapshot to JSON, meant to be dumped as is by a debug endpoint
+    ///
+    /// The schema is stable, new field may be added but existing one are never renamed or removed.
+    /// Every duration is in microsecond and the panic time is in millisecond since the Unix epoch.
+    ///
+    /// ```json
+    /// {
+    ///   "counters": { "panics": 0 },
+    ///   "gauges": { "live_workers": 4, "idle_workers": 3, "queue_depth": 0 },
+    ///   "histograms": {
+    ///     "queue_wait": { "count": 12, "p50_us": 40, "p95_us": 95, "p99_us": 120, "max_us": 130 },
+    ///     "job_duration": null
+    ///   },
+    ///   "last_panic": null
+    /// }
+    /// ```
+    ///
+    /// With the `cputime` feature it also has `"worker_cpu_time_us"`, the CPU time of every worker.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// println!("{}", pool.stats().to_json());
+    /// ```
+    pub fn to_json(&self) -> String {
+        use serde_json::json;
+
+        let micros = |duration: std::time::Duration| duration.as_micros() as u64;
+        let histogram = |summary: &HistogramSummary| {
+            json!({
+                "count": summary.count,
+                "p50_us": micros(summary.p50),
+                "p95_us": micros(summary.p95),
+                "p99_us": micros(summary.p99),
+                "max_us": micros(summary.max),
+            })
+        };
+        let last_panic = self.last_panic.as_ref().map(|panic| {
+            json!({
+                "message": panic.message,
+                "job": panic.job,
+                "at_unix_ms": panic
+                    .at
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |since| since.as_millis() as u64),
+                "backtrace": panic.backtrace,
+            })
+        });
+
+        #[cfg_attr(not(feature = "cputime"), allow(unused_mut))]
+        let mut stats = json!({
+            "counters": {
+                "panics": self.panics,
+            },
+            "gauges": {
+                "live_workers": self.live_workers,
+                "idle_workers": self.idle_workers,
+                "queue_depth": self.queue_depth,
+            },
+            "histograms": {
+                "queue_wait": histogram(&self.queue_wait),
+                "job_duration": self.job_duration.as_ref().map(histogram),
+            },
+            "last_panic": last_panic,
+        });
+        #[cfg(feature = "cputime")]
+        {
+            stats["worker_cpu_time_us"] =
+                self.worker_cpu_time.iter().copied().map(micros).collect();
+        }
+
+        stats.to_string()
+    }
+}
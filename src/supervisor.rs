@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+use crate::hook::Hook;
+use crate::sync::{Condvar, Mutex};
+
+/// Decide whether a worker that died from a panicking job is replaced by a new one
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use unknownrori_simple_thread_pool::{RestartPolicy, ThreadPoolBuilder};
+///
+/// let pool = ThreadPoolBuilder::new(4)
+///     .restart_policy(RestartPolicy::UpTo {
+///         restarts: 3,
+///         within: Duration::from_secs(60),
+///     })
+///     .on_worker_restart(|restart| eprintln!("worker {} restarted", restart.worker))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Dead worker are not replaced, the pool shrink with every panicking job
+    #[default]
+    Never,
+    /// Replace every dead worker immediately
+    Always,
+    /// Replace dead worker immediately, but give up once `restarts` worker
+    /// has been replaced within the `within` period
+    UpTo { restarts: usize, within: Duration },
+    /// Replace dead worker after a delay starting at `initial` which double on every consecutive restart
+    /// up to `max`, the delay go back to `initial` once no worker died for `max` period
+    Backoff { initial: Duration, max: Duration },
+}
+
+/// Reported to [`ThreadPoolBuilder::on_worker_restart`](crate::ThreadPoolBuilder::on_worker_restart)
+/// every time a dead worker is replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerRestart {
+    /// Index of the worker that died
+    pub worker: usize,
+    /// Index of the worker that replace it
+    pub replacement: usize,
+    /// How long the supervisor waited before spawning the replacement
+    pub delay: Duration,
+    /// How many worker has been replaced since the pool is created, including this one
+    pub restarts: usize,
+}
+
+/// Callback type of [`ThreadPoolBuilder::on_worker_restart`](crate::ThreadPoolBuilder::on_worker_restart)
+pub(crate) type RestartHook = Hook<dyn Fn(&WorkerRestart) + Send + Sync>;
+
+/// Keep track of the restart history to apply the [`RestartPolicy`]
+#[derive(Debug)]
+pub(crate) struct Supervisor {
+    policy: RestartPolicy,
+    on_restart: Option<RestartHook>,
+    history: Mutex<History>,
+    /// Signaled once the pool is closed so a pending restart stop waiting
+    closed_changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct History {
+    /// When the recent restart happened, only kept for [`RestartPolicy::UpTo`]
+    recent: VecDeque<Instant>,
+    last: Option<Instant>,
+    streak: u32,
+    total: usize,
+    closed: bool,
+}
+
+impl Supervisor {
+    pub(crate) fn new(policy: RestartPolicy, on_restart: Option<RestartHook>) -> Supervisor {
+        Supervisor {
+            policy,
+            on_restart,
+            history: Mutex::default(),
+            closed_changed: Condvar::new(),
+        }
+    }
+
+    /// Book a restart, returning how long to wait before spawning the replacement
+    ///
+    /// Return [`None`] if the worker should not be replaced.
+    pub(crate) fn next_restart(&self) -> Option<Duration> {
+        let mut history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+
+        let delay = match self.policy {
+            RestartPolicy::Never => return None,
+            RestartPolicy::Always => Duration::ZERO,
+            RestartPolicy::UpTo { restarts, within } => {
+                while history
+                    .recent
+                    .front()
+                    .is_some_and(|restart| now.duration_since(*restart) > within)
+                {
+                    history.recent.pop_front();
+                }
+
+                if history.recent.len() >= restarts {
+                    return None;
+                }
+
+                history.recent.push_back(now);
+                Duration::ZERO
+            }
+            RestartPolicy::Backoff { initial, max } => {
+                if history
+                    .last
+                    .is_some_and(|last| now.duration_since(last) > max)
+                {
+                    history.streak = 0;
+                }
+
+                let delay = initial
+                    .checked_mul(1 << history.streak.min(31))
+                    .map_or(max, |delay| delay.min(max));
+                history.streak = history.streak.saturating_add(1);
+                delay
+            }
+        };
+
+        history.last = Some(now + delay);
+        Some(delay)
+    }
+
+    /// Wait `delay` before spawning a replacement, returns `false` as soon as the pool is closed
+    pub(crate) fn wait(&self, delay: Duration) -> bool {
+        let deadline = Instant::now() + delay;
+        let mut history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while !history.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+
+            history = self
+                .closed_changed
+                .wait_timeout(history, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        false
+    }
+
+    /// Stop every pending restart, no worker is replaced once the pool is closed
+    pub(crate) fn close(&self) {
+        self.history
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .closed = true;
+        self.closed_changed.notify_all();
+    }
+
+    /// Report a replaced worker to the user callback
+    pub(crate) fn restarted(&self, worker: usize, replacement: usize, delay: Duration) {
+        let restarts = {
+            let mut history = self.history.lock().unwrap_or_else(PoisonError::into_inner);
+            history.total += 1;
+            history.total
+        };
+
+        if let Some(on_restart) = &self.on_restart {
+            on_restart(&WorkerRestart {
+                worker,
+                replacement,
+                delay,
+                restarts,
+            });
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Synchronization primitive used by the queue and shutdown logic, they come from `loom`
+//! when the crate is built with `RUSTFLAGS="--cfg loom"` so the logic can be model-checked.
+//!
+//! [`Arc`](std::sync::Arc) always come from the standard library since the pool rely on unsized coercion,
+//! and so do the primitive kept in `static` since `loom` ones cannot be created in a const context,
+//! unless the static is wrapped in `loom::lazy_static!` when built for `loom`.
+
+#[cfg(not(loom))]
+pub(crate) use std::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize},
+    Condvar, Mutex, MutexGuard, RwLock,
+};
+
+#[cfg(loom)]
+pub(crate) use loom::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize},
+    Condvar, Mutex, MutexGuard, RwLock,
+};
+
+#[cfg(not(loom))]
+pub(crate) use std::thread_local;
+
+#[cfg(loom)]
+pub(crate) use loom::thread_local;
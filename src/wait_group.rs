@@ -0,0 +1,173 @@
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::backend::QueueBackend;
+use crate::context;
+use crate::error::ThreadPoolError;
+use crate::oneshot;
+use crate::sync::{Condvar, Mutex};
+use crate::ThreadPool;
+
+/// Wait for an arbitrary set of job to finish, Go style
+///
+/// Every clone count as one more thing to wait for and dropping it mark it as done,
+/// [`WaitGroup::wait`] block until every other clone is dropped.
+/// [`ThreadPool::execute_tracked`] hand a clone to the job and drop it once the job is done.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use unknownrori_simple_thread_pool::{ThreadPool, WaitGroup};
+///
+/// let pool = ThreadPool::new(4).unwrap();
+/// let uploads = WaitGroup::new();
+///
+/// for file in ["a.txt", "b.txt", "c.txt"] {
+///     pool.execute_tracked(&uploads, move || println!("uploading {file}"))
+///         .unwrap();
+/// }
+/// pool.execute(|| println!("not part of the group")).unwrap();
+///
+/// uploads.wait();
+/// println!("every file is uploaded");
+/// ```
+pub struct WaitGroup {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    count: Mutex<usize>,
+    done: Condvar,
+}
+
+impl WaitGroup {
+    /// Creates a new [`WaitGroup`] with nothing to wait for other than itself
+    pub fn new() -> WaitGroup {
+        WaitGroup {
+            inner: Arc::new(Inner {
+                count: Mutex::new(1),
+                done: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Block until every other clone of the group is dropped
+    ///
+    /// When called from a worker thread the worker run the queued job of it's pool while waiting,
+    /// so waiting on job of the same pool cannot deadlock.
+    pub fn wait(self) {
+        self.wait_until(None);
+    }
+
+    /// Same as [`WaitGroup::wait`] but give up after `timeout`,
+    /// returns `true` if every other clone was dropped in time
+    pub fn wait_timeout(self, timeout: Duration) -> bool {
+        self.wait_until(Some(Instant::now() + timeout))
+    }
+
+    fn wait_until(self, deadline: Option<Instant>) -> bool {
+        let inner = Arc::clone(&self.inner);
+        drop(self);
+
+        let remaining = || *inner.count.lock().unwrap_or_else(PoisonError::into_inner);
+        let expired = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+        if let Some((pool, index)) = context::current_worker() {
+            oneshot::help_until(&*pool, index, || remaining() == 0 || expired());
+            return remaining() == 0;
+        }
+
+        let mut count = inner.count.lock().unwrap_or_else(PoisonError::into_inner);
+        while *count > 0 {
+            let left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            count = match left {
+                Some(left) if left.is_zero() => return false,
+                Some(left) => {
+                    inner
+                        .done
+                        .wait_timeout(count, left)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => inner
+                    .done
+                    .wait(count)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+
+        true
+    }
+
+    /// How many clone of the group are alive, including this one
+    pub fn count(&self) -> usize {
+        *self
+            .inner
+            .count
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for WaitGroup {
+    fn clone(&self) -> WaitGroup {
+        *self
+            .inner
+            .count
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) += 1;
+
+        WaitGroup {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl Drop for WaitGroup {
+    fn drop(&mut self) {
+        let mut count = self
+            .inner
+            .count
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *count -= 1;
+        if *count == 0 {
+            self.inner.done.notify_all();
+        }
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> WaitGroup {
+        WaitGroup::new()
+    }
+}
+
+impl core::fmt::Debug for WaitGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitGroup")
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Execute a job that is part of `group`, [`WaitGroup::wait`] doesn't return until it has finished.
+    /// A job that is discarded without running count as done.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return an [`Err`] if the communication channel between worker thread
+    /// and main thread is closed.
+    pub fn execute_tracked<F>(&self, group: &WaitGroup, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let group = group.clone();
+        self.execute(move || {
+            let _group = group;
+            job();
+        })
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError, Weak};
+
+use crate::backend::QueueBackend;
+use crate::hook::Hook;
+use crate::shared::Shared;
+use crate::sync::{AtomicBool, Condvar, Mutex};
+use crate::ThreadPool;
+
+/// Snapshot of the pool sent by [`StateWatch`] whenever one of it's field may have changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolState {
+    /// Number of worker thread that are currently running
+    pub live_workers: usize,
+    /// Number of job waiting in the queue
+    pub queue_depth: usize,
+    /// Returns `true` once the pool is in drain mode
+    pub draining: bool,
+    /// Returns `true` from the moment the queue depth reach the high water mark until it fall back
+    /// to the low water mark, see [`ThreadPoolBuilder::queue_watermarks`](crate::ThreadPoolBuilder::queue_watermarks)
+    pub above_high_water: bool,
+}
+
+/// Called with the queue depth when it cross a watermark
+pub(crate) type WatermarkHook = Hook<dyn Fn(usize) + Send + Sync>;
+
+/// Queue depth thresholds of [`ThreadPoolBuilder::queue_watermarks`](crate::ThreadPoolBuilder::queue_watermarks)
+#[derive(Debug, Clone)]
+pub(crate) struct Watermarks {
+    pub(crate) high: usize,
+    /// Always lower than `high`
+    pub(crate) low: usize,
+    pub(crate) on_high: Option<WatermarkHook>,
+    pub(crate) on_low: Option<WatermarkHook>,
+}
+
+/// Version of the pool state bumped on every material change, shared by every [`StateWatch`]
+#[derive(Debug, Default)]
+pub(crate) struct Watch {
+    version: Mutex<Version>,
+    changed: Condvar,
+    watermarks: Option<Watermarks>,
+    above_high_water: AtomicBool,
+}
+
+#[derive(Debug, Default)]
+struct Version {
+    number: u64,
+    closed: bool,
+}
+
+impl Watch {
+    pub(crate) fn new(watermarks: Option<Watermarks>) -> Watch {
+        Watch {
+            watermarks,
+            ..Watch::default()
+        }
+    }
+
+    /// Wake every [`StateWatch`] so they send a new snapshot
+    pub(crate) fn notify(&self) {
+        self.version
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .number += 1;
+        self.changed.notify_all();
+    }
+
+    /// The pool is closed, [`StateWatch`] stop once they have seen the last change
+    pub(crate) fn close(&self) {
+        let mut version = self.version.lock().unwrap_or_else(PoisonError::into_inner);
+        version.number += 1;
+        version.closed = true;
+        self.changed.notify_all();
+    }
+
+    /// Check the queue depth against the watermarks, notifying when it reach the high one
+    /// or fall back to the low one. Only the thread that flip the state call the callback.
+    pub(crate) fn queue_depth(&self, depth: impl FnOnce() -> usize) {
+        let Some(watermarks) = &self.watermarks else {
+            return;
+        };
+
+        let depth = depth();
+        let (above, callback) = if depth >= watermarks.high {
+            (true, &watermarks.on_high)
+        } else if depth <= watermarks.low {
+            (false, &watermarks.on_low)
+        } else {
+            return;
+        };
+
+        if self.above_high_water.swap(above, Ordering::SeqCst) != above {
+            self.notify();
+            if let Some(callback) = callback {
+                callback(depth);
+            }
+        }
+    }
+
+    pub(crate) fn is_above_high_water(&self) -> bool {
+        self.above_high_water.load(Ordering::SeqCst)
+    }
+
+    /// Block until the version is newer than `seen`, returns [`None`] if the pool is closed and nothing changed
+    fn wait_newer(&self, seen: u64) -> Option<u64> {
+        let mut version = self.version.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while version.number == seen {
+            if version.closed {
+                return None;
+            }
+            version = self
+                .changed
+                .wait(version)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        Some(version.number)
+    }
+
+    fn current(&self) -> u64 {
+        self.version
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .number
+    }
+}
+
+/// Receiver returned by [`ThreadPool::state_watch`], yields a [`PoolState`] whenever the pool state changed
+///
+/// Like a watch channel it only keep the latest state, change that happen while the receiver
+/// is not waiting are coalesced into one snapshot. Iterating over it block until the next change
+/// and end once the pool is closed.
+pub struct StateWatch {
+    watch: Arc<Watch>,
+    seen: u64,
+    snapshot: Box<dyn Fn() -> Option<PoolState> + Send + Sync>,
+}
+
+impl fmt::Debug for StateWatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateWatch")
+            .field("seen", &self.seen)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StateWatch {
+    /// Block until the state change and return the new state, [`None`] once the pool is closed
+    pub fn recv(&mut self) -> Option<PoolState> {
+        self.seen = self.watch.wait_newer(self.seen)?;
+        (self.snapshot)()
+    }
+
+    /// Returns the new state if it changed since the last one received, without blocking
+    pub fn try_recv(&mut self) -> Option<PoolState> {
+        let current = self.watch.current();
+        if current == self.seen {
+            return None;
+        }
+
+        self.seen = current;
+        (self.snapshot)()
+    }
+
+    /// The current state, whether it changed or not
+    pub fn borrow(&self) -> Option<PoolState> {
+        (self.snapshot)()
+    }
+}
+
+impl Iterator for StateWatch {
+    type Item = PoolState;
+
+    fn next(&mut self) -> Option<PoolState> {
+        self.recv()
+    }
+}
+
+impl<B: QueueBackend> Shared<B> {
+    pub(crate) fn state(&self) -> PoolState {
+        PoolState {
+            live_workers: self.live_workers.load(Ordering::SeqCst),
+            queue_depth: self.queue.len(),
+            draining: self.drain.is_draining(),
+            above_high_water: self.watch.is_above_high_water(),
+        }
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Watch the state of the pool, the returned [`StateWatch`] yields a [`PoolState`] whenever
+    /// a worker is spawned or exit, the drain start or the queue cross one of it's watermarks
+    ///
+    /// Only the change made after this call are yielded, [`StateWatch::borrow`] give the current state.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use std::thread;
+    ///
+    /// use unknownrori_simple_thread_pool::ThreadPoolBuilder;
+    ///
+    /// let pool = ThreadPoolBuilder::new(4).high_water_mark(64).build().unwrap();
+    /// let states = pool.state_watch();
+    ///
+    /// thread::spawn(move || {
+    ///     for state in states {
+    ///         if state.above_high_water {
+    ///             eprintln!("{} job are waiting, scale out", state.queue_depth);
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn state_watch(&self) -> StateWatch {
+        let shared: Weak<Shared<B>> = Arc::downgrade(&self.shared);
+
+        StateWatch {
+            watch: Arc::clone(&self.shared.watch),
+            seen: self.shared.watch.current(),
+            snapshot: Box::new(move || shared.upgrade().map(|shared| shared.state())),
+        }
+    }
+}
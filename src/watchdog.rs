@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::sync::{Condvar, Mutex};
+
+/// How long the watchdog thread wait for a new timer before exiting
+const LINGER: Duration = Duration::from_secs(1);
+
+/// Run callback once their deadline has passed, on a thread that is only alive while there are timer
+#[derive(Debug, Default)]
+pub(crate) struct Watchdog {
+    timers: Mutex<Timers>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Timers {
+    heap: BinaryHeap<Timer>,
+    /// Used to keep timer with the same deadline in insertion order
+    next_seq: u64,
+    running: bool,
+}
+
+struct Timer {
+    deadline: Instant,
+    seq: u64,
+    callback: Box<dyn FnOnce() + Send>,
+}
+
+impl core::fmt::Debug for Timer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Timer")
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Timer {}
+
+impl PartialOrd for Timer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timer {
+    /// Reversed so the [`BinaryHeap`] pop the earliest deadline first
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.deadline, other.seq).cmp(&(self.deadline, self.seq))
+    }
+}
+
+impl Watchdog {
+    /// Call `callback` from the watchdog thread once `deadline` has passed
+    pub(crate) fn schedule(
+        self: &Arc<Self>,
+        deadline: Instant,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        let mut timers = self.timers.lock().unwrap_or_else(PoisonError::into_inner);
+        let seq = timers.next_seq;
+        timers.next_seq += 1;
+        timers.heap.push(Timer {
+            deadline,
+            seq,
+            callback: Box::new(callback),
+        });
+
+        if !timers.running {
+            let watchdog = Arc::clone(self);
+            // The timer stay queued and the next one retry if the thread cannot be spawned
+            timers.running = thread::Builder::new()
+                .name(String::from("thread-pool-watchdog"))
+                .spawn(move || watchdog.run())
+                .is_ok();
+        }
+
+        self.changed.notify_one();
+    }
+
+    fn run(&self) {
+        let mut timers = self.timers.lock().unwrap_or_else(PoisonError::into_inner);
+
+        loop {
+            let now = Instant::now();
+            match timers.heap.peek().map(|timer| timer.deadline) {
+                Some(deadline) if deadline <= now => {
+                    let timer = timers.heap.pop().unwrap();
+                    drop(timers);
+                    (timer.callback)();
+                    timers = self.timers.lock().unwrap_or_else(PoisonError::into_inner);
+                }
+                Some(deadline) => {
+                    timers = self
+                        .changed
+                        .wait_timeout(timers, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                None => {
+                    let (lock, timeout) = self
+                        .changed
+                        .wait_timeout(timers, LINGER)
+                        .unwrap_or_else(PoisonError::into_inner);
+                    timers = lock;
+
+                    if timeout.timed_out() && timers.heap.is_empty() {
+                        timers.running = false;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
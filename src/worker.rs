@@ -1,16 +1,21 @@
-#[cfg(feature = "crossbeam")]
-use crossbeam_channel::Receiver;
-
-#[cfg(feature = "mpsc")]
-use std::sync::mpsc::Receiver;
-
-#[cfg(feature = "mpsc")]
-use std::sync::{Arc, Mutex};
-
 use std::io;
-use std::thread::{self, JoinHandle};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Instant, SystemTime};
 
+use crate::backend::{QueueBackend, TryRecvError};
+use crate::context;
+use crate::control;
+use crate::events::PoolEvent;
+use crate::idle::SpinWindow;
+use crate::inbox::Inbox;
 use crate::message::Message;
+use crate::middleware::Next;
+use crate::report::JobPanic;
+use crate::shared::Shared;
+use crate::Job;
 
 #[derive(Debug)]
 pub struct Worker {
@@ -18,51 +23,377 @@ pub struct Worker {
 }
 
 impl Worker {
-    /// Creates a new [`Worker`].
-    ///
-    /// ## Error
-    ///
-    /// Will return [`Err`] if it cannot create a thread
-    #[cfg(feature = "crossbeam")]
-    pub fn new(receiver: Receiver<Message>, thread_builder: thread::Builder) -> io::Result<Worker> {
-        let thread = thread_builder.spawn(move || loop {
-            if let Ok(message) = receiver.recv() {
-                let _ = match message {
-                    Message::NewJob(job) => job(),
-                    Message::Terminate => break,
-                };
-            }
-        })?;
+    /// Take the ownership of [`JoinHandle`]
+    pub fn take_thread(&mut self) -> Option<JoinHandle<()>> {
+        self.thread.take()
+    }
 
-        Ok(Worker {
-            thread: Some(thread),
-        })
+    /// Returns `true` if the worker thread has exited
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
     }
+}
+
+/// Spawn a new worker and register it in the [`Shared`] state
+///
+/// ## Error
+///
+/// Will return [`Err`] if it cannot create a thread or the pool is shutting down
+pub(crate) fn spawn<B: QueueBackend>(shared: &Arc<Shared<B>>) -> io::Result<usize> {
+    shared.live_workers.fetch_add(1, Ordering::SeqCst);
+    spawn_counted(shared)
+}
 
-    /// Creates a new [`Worker`].
-    ///
-    /// ## Error
-    ///
-    /// Will return [`Err`] if it cannot create a thread
-    #[cfg(feature = "mpsc")]
-    pub fn new(
-        receiver: Arc<Mutex<Receiver<Message>>>,
-        thread_builder: thread::Builder,
-    ) -> io::Result<Worker> {
-        let thread = thread_builder.spawn(move || loop {
-            let _ = match receiver.lock().unwrap().recv().unwrap() {
-                Message::NewJob(job) => job(),
-                Message::Terminate => break,
-            };
-        })?;
-
-        Ok(Worker {
-            thread: Some(thread),
+/// Spawn a new worker only if there are less than `max` live worker
+///
+/// Return [`None`] if the pool already has enough worker.
+pub(crate) fn spawn_below<B: QueueBackend>(
+    shared: &Arc<Shared<B>>,
+    max: usize,
+) -> Option<io::Result<usize>> {
+    shared
+        .live_workers
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| {
+            (live < max).then_some(live + 1)
         })
+        .ok()?;
+
+    Some(spawn_counted(shared))
+}
+
+/// Spawn a worker that is already counted in [`Shared::live_workers`], returning it's index
+fn spawn_counted<B: QueueBackend>(shared: &Arc<Shared<B>>) -> io::Result<usize> {
+    // Hold the lock while spawning so the pool cannot miss the worker when it's joining them
+    let mut workers = shared
+        .workers
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    if shared.is_closed() {
+        shared.live_workers.fetch_sub(1, Ordering::SeqCst);
+        return Err(io::Error::other("thread pool is shutting down"));
     }
 
-    /// Take the ownership of [`JoinHandle`]
-    pub fn take_thread(&mut self) -> Option<JoinHandle<()>> {
-        self.thread.take()
+    shared.spawned_workers.fetch_add(1, Ordering::SeqCst);
+    let (index, inbox) = shared.inboxes.claim();
+    let thread_name = shared
+        .thread_name
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let name = thread_name.map(|prefix| format!("{prefix}-{index}"));
+
+    inbox.set_name(name.clone());
+
+    let worker_shared = Arc::clone(shared);
+    let worker_inbox = Arc::clone(&inbox);
+    let spawned = shared.spawner.spawn(
+        name,
+        Box::new(move || supervise(index, worker_inbox, worker_shared)),
+    );
+    match spawned {
+        Ok(thread) => {
+            workers.retain(|worker| !worker.is_finished());
+            workers.push(Worker {
+                thread: Some(thread),
+            });
+            shared
+                .events
+                .emit(|| PoolEvent::WorkerSpawned { worker: index });
+            shared.watch.notify();
+            #[cfg(feature = "metrics")]
+            shared
+                .metrics
+                .workers_live(shared.live_workers.load(Ordering::SeqCst));
+
+            Ok(index)
+        }
+        Err(err) => {
+            inbox.release();
+            shared.live_workers.fetch_sub(1, Ordering::SeqCst);
+            Err(err)
+        }
+    }
+}
+
+/// Run the worker and replace it according to the [`RestartPolicy`](crate::RestartPolicy) if it died from a panic
+fn supervise<B: QueueBackend>(index: usize, inbox: Arc<Inbox>, shared: Arc<Shared<B>>) {
+    let exited = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _claimed = Claimed(&inbox);
+        #[cfg(feature = "stacks")]
+        inbox.set_os_thread(Some(crate::stacks::OsThread::current()));
+        run(index, &inbox, &shared);
+        inbox.is_restarting()
+    }));
+    shared
+        .events
+        .emit(|| PoolEvent::WorkerExited { worker: index });
+    shared.watch.notify();
+    #[cfg(feature = "metrics")]
+    shared
+        .metrics
+        .workers_live(shared.live_workers.load(Ordering::SeqCst));
+
+    let payload = match exited {
+        // The index is released so the replacement take it over
+        Ok(true) => {
+            let _ = spawn(&shared);
+            return;
+        }
+        Ok(false) => {
+            // A job sent to the index while the worker was retiring still need an owner
+            if !inbox.is_empty() && !shared.is_stopping() {
+                let _ = shared.spawn_owner(index);
+            }
+            return;
+        }
+        Err(payload) => payload,
+    };
+
+    *shared
+        .last_panic
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(SystemTime::now());
+
+    while let Some(delay) = shared.supervisor.next_restart() {
+        // The pool is gone if it's closed, it isn't kept waiting for the delay
+        if shared.is_closed() || !shared.supervisor.wait(delay) {
+            break;
+        }
+
+        // A worker spawned for a job routed to the index may already have replaced it
+        match spawn_below(&shared, shared.max_workers()) {
+            Some(Ok(replacement)) => {
+                shared.supervisor.restarted(index, replacement, delay);
+                break;
+            }
+            None => break,
+            // Retrying right away would spin, and the pool is gone if it's closed
+            Some(Err(_)) if delay.is_zero() || shared.is_closed() => break,
+            Some(Err(_)) => continue,
+        }
+    }
+
+    // A job sent to the index still need an owner when the worker isn't replaced
+    if !inbox.is_empty() && !shared.is_stopping() {
+        let _ = shared.spawn_owner(index);
+    }
+
+    // Keep reporting the panic through the thread's JoinHandle
+    panic::resume_unwind(payload);
+}
+
+/// Keep [`Shared::live_workers`] accurate even if the worker die from a panic
+struct Alive<'a, B: QueueBackend>(&'a Shared<B>);
+
+impl<B: QueueBackend> Alive<'_, B> {
+    /// The worker is already uncounted by [`Shared::try_retire`]
+    fn retire(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl<B: QueueBackend> Drop for Alive<'_, B> {
+    fn drop(&mut self) {
+        self.0.live_workers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Give the worker index back when the worker exit, even from a panic
+struct Claimed<'a>(&'a Inbox);
+
+impl Drop for Claimed<'_> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// Main loop of the worker thread
+fn run<B: QueueBackend>(index: usize, inbox: &Inbox, shared: &Arc<Shared<B>>) {
+    let alive = Alive(shared);
+    context::enter_worker(Arc::clone(shared) as _, index);
+
+    #[cfg(feature = "numa")]
+    if let Some(numa) = &shared.numa {
+        crate::numa::pin_current_thread(&numa.nodes[numa.node_of(index)]);
+    }
+
+    #[cfg(feature = "priority")]
+    if let Some(priority) = shared.priority {
+        priority.apply();
+    }
+
+    let mut batch = Vec::with_capacity(shared.batch_size);
+    let mut spin_window = SpinWindow::default();
+
+    loop {
+        if !run_pending(index, shared, inbox) {
+            return;
+        }
+
+        // Job pushed to the inbox before the worker is marked idle doesn't send a wake
+        inbox.set_idle(true);
+        if !inbox.is_empty() || inbox.is_exiting() {
+            inbox.set_idle(false);
+            continue;
+        }
+
+        let keep_alive =
+            (shared.max_workers() > shared.core_workers()).then(|| shared.keep_alive());
+
+        shared.idle_workers.fetch_add(1, Ordering::SeqCst);
+        shared.worker_idle();
+        let message = shared
+            .idle
+            .recv(&shared.queue, keep_alive, &mut spin_window);
+        shared.idle_workers.fetch_sub(1, Ordering::SeqCst);
+        inbox.set_idle(false);
+
+        let message = match message {
+            Ok(message) => message,
+            Err(TryRecvError::Closed) => {
+                run_pending(index, shared, inbox);
+                return;
+            }
+            Err(TryRecvError::Empty) => {
+                if shared.try_retire(index) {
+                    alive.retire();
+                    return;
+                }
+                continue;
+            }
+        };
+
+        batch.push(message);
+        if shared.batch_size > 1 {
+            shared
+                .queue
+                .try_recv_batch(shared.batch_size - 1, &mut batch);
+        }
+        shared.room.notify();
+
+        let mut messages = batch.drain(..);
+        while let Some(message) = messages.next() {
+            if shared.is_stopping() {
+                return;
+            }
+
+            match message {
+                Message::NewJob(job) => run_tracked(index, shared, inbox, job),
+                Message::Scheduled => {
+                    if let Some(job) = shared.next_scheduled(Some(index)) {
+                        run_tracked(index, shared, inbox, job);
+                    }
+                }
+                // The inbox is drained right after
+                Message::Wake(target) if target == index => {}
+                Message::Wake(target) => shared.forward_wake(target),
+                Message::Control(command) => control::dispatch(&shared.controls, index, &*command),
+                Message::Terminate => {
+                    // Hand the rest of the batch to the other worker
+                    for message in messages {
+                        let _ = shared.queue.send(message);
+                    }
+                    run_pending(index, shared, inbox);
+                    return;
+                }
+            }
+
+            if !run_pending(index, shared, inbox) {
+                // Hand the rest of the batch to the other worker if this one is only restarting
+                if !shared.is_stopping() {
+                    for message in messages {
+                        let _ = shared.queue.send(message);
+                    }
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Run the local job and the job in the worker inbox
+///
+/// Return `false` if the worker should stop, restart or retire.
+fn run_pending<B: QueueBackend>(index: usize, shared: &Shared<B>, inbox: &Inbox) -> bool {
+    while let Some(job) = context::pop_local().or_else(|| inbox.pop()) {
+        if shared.is_stopping() {
+            return false;
+        }
+
+        run_tracked(index, shared, inbox, job);
+    }
+
+    !shared.is_stopping() && !inbox.is_exiting()
+}
+
+/// Run a job while the worker [`Inbox`] report it as busy
+///
+/// The worker is restarted once it reach [`ThreadPoolBuilder::max_jobs_per_worker`](crate::ThreadPoolBuilder::max_jobs_per_worker).
+fn run_tracked<B: QueueBackend>(index: usize, shared: &Shared<B>, inbox: &Inbox, job: Job) {
+    struct Finish<'a>(&'a Inbox, Option<usize>);
+
+    impl Drop for Finish<'_> {
+        fn drop(&mut self) {
+            let completed = self.0.finish();
+            if self.1.is_some_and(|max_jobs| completed >= max_jobs) {
+                self.0.request_restart();
+            }
+        }
+    }
+
+    let name = job.shared_name();
+    #[cfg(feature = "rename")]
+    let _renamed = name
+        .as_deref()
+        .filter(|_| shared.rename)
+        .map(crate::rename::rename_current);
+
+    inbox.start(name);
+    let _finish = Finish(inbox, shared.max_jobs_per_worker);
+    run_job(shared, Some(index), job);
+}
+
+/// Run a job while recording it's statistic, `worker` is [`None`] when it run on the caller thread
+pub(crate) fn run_job<B: QueueBackend>(shared: &Shared<B>, worker: Option<usize>, job: Job) {
+    let queue_wait = job.enqueued_at().elapsed();
+    shared.stats.queue_wait.record(queue_wait);
+    shared.admission.record(queue_wait);
+    shared.watch.queue_depth(|| shared.queue.len());
+    shared.job_started();
+    let _running = shared.events.job_started(worker, &job);
+    #[cfg(feature = "metrics")]
+    let _busy = shared.metrics.job_started(shared.queue.len());
+    let name = job.shared_name();
+    let _capture = shared
+        .stats
+        .panic_backtrace
+        .then(crate::panic_trace::Capture::enter);
+
+    let named = name.as_ref().map(|name| shared.stats.named(name));
+
+    let start = (shared.stats.job_duration.is_some() || named.is_some()).then(Instant::now);
+    let ran = panic::catch_unwind(AssertUnwindSafe(|| Next::new(job, &shared.layers).run()));
+    let elapsed = start.map(|start| start.elapsed());
+
+    if let (Ok(()), Some(job_duration), Some(elapsed)) = (&ran, &shared.stats.job_duration, elapsed)
+    {
+        job_duration.record(elapsed);
+    }
+    if let Some(named) = named {
+        named.record(ran.is_ok(), elapsed.unwrap_or_default());
+    }
+
+    // The panic is only recorded, it still kill the worker
+    if let Err(payload) = ran {
+        let details = shared.stats.record_panic(name.as_deref(), &*payload);
+        if let Some(reporter) = &shared.panic_reporter {
+            reporter.report(&JobPanic {
+                worker,
+                details: &details,
+                payload: &*payload,
+            });
+        }
+        panic::resume_unwind(payload);
     }
 }
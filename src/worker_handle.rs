@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use crate::backend::QueueBackend;
+use crate::error::ThreadPoolError;
+use crate::inbox::Inbox;
+use crate::{Job, Message, ThreadPool};
+
+/// What a worker is doing right now, see [`WorkerHandle::status`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerStatus {
+    /// The worker is waiting for a job
+    Idle,
+    /// The worker is running a job, `job` is it's name if it was executed with [`ThreadPool::execute_named`]
+    Busy { job: Option<String> },
+    /// The worker has exited and no other worker took over it's index yet
+    Stopped,
+}
+
+/// Handle to a single worker of the pool, see [`ThreadPool::workers`]
+///
+/// It refer to the worker index, a worker that replace a dead, retired or restarted one
+/// take over it's index so the handle keep following it.
+#[derive(Debug)]
+pub struct WorkerHandle<'pool, B: QueueBackend = crate::DefaultBackend> {
+    pool: &'pool ThreadPool<B>,
+    index: usize,
+    inbox: Arc<Inbox>,
+}
+
+impl<B: QueueBackend> WorkerHandle<'_, B> {
+    /// Index of the worker, the same one used by [`ThreadPool::execute_on`]
+    pub fn id(&self) -> usize {
+        self.index
+    }
+
+    /// Thread name of the worker, [`None`] if the pool doesn't name it's thread
+    pub fn name(&self) -> Option<String> {
+        self.inbox.name()
+    }
+
+    /// CPU time consumed by the worker thread as of it's last finished job
+    #[cfg(feature = "cputime")]
+    pub fn cpu_time(&self) -> std::time::Duration {
+        self.inbox.cpu_time()
+    }
+
+    /// What the worker is doing right now
+    pub fn status(&self) -> WorkerStatus {
+        if !self.inbox.is_occupied() {
+            WorkerStatus::Stopped
+        } else if self.inbox.is_busy() {
+            WorkerStatus::Busy {
+                job: self.inbox.job().map(|job| job.to_string()),
+            }
+        } else {
+            WorkerStatus::Idle
+        }
+    }
+
+    /// Make the worker exit once it finished it's current job and the job waiting for it,
+    /// a new thread is spawned to take over it's index.
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::NoSuchWorker`] if the worker is already stopped,
+    /// or an [`Err`] if the communication channel between worker thread and main thread is closed.
+    pub fn restart(&self) -> Result<(), ThreadPoolError> {
+        if !self.inbox.is_occupied() {
+            return Err(ThreadPoolError::NoSuchWorker { worker: self.index });
+        }
+
+        self.inbox.request_restart();
+        if self.inbox.is_idle() {
+            self.pool
+                .shared
+                .queue
+                .send(Message::Wake(self.index))
+                .map_err(|_| ThreadPoolError::QueueClosed)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<B: QueueBackend> ThreadPool<B> {
+    /// Get a handle to every running worker, ordered by their index
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::{ThreadPoolBuilder, WorkerStatus};
+    ///
+    /// let pool = ThreadPoolBuilder::new(4)
+    ///     .thread_name("service")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// for worker in pool.workers() {
+    ///     if let WorkerStatus::Busy { job: Some(job) } = worker.status() {
+    ///         println!("{:?} is running {job}", worker.name());
+    ///     }
+    /// }
+    /// ```
+    pub fn workers(&self) -> Vec<WorkerHandle<'_, B>> {
+        self.shared
+            .inboxes
+            .occupied()
+            .into_iter()
+            .map(|index| WorkerHandle {
+                pool: self,
+                index,
+                inbox: self.shared.inboxes.get(index),
+            })
+            .collect()
+    }
+
+    /// Execute a job to worker thread under a name reported by [`WorkerHandle::status`] while it run
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// use unknownrori_simple_thread_pool::ThreadPool;
+    ///
+    /// let pool = ThreadPool::new(2).unwrap();
+    /// pool.execute_named("rebuild-index", || println!("rebuilding")).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// This function will return [`ThreadPoolError::CircuitOpen`] if the [`CircuitBreaker`](crate::CircuitBreaker)
+    /// of the name is open, or an [`Err`] if the communication channel between worker thread and main thread is closed.
+    pub fn execute_named<F>(&self, name: &str, job: F) -> Result<(), ThreadPoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let job = self
+            .shared
+            .breakers
+            .guard(Job::new(job).named(Arc::from(name)))?;
+        self.shared.submit(job)
+    }
+}
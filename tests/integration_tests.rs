@@ -4,16 +4,18 @@ mod mpsc {
     use std::sync::mpsc::channel;
     use std::{thread, time::Duration};
 
-    use unknownrori_simple_thread_pool::{error::FailedToSendJob, ThreadPool};
+    use unknownrori_simple_thread_pool::{
+        backend::Mpsc, error::ThreadPoolError, ThreadPool, ThreadPoolBuilder,
+    };
 
-    /// Test the crossbeam thread pooling implementation
+    /// Test the mpsc thread pooling implementation
     ///
     /// ## Panic
     ///
     /// It may panic if the OS cannot create a thread
     #[test]
-    fn test_mpsc() -> Result<(), FailedToSendJob> {
-        let pool = ThreadPool::new(2).unwrap();
+    fn test_mpsc() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::with_backend(2, Mpsc::default()).unwrap();
 
         let (send, recv) = channel();
 
@@ -34,30 +36,161 @@ mod mpsc {
         assert_eq!(recv.recv().unwrap(), 40);
         assert_eq!(recv.recv().unwrap(), 40);
         assert_eq!(recv.recv().unwrap(), 40);
-        assert_eq!(recv.try_recv().is_err(), true);
+        assert!(recv.try_recv().is_err());
 
         Ok(())
     }
 
     #[test]
-    #[should_panic]
     fn panic_inside_worker() {
-        let pool = ThreadPool::new(2).unwrap();
+        let pool = ThreadPool::with_backend(2, Mpsc::default()).unwrap();
 
         pool.execute(|| {
             panic!("Oh no!");
         })
         .unwrap();
     }
+
+    #[test]
+    fn scatter_gather_keep_input_order() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::with_backend(2, Mpsc::default()).unwrap();
+
+        let result = pool.scatter_gather(0..8, |x| {
+            thread::sleep(Duration::from_millis(10 * (8 - x)));
+            x * 2
+        })?;
+
+        assert_eq!(result, vec![0, 2, 4, 6, 8, 10, 12, 14]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounded_queue_many_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::with_backend(8, Mpsc::bounded(2)).unwrap();
+        let (send, recv) = channel();
+
+        for i in 0..400 {
+            let send = send.clone();
+            pool.execute(move || send.send(i).unwrap())?;
+        }
+
+        let mut result: Vec<i32> = recv.iter().take(400).collect();
+        result.sort();
+        assert_eq!(result, (0..400).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn batch_receive() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2)
+            .batch_size(8)
+            .build_with_backend(Mpsc::default())?;
+        let (send, recv) = channel();
+
+        for i in 0..32 {
+            let send = send.clone();
+            pool.execute(move || send.send(i).unwrap())?;
+        }
+
+        let mut result: Vec<i32> = recv.iter().take(32).collect();
+        result.sort();
+        assert_eq!(result, (0..32).collect::<Vec<_>>());
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "flume")]
+#[cfg(test)]
+mod flume {
+    use std::sync::Arc;
+    use std::{thread, time::Duration};
+
+    use unknownrori_simple_thread_pool::{
+        backend::{Flume, QueueBackend},
+        error::ThreadPoolError,
+        flume::unbounded,
+        Message, RejectionPolicy, ThreadPool, ThreadPoolBuilder,
+    };
+
+    /// Test the flume thread pooling implementation
+    ///
+    /// ## Panic
+    ///
+    /// It may panic if the OS cannot create a thread
+    #[test]
+    fn test_flume() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::with_backend(2, Flume::default()).unwrap();
+        let (send, recv) = unbounded();
+
+        for _ in 0..4 {
+            let send = send.clone();
+
+            pool.execute(move || {
+                // Simulate long process
+                thread::sleep(Duration::from_millis(100));
+
+                send.send(40).unwrap();
+            })?;
+        }
+
+        assert_eq!(recv.iter().take(4).sum::<i32>(), 160);
+        assert!(recv.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn close_while_send_blocked() {
+        let queue = Arc::new(Flume::bounded(1));
+        queue.send(Message::Scheduled).unwrap();
+
+        let sender = Arc::clone(&queue);
+        let blocked = thread::spawn(move || sender.send(Message::Scheduled).is_ok());
+        thread::sleep(Duration::from_millis(50));
+
+        // Closing doesn't wait for the sender blocked on the full queue
+        queue.close();
+        assert!(queue.recv().is_some());
+        assert!(blocked.join().unwrap());
+        assert!(queue.send(Message::Scheduled).is_err());
+    }
+
+    #[test]
+    fn bounded_queue_reject() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .rejection_policy(RejectionPolicy::Reject)
+            .build_with_backend(Flume::bounded(1))?;
+
+        pool.execute(|| thread::sleep(Duration::from_millis(200)))?;
+        thread::sleep(Duration::from_millis(50));
+        pool.execute(|| {})?;
+
+        assert!(matches!(
+            pool.execute(|| {}),
+            Err(ThreadPoolError::QueueFull)
+        ));
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "crossbeam")]
 #[cfg(test)]
 mod crossbeam {
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
     use std::{thread, time::Duration};
 
     use unknownrori_simple_thread_pool::{
-        crossbeam_channel::unbounded, error::FailedToSendJob, ThreadPool,
+        backend::{Crossbeam, QueueBackend},
+        crossbeam_channel::unbounded,
+        error::ThreadPoolError,
+        Message, ThreadPool,
     };
 
     /// Test the crossbeam thread pooling implementation
@@ -66,7 +199,7 @@ mod crossbeam {
     ///
     /// It may panic if the OS cannot create a thread
     #[test]
-    fn test_crossbeam() -> Result<(), FailedToSendJob> {
+    fn test_crossbeam() -> Result<(), ThreadPoolError> {
         let pool = ThreadPool::new(2).unwrap();
         let (send, recv) = unbounded();
 
@@ -87,13 +220,28 @@ mod crossbeam {
         assert_eq!(recv.recv().unwrap(), 40);
         assert_eq!(recv.recv().unwrap(), 40);
         assert_eq!(recv.recv().unwrap(), 40);
-        assert_eq!(recv.try_recv().is_err(), true);
+        assert!(recv.try_recv().is_err());
 
         Ok(())
     }
 
     #[test]
-    #[should_panic]
+    fn close_while_send_blocked() {
+        let queue = Arc::new(Crossbeam::bounded(1));
+        queue.send(Message::Scheduled).unwrap();
+
+        let sender = Arc::clone(&queue);
+        let blocked = thread::spawn(move || sender.send(Message::Scheduled).is_ok());
+        thread::sleep(Duration::from_millis(50));
+
+        // Closing doesn't wait for the sender blocked on the full queue
+        queue.close();
+        assert!(queue.recv().is_some());
+        assert!(blocked.join().unwrap());
+        assert!(queue.send(Message::Scheduled).is_err());
+    }
+
+    #[test]
     fn panic_inside_worker() {
         let pool = ThreadPool::new(2).unwrap();
 
@@ -102,4 +250,3484 @@ mod crossbeam {
         })
         .unwrap();
     }
+
+    #[test]
+    fn drop_after_every_worker_died() {
+        let pool = ThreadPool::new(2).unwrap();
+
+        for _ in 0..2 {
+            pool.execute(|| panic!("Oh no!")).unwrap();
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool.live_workers() > 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(pool.live_workers(), 0);
+
+        // Joining the dead worker doesn't bring their panic back
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(pool))).is_ok());
+    }
+
+    #[test]
+    fn scatter_gather_keep_input_order() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2).unwrap();
+
+        let result = pool.scatter_gather(0..8, |x| {
+            thread::sleep(Duration::from_millis(10 * (8 - x)));
+            x * 2
+        })?;
+
+        assert_eq!(result, vec![0, 2, 4, 6, 8, 10, 12, 14]);
+
+        let result = pool.scatter_gather(0..8, |x| {
+            assert!(x != 3, "Oh no!");
+            x
+        });
+        assert!(matches!(result, Err(ThreadPoolError::JobPanicked)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn map_reduce_keep_input_order() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(3).unwrap();
+
+        let sum = pool.map_reduce(1..=100, |x| x, 0, |a, b| a + b)?;
+        assert_eq!(sum, 5050);
+
+        let joined = pool.map_reduce(0..10, |x| x.to_string(), String::new(), |a, b| a + &b)?;
+        assert_eq!(joined, "0123456789");
+
+        Ok(())
+    }
+
+    #[test]
+    fn map_stream_bound_in_flight() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let pool = ThreadPool::new(4).unwrap();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let (running_job, peak_job) = (Arc::clone(&running), Arc::clone(&peak));
+        let mut result: Vec<usize> = pool
+            .map_stream(0..32, move |x: usize| {
+                let now = running_job.fetch_add(1, Ordering::SeqCst) + 1;
+                peak_job.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(5));
+                running_job.fetch_sub(1, Ordering::SeqCst);
+                assert!(x != 7, "Oh no!");
+                x * x
+            })
+            .in_flight(2)
+            .filter_map(Result::ok)
+            .collect();
+
+        result.sort_unstable();
+        let expected: Vec<usize> = (0..32).filter(|x| *x != 7).map(|x| x * x).collect();
+        assert_eq!(result, expected);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn buffered_map_keep_input_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let pool = ThreadPool::new(4).unwrap();
+        let submitted = Arc::new(AtomicUsize::new(0));
+
+        let submitted_job = Arc::clone(&submitted);
+        let mut results = pool.buffered_map(
+            0..32usize,
+            move |x| {
+                submitted_job.fetch_add(1, Ordering::SeqCst);
+                // Later input finish first
+                thread::sleep(Duration::from_millis(((32 - x) % 4) as u64 * 2));
+                assert!(x != 7, "Oh no!");
+                x * x
+            },
+            3,
+        );
+
+        assert_eq!(results.next().unwrap().unwrap(), 0);
+        thread::sleep(Duration::from_millis(20));
+        assert!(submitted.load(Ordering::SeqCst) <= 3);
+
+        let rest: Vec<_> = results.map(Result::ok).collect();
+        let expected: Vec<_> = (1..32).map(|x| (x != 7).then_some(x * x)).collect();
+        assert_eq!(rest, expected);
+    }
+
+    #[test]
+    fn consume_until_channel_close() -> Result<(), ThreadPoolError> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let pool = ThreadPool::new(3).unwrap();
+        let (send, recv) = unbounded();
+        let sum = Arc::new(AtomicUsize::new(0));
+
+        let total = Arc::clone(&sum);
+        let consumer = pool.consume(recv, move |x: usize| {
+            total.fetch_add(x, Ordering::SeqCst);
+        })?;
+
+        for x in 1..=100 {
+            send.send(x).unwrap();
+        }
+        drop(send);
+
+        assert_eq!(consumer.wait()?, 100);
+        assert_eq!(sum.load(Ordering::SeqCst), 5050);
+
+        Ok(())
+    }
+
+    #[test]
+    fn for_each_chunk_mutate_slice() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2).unwrap();
+        let mut numbers: Vec<usize> = (0..1000).collect();
+
+        pool.for_each_chunk(&mut numbers, 64, |chunk| {
+            chunk.iter_mut().for_each(|x| *x *= 2);
+        })?;
+
+        assert!(numbers.iter().enumerate().all(|(i, x)| *x == i * 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn scope_cancel_on_panic() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1).unwrap();
+        let skipped = AtomicUsize::new(0);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.scope(|scope| {
+                scope.execute(|| panic!("Oh no")).unwrap();
+                for _ in 0..4 {
+                    // Once the first job panicked the scope refuse new job
+                    let _ = scope.execute(|| {
+                        skipped.fetch_add(1, Ordering::SeqCst);
+                    });
+                }
+            })
+        }));
+
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"Oh no"));
+        assert_eq!(skipped.load(Ordering::SeqCst), 0);
+
+        // The worker survived the panic
+        pool.spawn(|| ())?.wait()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod backend {
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+    use std::sync::{Condvar, Mutex};
+    use std::time::Duration;
+
+    use unknownrori_simple_thread_pool::{
+        backend::{QueueBackend, TryRecvError},
+        error::ThreadPoolError,
+        Message, ThreadPool,
+    };
+
+    #[derive(Default)]
+    struct Deque {
+        queue: Mutex<(VecDeque<Message>, bool)>,
+        available: Condvar,
+    }
+
+    impl QueueBackend for Deque {
+        fn send(&self, message: Message) -> Result<(), Message> {
+            let mut queue = self.queue.lock().unwrap();
+            if queue.1 {
+                return Err(message);
+            }
+
+            queue.0.push_back(message);
+            self.available.notify_one();
+
+            Ok(())
+        }
+
+        fn recv(&self) -> Option<Message> {
+            let mut queue = self.queue.lock().unwrap();
+            loop {
+                if let Some(message) = queue.0.pop_front() {
+                    return Some(message);
+                }
+
+                if queue.1 {
+                    return None;
+                }
+
+                queue = self.available.wait(queue).unwrap();
+            }
+        }
+
+        fn try_recv(&self) -> Result<Message, TryRecvError> {
+            let mut queue = self.queue.lock().unwrap();
+            match queue.0.pop_front() {
+                Some(message) => Ok(message),
+                None if queue.1 => Err(TryRecvError::Closed),
+                None => Err(TryRecvError::Empty),
+            }
+        }
+
+        fn recv_timeout(&self, timeout: Duration) -> Result<Message, TryRecvError> {
+            let mut queue = self.queue.lock().unwrap();
+            loop {
+                if let Some(message) = queue.0.pop_front() {
+                    return Ok(message);
+                }
+
+                if queue.1 {
+                    return Err(TryRecvError::Closed);
+                }
+
+                let (guard, result) = self.available.wait_timeout(queue, timeout).unwrap();
+                queue = guard;
+                if result.timed_out() && queue.0.is_empty() {
+                    return Err(TryRecvError::Empty);
+                }
+            }
+        }
+
+        fn len(&self) -> usize {
+            self.queue.lock().unwrap().0.len()
+        }
+
+        fn close(&self) {
+            self.queue.lock().unwrap().1 = true;
+            self.available.notify_all();
+        }
+    }
+
+    #[test]
+    fn custom_backend() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::with_backend(2, Deque::default()).unwrap();
+        let (send, recv) = channel();
+
+        for i in 0..4 {
+            let send = send.clone();
+            pool.execute(move || send.send(i).unwrap())?;
+        }
+
+        let mut result: Vec<i32> = recv.iter().take(4).collect();
+        result.sort();
+        assert_eq!(result, vec![0, 1, 2, 3]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod error {
+    use std::error::Error;
+    use std::io;
+
+    use unknownrori_simple_thread_pool::error::ThreadPoolError;
+
+    #[test]
+    fn spawn_failed_has_source() {
+        let err = ThreadPoolError::SpawnFailed {
+            index: 3,
+            source: io::Error::other("no more thread"),
+        };
+
+        assert!(err.source().is_some());
+        assert!(err.to_string().contains('3'));
+        assert!(ThreadPoolError::QueueClosed.source().is_none());
+    }
+}
+
+#[cfg(test)]
+mod builder {
+    use std::io;
+    use std::sync::mpsc::channel;
+    use std::sync::{Arc, Mutex};
+    use std::thread::JoinHandle;
+    use std::time::Instant;
+    use std::{thread, time::Duration};
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, recommended_workers, CircuitBreaker, ControlHandler, DropPolicy,
+        DualPool, IdleStrategy, JobContext, MaxQueueLength, Next, PoolConfig, PriorityScheduler,
+        Qos, QosScheduler, RejectionPolicy, RestartPolicy, StdSpawner, ThreadPool,
+        ThreadPoolBuilder, ThreadSpawner, WorkloadProfile,
+    };
+
+    #[test]
+    fn drop_finish_running_only() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .on_drop(DropPolicy::FinishRunningOnly)
+            .build()?;
+        let (started, wait_started) = channel();
+        let (send, recv) = channel();
+
+        for i in 0..4 {
+            let send = send.clone();
+            let started = started.clone();
+            pool.execute(move || {
+                let _ = started.send(());
+                thread::sleep(Duration::from_millis(50));
+                send.send(i).unwrap();
+            })?;
+        }
+        drop(send);
+
+        wait_started.recv().unwrap();
+        drop(pool);
+
+        assert_eq!(recv.iter().collect::<Vec<_>>(), vec![0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn drop_detach() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .on_drop(DropPolicy::Detach)
+            .build()?;
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            thread::sleep(Duration::from_millis(200));
+            send.send(40).unwrap();
+        })?;
+
+        let now = Instant::now();
+        drop(pool);
+        assert!(now.elapsed() < Duration::from_millis(200));
+
+        assert_eq!(recv.recv().unwrap(), 40);
+
+        Ok(())
+    }
+
+    #[test]
+    fn idle_strategy() -> Result<(), ThreadPoolError> {
+        let strategies = [
+            IdleStrategy::Park,
+            IdleStrategy::Spin,
+            IdleStrategy::SpinThenYield {
+                spins: 100,
+                yields: 10,
+            },
+            IdleStrategy::AdaptiveSpin {
+                max: Duration::from_micros(50),
+            },
+        ];
+
+        for idle in strategies {
+            let pool = ThreadPoolBuilder::new(2).idle_strategy(idle).build()?;
+            let (send, recv) = channel();
+
+            for i in 0..4 {
+                let send = send.clone();
+                pool.execute(move || send.send(i).unwrap())?;
+            }
+
+            let mut result: Vec<i32> = recv.iter().take(4).collect();
+            result.sort();
+            assert_eq!(result, vec![0, 1, 2, 3]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn batch_receive() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2).batch_size(8).build()?;
+        let (send, recv) = channel();
+
+        for i in 0..32 {
+            let send = send.clone();
+            pool.execute(move || send.send(i).unwrap())?;
+        }
+
+        let mut result: Vec<i32> = recv.iter().take(32).collect();
+        result.sort();
+        assert_eq!(result, (0..32).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn lifo() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1).lifo(true).build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            wait_release.recv().unwrap();
+        })?;
+        wait_started.recv().unwrap();
+
+        for i in 0..4 {
+            let send = send.clone();
+            pool.execute(move || send.send(i).unwrap())?;
+        }
+        release.send(()).unwrap();
+
+        assert_eq!(recv.iter().take(4).collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn earliest_deadline_first() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .earliest_deadline_first(true)
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            wait_release.recv().unwrap();
+        })?;
+        wait_started.recv().unwrap();
+
+        let now = Instant::now();
+        let sender = send.clone();
+        pool.execute(move || sender.send(0).unwrap())?;
+        for (i, delay) in [(1, 30), (2, 10), (3, 20)] {
+            let send = send.clone();
+            pool.execute_with_deadline(now + Duration::from_millis(delay), move || {
+                send.send(i).unwrap()
+            })?;
+        }
+        release.send(()).unwrap();
+
+        assert_eq!(recv.iter().take(4).collect::<Vec<_>>(), vec![2, 3, 1, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn default_size_from_available_parallelism() -> Result<(), ThreadPoolError> {
+        let cpus = thread::available_parallelism().map_or(1, |cpus| cpus.get());
+
+        assert_eq!(ThreadPool::with_default_size()?.live_workers(), cpus);
+        assert_eq!(
+            ThreadPool::with_scaled_size(2.0, 1)?.live_workers(),
+            cpus * 2 + 1
+        );
+        assert_eq!(ThreadPool::with_scaled_size(0.0, -4)?.live_workers(), 1);
+        assert_eq!(ThreadPoolBuilder::default().build()?.live_workers(), cpus);
+
+        Ok(())
+    }
+
+    #[test]
+    fn recommended_workers_within_available_parallelism() {
+        let cpus = thread::available_parallelism().map_or(1, |cpus| cpus.get());
+
+        let recommended = recommended_workers(WorkloadProfile::Cpu);
+        assert!((1..=cpus).contains(&recommended));
+        assert_eq!(recommended_workers(WorkloadProfile::Io), recommended * 8);
+    }
+
+    #[test]
+    fn layer_wrap_every_job() -> Result<(), ThreadPoolError> {
+        let (send, recv) = channel();
+        let outer = send.clone();
+        let inner = send.clone();
+        let pool = ThreadPoolBuilder::new(1)
+            .layer(move |next: Next| {
+                outer.send("outer").unwrap();
+                next.run();
+            })
+            .layer(move |next: Next| {
+                // Drop the job named `skipped` instead of running it
+                if next.job().name() != Some("skipped") {
+                    inner.send("inner").unwrap();
+                    next.run();
+                }
+            })
+            .build()?;
+
+        pool.execute_named("skipped", || panic!("Oh no"))?;
+        pool.execute(move || send.send("job").unwrap())?;
+        drop(pool);
+
+        assert_eq!(
+            recv.iter().collect::<Vec<_>>(),
+            vec!["outer", "outer", "inner", "job"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn circuit_breaker_open_after_failures() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .circuit_breaker(
+                "flaky",
+                CircuitBreaker::new(2, Duration::from_secs(5), Duration::from_millis(100)),
+            )
+            .build()?;
+
+        pool.execute_named_fallible("flaky", || Err("Oh no"))?;
+        pool.execute_named("flaky", || panic!("Oh no"))?;
+
+        let start = Instant::now();
+        while pool.execute_named("flaky", || ()).is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(matches!(
+            pool.execute_named_fallible("flaky", || Ok::<(), ()>(())),
+            Err(ThreadPoolError::CircuitOpen { .. })
+        ));
+        pool.execute_named("other", || ())?;
+
+        thread::sleep(Duration::from_millis(150));
+        pool.execute_named("flaky", || ())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn starvation_alert() -> Result<(), ThreadPoolError> {
+        let (starved, wait_starved) = channel();
+        let pool = ThreadPoolBuilder::new(1)
+            .on_starvation(Duration::from_millis(50), move |job| {
+                starved.send(job.clone()).unwrap()
+            })
+            .build()?;
+        let (release, wait_release) = channel::<()>();
+
+        pool.execute(move || {
+            let _ = wait_release.recv();
+        })?;
+        pool.execute_named("report", || {})?;
+
+        let job = wait_starved.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(job.job.as_deref(), Some("report"));
+        assert!(job.waited >= Duration::from_millis(50));
+
+        release.send(()).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn admission_shed_load() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        pool.admission(MaxQueueLength(1));
+        let (release, wait_release) = channel::<()>();
+        let (started, wait_started) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        pool.execute(|| {})?;
+        assert!(matches!(
+            pool.execute(|| {}),
+            Err(ThreadPoolError::Overloaded)
+        ));
+
+        pool.clear_admission();
+        pool.execute(|| {})?;
+        release.send(()).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn qos_scheduler_with_aging() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .scheduler(QosScheduler::new(Duration::from_millis(100)))
+            .build()?;
+        let (send, recv) = channel();
+
+        let block = |pool: &ThreadPool| -> Result<_, ThreadPoolError> {
+            let (started, wait_started) = channel();
+            let (release, wait_release) = channel::<()>();
+            pool.execute(move || {
+                started.send(()).unwrap();
+                wait_release.recv().unwrap();
+            })?;
+            wait_started.recv().unwrap();
+            Ok(release)
+        };
+
+        let release = block(&pool)?;
+        for (i, qos) in [
+            (0, Qos::Background),
+            (1, Qos::Default),
+            (2, Qos::Interactive),
+        ] {
+            let send = send.clone();
+            pool.execute_with_qos(qos, move || send.send(i).unwrap())?;
+        }
+        release.send(()).unwrap();
+        assert_eq!(recv.iter().take(3).collect::<Vec<_>>(), vec![2, 1, 0]);
+
+        // The background job waited past the aging period so it's promoted
+        let release = block(&pool)?;
+        for (i, qos) in [(3, Qos::Background), (4, Qos::Interactive)] {
+            let send = send.clone();
+            pool.execute_with_qos(qos, move || send.send(i).unwrap())?;
+        }
+        thread::sleep(Duration::from_millis(150));
+        release.send(()).unwrap();
+        assert_eq!(recv.iter().take(2).collect::<Vec<_>>(), vec![3, 4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn priority_aging() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .priority_aging(Duration::from_millis(10))
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            wait_release.recv().unwrap();
+        })?;
+        wait_started.recv().unwrap();
+
+        let low = send.clone();
+        pool.execute_with_priority(0, move || low.send("low").unwrap())?;
+        // Waited long enough to gain more than five priority level
+        thread::sleep(Duration::from_millis(100));
+        pool.execute_with_priority(5, move || send.send("high").unwrap())?;
+        release.send(()).unwrap();
+
+        assert_eq!(recv.iter().take(2).collect::<Vec<_>>(), vec!["low", "high"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn priority_scheduler() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .scheduler(PriorityScheduler::default())
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            wait_release.recv().unwrap();
+        })?;
+        wait_started.recv().unwrap();
+
+        for (i, priority) in [(0, -1), (1, 5), (2, 0), (3, 5)] {
+            let send = send.clone();
+            pool.execute_with_priority(priority, move || send.send(i).unwrap())?;
+        }
+        release.send(()).unwrap();
+
+        assert_eq!(recv.iter().take(4).collect::<Vec<_>>(), vec![1, 3, 2, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_spawn() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2).lazy(true).build()?;
+        assert_eq!(pool.live_workers(), 0);
+
+        let (started, wait_started) = channel();
+        let (send, recv) = channel();
+        let (release, wait_release) = channel::<()>();
+        let wait_release = Arc::new(Mutex::new(wait_release));
+
+        for i in 0..4 {
+            let send = send.clone();
+            let started = started.clone();
+            let wait_release = wait_release.clone();
+            pool.execute(move || {
+                started.send(()).unwrap();
+                let _ = wait_release.lock().unwrap().recv();
+                send.send(i).unwrap();
+            })?;
+
+            if i < 2 {
+                wait_started.recv().unwrap();
+            }
+        }
+        assert_eq!(pool.live_workers(), 2);
+
+        drop(release);
+        assert_eq!(recv.iter().take(4).count(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn core_and_max_workers() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .max_workers(3)
+            .keep_alive(Duration::from_millis(100))
+            .build()?;
+        assert_eq!(pool.live_workers(), 1);
+
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let wait_release = Arc::new(Mutex::new(wait_release));
+
+        for _ in 0..3 {
+            let started = started.clone();
+            let wait_release = wait_release.clone();
+            pool.execute(move || {
+                started.send(()).unwrap();
+                let _ = wait_release.lock().unwrap().recv();
+            })?;
+            wait_started.recv().unwrap();
+        }
+        assert_eq!(pool.live_workers(), 3);
+
+        drop(release);
+        thread::sleep(Duration::from_millis(500));
+        assert_eq!(pool.live_workers(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn restart_panicked_worker() -> Result<(), ThreadPoolError> {
+        let (restarted, wait_restarted) = channel();
+        let restarted = Mutex::new(restarted);
+        let pool = ThreadPoolBuilder::new(1)
+            .restart_policy(RestartPolicy::UpTo {
+                restarts: 1,
+                within: Duration::from_secs(60),
+            })
+            .on_worker_restart(move |restart| {
+                restarted.lock().unwrap().send(*restart).unwrap();
+            })
+            .build()?;
+
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+        let restart = wait_restarted.recv().unwrap();
+        assert_eq!((restart.worker, restart.restarts), (0, 1));
+        assert_eq!(pool.live_workers(), 1);
+
+        let (send, recv) = channel();
+        pool.execute(move || send.send(40).unwrap())?;
+        assert_eq!(recv.recv().unwrap(), 40);
+
+        // The policy only allow one restart
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(pool.live_workers(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn drop_during_backoff_restart() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .restart_policy(RestartPolicy::Backoff {
+                initial: Duration::from_secs(30),
+                max: Duration::from_secs(60),
+            })
+            .build()?;
+
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+        thread::sleep(Duration::from_millis(50));
+
+        // The dead worker doesn't wait for it's replacement delay
+        let dropped = Instant::now();
+        drop(pool);
+        assert!(dropped.elapsed() < Duration::from_secs(5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounded_queue_reject() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .queue_capacity(1)
+            .rejection_policy(RejectionPolicy::Reject)
+            .thread_name("io")
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+
+        pool.execute(move || {
+            started
+                .send(thread::current().name().map(String::from))
+                .unwrap();
+            let _ = wait_release.recv();
+        })?;
+        assert_eq!(wait_started.recv().unwrap().as_deref(), Some("io-0"));
+
+        pool.execute(|| {})?;
+        assert!(matches!(
+            pool.execute(|| {}),
+            Err(ThreadPoolError::QueueFull)
+        ));
+        assert_eq!(pool.health().queue_capacity, Some(1));
+
+        drop(release);
+
+        Ok(())
+    }
+
+    #[test]
+    fn caller_runs_when_saturated() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1).caller_runs(1).build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let caller = thread::current().id();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        // The only worker is busy but the queue is still below the threshold
+        let (send, recv) = channel();
+        let queued = send.clone();
+        pool.execute(move || queued.send(thread::current().id()).unwrap())?;
+        pool.execute(move || send.send(thread::current().id()).unwrap())?;
+        assert_eq!(recv.try_recv().unwrap(), caller);
+
+        drop(release);
+        assert_ne!(recv.recv().unwrap(), caller);
+
+        Ok(())
+    }
+
+    #[test]
+    fn queue_watermarks_with_hysteresis() -> Result<(), ThreadPoolError> {
+        let crossed = Arc::new(Mutex::new(Vec::new()));
+        let (high, low) = (Arc::clone(&crossed), Arc::clone(&crossed));
+        let pool = ThreadPoolBuilder::new(1)
+            .queue_watermarks(
+                3,
+                1,
+                move |depth| high.lock().unwrap().push(("high", depth)),
+                move |depth| low.lock().unwrap().push(("low", depth)),
+            )
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+        for _ in 0..4 {
+            pool.execute(|| thread::sleep(Duration::from_millis(5)))?;
+        }
+        assert_eq!(*crossed.lock().unwrap(), vec![("high", 3)]);
+
+        // Falling to 2 is still above the low water mark
+        drop(release);
+        pool.shutdown();
+        assert_eq!(*crossed.lock().unwrap(), vec![("high", 3), ("low", 1)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn on_idle_after_every_wave() -> Result<(), ThreadPoolError> {
+        let (idle, wait_idle) = channel();
+        let idle = Mutex::new(idle);
+        let pool = ThreadPoolBuilder::new(1)
+            .on_idle(move || idle.lock().unwrap().send(()).unwrap())
+            .build()?;
+        let finished = Arc::new(Mutex::new(0));
+
+        for wave in 1..=2 {
+            for _ in 0..4 {
+                let finished = Arc::clone(&finished);
+                pool.execute(move || {
+                    thread::sleep(Duration::from_millis(5));
+                    *finished.lock().unwrap() += 1;
+                })?;
+            }
+
+            wait_idle.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(*finished.lock().unwrap(), wave * 4);
+        }
+
+        // Waking up without running a job is not a transition
+        thread::sleep(Duration::from_millis(50));
+        assert!(wait_idle.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_timeout_bounded_queue() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1).queue_capacity(1).build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+        pool.execute(|| {})?;
+
+        let start = Instant::now();
+        assert!(matches!(
+            pool.execute_timeout(|| {}, Duration::from_millis(50)),
+            Err(ThreadPoolError::Timeout)
+        ));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let release = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            drop(release);
+        });
+        pool.execute_timeout(|| {}, Duration::from_secs(10))?;
+        release.join().unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn named_queue_weighted_round_robin() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .named_queue("io", 2, None)
+            .named_queue("cpu", 1, Some(2))
+            .rejection_policy(RejectionPolicy::Reject)
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        for (queue, count) in [("io", 4), ("cpu", 2)] {
+            for i in 0..count {
+                let send = send.clone();
+                pool.queue(queue)?
+                    .execute(move || send.send(format!("{queue}{i}")).unwrap())?;
+            }
+        }
+        drop(send);
+
+        assert_eq!(pool.queue("cpu")?.len(), 2);
+        assert!(matches!(
+            pool.queue("cpu")?.execute(|| {}),
+            Err(ThreadPoolError::QueueFull)
+        ));
+        assert!(matches!(
+            pool.queue("gpu"),
+            Err(ThreadPoolError::NoSuchQueue { name }) if name == "gpu"
+        ));
+
+        drop(release);
+        assert_eq!(
+            recv.iter().collect::<Vec<_>>(),
+            vec!["io0", "io1", "cpu0", "io2", "io3", "cpu1"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn named_queue_block_until_room() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .named_queue("io", 1, Some(1))
+            .rejection_policy(RejectionPolicy::Block)
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        let first = send.clone();
+        pool.queue("io")?.execute(move || first.send(0).unwrap())?;
+
+        thread::scope(|scope| -> Result<(), ThreadPoolError> {
+            let blocked = scope.spawn(|| pool.queue("io")?.execute(move || send.send(1).unwrap()));
+
+            thread::sleep(Duration::from_millis(50));
+            assert!(!blocked.is_finished());
+
+            // The worker taking the first job make room for the blocked one
+            drop(release);
+            blocked.join().unwrap()
+        })?;
+        assert_eq!(recv.iter().collect::<Vec<_>>(), vec![0, 1]);
+
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+        pool.queue("io")?.execute(|| {})?;
+
+        thread::scope(|scope| {
+            let blocked = scope.spawn(|| pool.queue("io")?.execute(|| {}));
+
+            thread::sleep(Duration::from_millis(50));
+            pool.drain();
+            assert!(matches!(
+                blocked.join().unwrap(),
+                Err(ThreadPoolError::Draining)
+            ));
+        });
+        drop(release);
+
+        Ok(())
+    }
+
+    #[test]
+    fn permit_limit_concurrent_job() -> Result<(), ThreadPoolError> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let pool = ThreadPool::new(4)?;
+        pool.permit_set("gpu", 2);
+        assert!(matches!(
+            pool.execute_with_permit("disk", || {}),
+            Err(ThreadPoolError::NoSuchPermit { .. })
+        ));
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (send, recv) = channel();
+
+        for _ in 0..8 {
+            let (running, peak, send) = (Arc::clone(&running), Arc::clone(&peak), send.clone());
+            pool.execute_with_permit("gpu", move || {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(20));
+                running.fetch_sub(1, Ordering::SeqCst);
+                send.send("gpu").unwrap();
+            })?;
+        }
+
+        // Waiting job doesn't hold a worker, so this one run before the first permit is released
+        pool.execute(move || send.send("cpu").unwrap())?;
+        assert_eq!(recv.recv().unwrap(), "cpu");
+
+        assert_eq!(recv.iter().take(8).count(), 8);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dual_pool() -> Result<(), ThreadPoolError> {
+        let pool = DualPool::with_workers(4, 1)?;
+        let (send, recv) = channel();
+
+        let io = send.clone();
+        pool.execute_io(move || io.send(thread::current().name().map(String::from)).unwrap())?;
+        pool.execute_cpu(move || {
+            send.send(thread::current().name().map(String::from))
+                .unwrap()
+        })?;
+
+        let mut names = recv.iter().flatten().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["cpu-0", "io-0"]);
+        assert_eq!(pool.cpu().live_workers(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn recycle_worker_after_max_jobs() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1).max_jobs_per_worker(2).build()?;
+        let mut threads = Vec::new();
+
+        for _ in 0..6 {
+            let (send, recv) = channel();
+            pool.execute(move || send.send(thread::current().id()).unwrap())?;
+            threads.push(recv.recv().unwrap());
+        }
+
+        assert_eq!(threads[0], threads[1]);
+        assert_ne!(threads[1], threads[2]);
+        assert_eq!(threads[2], threads[3]);
+        assert_ne!(threads[3], threads[4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn control_every_worker() -> Result<(), ThreadPoolError> {
+        struct Flush(Arc<Mutex<Vec<usize>>>);
+
+        impl ControlHandler for Flush {
+            type Command = &'static str;
+
+            fn handle(&self, worker: usize, command: &&'static str) {
+                assert_eq!(*command, "flush");
+                self.0.lock().unwrap().push(worker);
+            }
+        }
+
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let pool = ThreadPoolBuilder::new(3)
+            .control_handler(Flush(Arc::clone(&flushed)))
+            .build()?;
+
+        pool.control("flush")?;
+        pool.control_any("flush")?;
+        assert!(matches!(
+            pool.control(42),
+            Err(ThreadPoolError::InvalidConfig { .. })
+        ));
+
+        let start = Instant::now();
+        while flushed.lock().unwrap().len() < 4 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut workers = flushed.lock().unwrap().clone();
+        workers.sort();
+        workers.dedup();
+        assert_eq!(workers, vec![0, 1, 2]);
+        assert_eq!(flushed.lock().unwrap().len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn control_not_handled_by_helping_thread() -> Result<(), ThreadPoolError> {
+        struct Record(Mutex<std::sync::mpsc::Sender<thread::ThreadId>>);
+
+        impl ControlHandler for Record {
+            type Command = ();
+
+            fn handle(&self, _: usize, _: &()) {
+                self.0.lock().unwrap().send(thread::current().id()).unwrap();
+            }
+        }
+
+        let (handled, wait_handled) = channel();
+        let pool = ThreadPoolBuilder::new(1)
+            .control_handler(Record(Mutex::new(handled)))
+            .build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        pool.execute(move || {
+            started.send(()).unwrap();
+            wait_release.recv().unwrap();
+        })?;
+        wait_started.recv().unwrap();
+
+        // The caller help while the only worker is busy, the command is left to the worker
+        pool.control_any(())?;
+        let handle = pool.spawn(|| 42)?;
+        assert_eq!(pool.block_on_handle(handle)?, 42);
+
+        release.send(()).unwrap();
+        let handler = wait_handled.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_ne!(handler, thread::current().id());
+
+        Ok(())
+    }
+
+    #[test]
+    fn config_from_env() -> Result<(), ThreadPoolError> {
+        std::env::set_var("FROM_ENV_TEST_WORKERS", "3");
+        std::env::set_var("FROM_ENV_TEST_REJECTION_POLICY", "Reject");
+
+        let config = PoolConfig::from_env("FROM_ENV_TEST")?;
+        assert_eq!(config.workers, 3);
+        assert_eq!(config.rejection_policy, RejectionPolicy::Reject);
+        assert_eq!(config.queue_capacity, None);
+
+        std::env::set_var("FROM_ENV_TEST_QUEUE_CAPACITY", "a lot");
+        assert!(matches!(
+            PoolConfig::from_env("FROM_ENV_TEST"),
+            Err(ThreadPoolError::InvalidConfig { key }) if key == "FROM_ENV_TEST_QUEUE_CAPACITY"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reconfigure_worker_count() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2).build()?;
+        let config = PoolConfig {
+            workers: 4,
+            keep_alive_ms: 60_000,
+            ..PoolConfig::default()
+        };
+
+        let report = pool.reconfigure(&config)?;
+        assert_eq!(report.applied, vec!["workers", "max_workers"]);
+        assert_eq!(report.spawned_workers, 2);
+        assert_eq!(pool.live_workers(), 4);
+
+        let report = pool.reconfigure(&PoolConfig {
+            workers: 1,
+            queue_capacity: Some(8),
+            ..config
+        })?;
+        assert_eq!(report.terminated_workers, 3);
+        assert_eq!(report.requires_rebuild, vec!["queue_capacity"]);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool.live_workers() > 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(pool.live_workers(), 1);
+
+        // The remaining worker keep index 0
+        let (sender, receiver) = channel();
+        pool.execute_on(0, move || sender.send(()).unwrap())?;
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn inline_without_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(0)?;
+        let caller = thread::current().id();
+        let (send, recv) = channel();
+
+        pool.execute(move || send.send(thread::current().id()).unwrap())?;
+        assert_eq!(recv.try_recv().unwrap(), caller);
+
+        assert_eq!(pool.spawn(|| 40 + 2)?.wait()?, 42);
+        assert!(matches!(
+            pool.spawn(|| panic!("inline job panicked"))?.wait(),
+            Err(ThreadPoolError::JobPanicked)
+        ));
+        assert_eq!(pool.live_workers(), 0);
+
+        // The caller act as a worker while it run the job
+        let (send, recv) = channel();
+        pool.execute(move || {
+            let local = send.clone();
+            send.send(JobContext::in_worker()).unwrap();
+            JobContext::spawn_local(move || local.send(true).unwrap()).unwrap();
+        })?;
+        assert_eq!(recv.try_iter().collect::<Vec<_>>(), vec![true, true]);
+        assert!(!JobContext::in_worker());
+
+        Ok(())
+    }
+
+    #[test]
+    fn partial_spawn_join_spawned_worker() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Fail the third spawn and count the worker thread still running
+        struct FailThird {
+            spawned: AtomicUsize,
+            running: Arc<AtomicUsize>,
+        }
+
+        impl ThreadSpawner for FailThird {
+            fn spawn(
+                &self,
+                name: Option<String>,
+                worker: Box<dyn FnOnce() + Send>,
+            ) -> io::Result<JoinHandle<()>> {
+                if self.spawned.fetch_add(1, Ordering::SeqCst) == 2 {
+                    return Err(io::Error::other("Oh no!"));
+                }
+
+                let running = Arc::clone(&self.running);
+                running.fetch_add(1, Ordering::SeqCst);
+                StdSpawner.spawn(
+                    name,
+                    Box::new(move || {
+                        worker();
+                        running.fetch_sub(1, Ordering::SeqCst);
+                    }),
+                )
+            }
+        }
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let result = ThreadPoolBuilder::new(4)
+            .spawner(FailThird {
+                spawned: AtomicUsize::new(0),
+                running: Arc::clone(&running),
+            })
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(ThreadPoolError::SpawnFailed { index: 2, .. })
+        ));
+        // The two worker spawned before the failure are joined before the error is returned
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn custom_spawner() -> Result<(), ThreadPoolError> {
+        struct Recording(Arc<Mutex<Vec<Option<String>>>>);
+
+        impl ThreadSpawner for Recording {
+            fn spawn(
+                &self,
+                name: Option<String>,
+                worker: Box<dyn FnOnce() + Send>,
+            ) -> io::Result<JoinHandle<()>> {
+                self.0.lock().unwrap().push(name.clone());
+                StdSpawner.spawn(name, worker)
+            }
+        }
+
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let pool = ThreadPoolBuilder::new(2)
+            .thread_name("custom")
+            .spawner(Recording(Arc::clone(&names)))
+            .build()?;
+        let (send, recv) = channel();
+
+        pool.execute(move || send.send(()).unwrap())?;
+        recv.recv().unwrap();
+
+        assert_eq!(
+            *names.lock().unwrap(),
+            vec![
+                Some(String::from("custom-0")),
+                Some(String::from("custom-1"))
+            ]
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg(test)]
+mod config {
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, PoolConfig, RejectionPolicy, ThreadPool,
+    };
+
+    #[test]
+    fn deserialize_config() -> Result<(), ThreadPoolError> {
+        let config: PoolConfig = serde_json::from_str(
+            r#"{ "workers": 2, "queue_capacity": 8, "rejection_policy": "reject" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.workers, 2);
+        assert_eq!(config.rejection_policy, RejectionPolicy::Reject);
+        assert_eq!(config.keep_alive_ms, PoolConfig::default().keep_alive_ms);
+
+        let pool = ThreadPool::from_config(&config)?;
+        assert_eq!(pool.live_workers(), 2);
+        assert_eq!(pool.health().queue_capacity, Some(8));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod stats {
+    use std::sync::mpsc::channel;
+    use std::{thread, time::Duration};
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, JobPanic, PoolEvent, ThreadPool, ThreadPoolBuilder, WorkerStatus,
+    };
+
+    #[test]
+    fn worker_handle_status_and_restart() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2).thread_name("svc").build()?;
+        let (started, wait_started) = channel();
+
+        pool.execute_named("slow", move || {
+            started.send(()).unwrap();
+            thread::sleep(Duration::from_millis(200));
+        })?;
+        wait_started.recv().unwrap();
+
+        let workers = pool.workers();
+        assert_eq!(workers.len(), 2);
+        let mut names: Vec<_> = workers.iter().filter_map(|worker| worker.name()).collect();
+        names.sort();
+        assert_eq!(names, vec!["svc-0", "svc-1"]);
+
+        let busy = workers
+            .iter()
+            .find(|worker| worker.status() != WorkerStatus::Idle)
+            .unwrap();
+        assert_eq!(
+            busy.status(),
+            WorkerStatus::Busy {
+                job: Some(String::from("slow"))
+            }
+        );
+
+        let thread_of = |worker: usize| {
+            let (send, recv) = channel();
+            pool.execute_on(worker, move || send.send(thread::current().id()).unwrap())
+                .unwrap();
+            recv.recv().unwrap()
+        };
+
+        let idle = workers
+            .iter()
+            .find(|worker| worker.id() != busy.id())
+            .unwrap();
+        let before = thread_of(idle.id());
+        idle.restart()?;
+
+        // Job already waiting for the worker still run on the old thread
+        let mut after = thread_of(idle.id());
+        for _ in 0..100 {
+            if after != before {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+            after = thread_of(idle.id());
+        }
+        assert_ne!(after, before);
+        assert_eq!(pool.live_workers(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn padded_counter_under_contention() -> Result<(), ThreadPoolError> {
+        use std::sync::{Arc, Barrier};
+        use std::time::Instant;
+
+        let pool = ThreadPool::new(8)?;
+
+        // Every worker hammer it's own flag and the shared counter at the same time
+        let (send, recv) = channel();
+        for _ in 0..20_000 {
+            let send = send.clone();
+            pool.execute(move || send.send(()).unwrap())?;
+        }
+        drop(send);
+        assert_eq!(recv.iter().count(), 20_000);
+        assert_eq!(pool.stats().queue_wait.count, 20_000);
+
+        // The busy flag of one worker is not mixed up with it's neighbour
+        let barrier = Arc::new(Barrier::new(9));
+        for _ in 0..8 {
+            let barrier = Arc::clone(&barrier);
+            pool.execute(move || {
+                barrier.wait();
+                barrier.wait();
+            })?;
+        }
+        barrier.wait();
+        assert!(pool
+            .workers()
+            .iter()
+            .all(|worker| matches!(worker.status(), WorkerStatus::Busy { .. })));
+        barrier.wait();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool
+            .workers()
+            .iter()
+            .any(|worker| worker.status() != WorkerStatus::Idle)
+            && Instant::now() < deadline
+        {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(pool
+            .workers()
+            .iter()
+            .all(|worker| worker.status() == WorkerStatus::Idle));
+        assert_eq!(pool.live_workers(), 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn queue_wait_latency() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+
+        pool.execute(|| thread::sleep(Duration::from_millis(100)))?;
+        pool.execute(|| {})?;
+        thread::sleep(Duration::from_millis(200));
+
+        let stats = pool.stats();
+        assert_eq!(stats.queue_wait.count, 2);
+        assert!(stats.queue_wait.max >= Duration::from_millis(90));
+        assert!(stats.queue_wait.p50 < Duration::from_millis(90));
+
+        Ok(())
+    }
+
+    #[test]
+    fn job_duration_histogram() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2)
+            .record_job_duration(true)
+            .build()?;
+
+        pool.execute(|| thread::sleep(Duration::from_millis(50)))?;
+        thread::sleep(Duration::from_millis(100));
+
+        let job_duration = pool.stats().job_duration.unwrap();
+        assert_eq!(job_duration.count, 1);
+        assert!(job_duration.p50 >= Duration::from_millis(45));
+
+        pool.reset_stats();
+        assert_eq!(pool.stats().job_duration.unwrap().count, 0);
+        assert!(ThreadPool::new(1)?.stats().job_duration.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn health_report() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+
+        let health = pool.health();
+        assert!(health.is_healthy());
+        assert_eq!((health.live_workers, health.core_workers), (2, 2));
+        assert!(health.queue_open && health.last_panic.is_none());
+
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+        thread::sleep(Duration::from_millis(100));
+
+        let health = pool.health();
+        assert!(!health.is_healthy());
+        assert_eq!(health.live_workers, 1);
+        assert!(health.last_panic.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn panic_counter_and_last_panic() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        assert_eq!((pool.stats().panics, pool.stats().last_panic), (0, None));
+
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+        thread::sleep(Duration::from_millis(100));
+        pool.execute_named("parse", || panic!("Oh no! {} went wrong!", "parsing"))?;
+        thread::sleep(Duration::from_millis(100));
+
+        let stats = pool.stats();
+        assert_eq!(stats.panics, 2);
+        let last_panic = stats.last_panic.unwrap();
+        assert_eq!(last_panic.job.as_deref(), Some("parse"));
+        assert_eq!(
+            last_panic.message.as_deref(),
+            Some("Oh no! parsing went wrong!")
+        );
+
+        let health = pool.health();
+        assert_eq!(health.panics, 2);
+        assert!(health.last_panic_details.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stats_by_job_name() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+
+        for _ in 0..3 {
+            pool.execute_named("resize", || thread::sleep(Duration::from_millis(5)))?;
+        }
+        pool.execute_named("parse", || panic!("Oh no! Something went wrong!"))?;
+        pool.execute(|| {})?;
+        thread::sleep(Duration::from_millis(100));
+
+        let by_name = pool.stats_by_name();
+        assert_eq!(by_name.len(), 2);
+        let resize = by_name["resize"];
+        assert_eq!((resize.completed, resize.panicked), (3, 0));
+        assert_eq!(resize.duration.count, 3);
+        assert!(resize.duration.max >= Duration::from_millis(5));
+        assert_eq!(
+            (by_name["parse"].completed, by_name["parse"].panicked),
+            (0, 1)
+        );
+
+        pool.reset_stats();
+        assert!(pool.stats_by_name().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn capture_panic_backtrace() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .capture_panic_backtrace(true)
+            .build()?;
+
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+        thread::sleep(Duration::from_millis(100));
+
+        let backtrace = pool.stats().last_panic.unwrap().backtrace.unwrap();
+        assert!(backtrace.contains("capture_panic_backtrace"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn stats_to_json() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2)
+            .record_job_duration(true)
+            .build()?;
+
+        pool.execute_named("parse", || panic!("Oh no! Something went wrong!"))?;
+        pool.execute(|| {})?;
+        thread::sleep(Duration::from_millis(100));
+
+        let stats: serde_json::Value = serde_json::from_str(&pool.stats().to_json()).unwrap();
+        assert_eq!(stats["counters"]["panics"], 1);
+        assert!(stats["gauges"]["live_workers"].is_u64());
+        assert_eq!(stats["gauges"]["queue_depth"], 0);
+        assert!(stats["histograms"]["queue_wait"]["count"].as_u64() >= Some(1));
+        assert!(stats["histograms"]["job_duration"]["p99_us"].is_u64());
+        assert_eq!(stats["last_panic"]["job"], "parse");
+        assert_eq!(
+            stats["last_panic"]["message"],
+            "Oh no! Something went wrong!"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_panic_to_reporter() -> Result<(), ThreadPoolError> {
+        let (report, reported) = channel();
+        let report = std::sync::Mutex::new(report);
+        let pool = ThreadPoolBuilder::new(1)
+            .panic_reporter(move |panic: &JobPanic<'_>| {
+                let payload = panic.payload.downcast_ref::<&str>().copied();
+                report
+                    .lock()
+                    .unwrap()
+                    .send((panic.worker, panic.details.job.clone(), payload))
+                    .unwrap();
+            })
+            .build()?;
+
+        pool.execute_named("parse", || panic!("Oh no! Something went wrong!"))?;
+
+        let (worker, job, payload) = reported.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(worker, Some(0));
+        assert_eq!(job.as_deref(), Some("parse"));
+        assert_eq!(payload, Some("Oh no! Something went wrong!"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn subscribe_pool_event() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1).lazy(true).build()?;
+        let events = pool.subscribe();
+
+        pool.execute_named("resize", || {})?;
+        pool.execute(|| panic!("Oh no! Something went wrong!"))?;
+
+        let events: Vec<PoolEvent> = events
+            .iter()
+            .take_while(|event| !matches!(event, PoolEvent::WorkerExited { .. }))
+            .collect();
+        let resize = Some(String::from("resize"));
+
+        assert_eq!(events[0], PoolEvent::WorkerSpawned { worker: 0 });
+        assert!(events.contains(&PoolEvent::JobQueued {
+            job: resize.clone()
+        }));
+        assert!(events.contains(&PoolEvent::JobStarted {
+            worker: Some(0),
+            job: resize.clone()
+        }));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            PoolEvent::JobFinished { worker: Some(0), job, .. } if *job == resize
+        )));
+        assert_eq!(
+            events.last(),
+            Some(&PoolEvent::JobPanicked {
+                worker: Some(0),
+                job: None
+            })
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod job {
+    use std::sync::mpsc::channel;
+    use std::sync::Arc;
+    use std::{thread, time::Duration};
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, DropPolicy, ThreadPool, ThreadPoolBuilder,
+    };
+
+    #[test]
+    fn small_and_big_closure() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let (send, recv) = channel();
+
+        let small = send.clone();
+        pool.execute(move || small.send(1u64).unwrap())?;
+
+        let big = [2u64; 16];
+        let medium = send.clone();
+        pool.execute(move || medium.send(big.iter().sum()).unwrap())?;
+
+        let huge = [1u64; 1024];
+        pool.execute(move || send.send(huge.iter().sum()).unwrap())?;
+
+        let mut result: Vec<u64> = recv.iter().take(3).collect();
+        result.sort();
+        assert_eq!(result, vec![1, 32, 1024]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn recycled_job_keep_it_capture() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(4)?;
+        let (send, recv) = channel();
+
+        // Block freed by the worker are handed back to the submitting thread through the shared overflow,
+        // and the one cached by a submitter that exited are reused by the other
+        thread::scope(|scope| {
+            for submitter in 0..4u64 {
+                let (pool, send) = (&pool, send.clone());
+                scope.spawn(move || {
+                    for i in 0..2_000u64 {
+                        let value = submitter * 2_000 + i;
+                        let (small, big) = (send.clone(), send.clone());
+                        let medium = [value; 12];
+                        let large = [value; 100];
+                        pool.execute(move || small.send(medium[11]).unwrap())
+                            .unwrap();
+                        pool.execute(move || big.send(large[99]).unwrap()).unwrap();
+                    }
+                });
+            }
+        });
+        drop(send);
+
+        let mut received: Vec<u64> = recv.iter().collect();
+        received.sort();
+        let expected: Vec<u64> = (0..8_000).flat_map(|value| [value, value]).collect();
+        assert_eq!(received, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn discarded_job_drop_it_capture() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .on_drop(DropPolicy::FinishRunningOnly)
+            .build()?;
+        let (started, wait_started) = channel();
+        let small = Arc::new(());
+        let big = (Arc::new(()), [0u64; 16]);
+
+        pool.execute(move || {
+            started.send(()).unwrap();
+            thread::sleep(Duration::from_millis(50));
+        })?;
+
+        let captured = Arc::clone(&small);
+        pool.execute(move || drop(captured))?;
+        let captured = (Arc::clone(&big.0), big.1);
+        pool.execute(move || drop(captured))?;
+
+        wait_started.recv().unwrap();
+        drop(pool);
+
+        assert_eq!(Arc::strong_count(&small), 1);
+        assert_eq!(Arc::strong_count(&big.0), 1);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod context {
+    use std::cell::Cell;
+    use std::sync::mpsc::channel;
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, ContextPropagator, JobContext, ThreadPool, ThreadPoolBuilder,
+    };
+
+    thread_local! {
+        static REQUEST_ID: Cell<Option<u64>> = const { Cell::new(None) };
+    }
+
+    struct RequestId;
+
+    impl ContextPropagator for RequestId {
+        type Context = Option<u64>;
+
+        fn capture(&self) -> Option<u64> {
+            REQUEST_ID.get()
+        }
+
+        fn install(&self, context: &Option<u64>) {
+            REQUEST_ID.set(*context);
+        }
+
+        fn uninstall(&self, _context: Option<u64>) {
+            REQUEST_ID.set(None);
+        }
+    }
+
+    #[test]
+    fn spawn_local() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let (send, recv) = channel();
+
+        pool.execute(move || {
+            for i in 0..4 {
+                let send = send.clone();
+                JobContext::spawn_local(move || {
+                    assert!(JobContext::in_worker());
+                    send.send(i).unwrap();
+                })
+                .unwrap();
+            }
+        })?;
+
+        assert_eq!(recv.iter().collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn propagate_context() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .context_propagator(RequestId)
+            .build()?;
+        let (send, recv) = channel();
+
+        for id in [Some(40), None] {
+            REQUEST_ID.set(id);
+            let send = send.clone();
+            pool.execute(move || send.send(REQUEST_ID.get()).unwrap())?;
+        }
+        drop(send);
+
+        assert_eq!(recv.iter().collect::<Vec<_>>(), vec![Some(40), None]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn spawn_local_outside_worker() {
+        assert!(!JobContext::in_worker());
+        assert!(matches!(
+            JobContext::spawn_local(|| {}),
+            Err(ThreadPoolError::NotInWorker)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod route {
+    use std::collections::HashSet;
+    use std::io;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+    use std::sync::{Arc, Barrier};
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, StdSpawner, ThreadPool, ThreadPoolBuilder, ThreadSpawner,
+    };
+
+    /// Panic the only worker of the pool and wait until it's gone
+    fn kill_worker(pool: &ThreadPool) -> Result<(), ThreadPoolError> {
+        pool.execute_on(0, || panic!("Oh no!"))?;
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool.live_workers() > 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(pool.live_workers(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn broadcast_run_once_per_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(3)?;
+        let (send, recv) = channel();
+
+        // Wait until every worker is running before broadcasting
+        let barrier = Arc::new(Barrier::new(4));
+        for _ in 0..3 {
+            let barrier = Arc::clone(&barrier);
+            pool.execute(move || {
+                barrier.wait();
+            })?;
+        }
+        barrier.wait();
+
+        pool.broadcast(move || send.send(thread::current().id()).unwrap())?;
+
+        let threads: HashSet<_> = recv.iter().take(3).collect();
+        assert_eq!(threads.len(), 3);
+        assert!(recv.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_on_same_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let (send, recv) = channel();
+
+        for i in 0..8 {
+            let send = send.clone();
+            pool.execute_on(i % 2, move || {
+                send.send((i % 2, thread::current().id())).unwrap()
+            })?;
+        }
+
+        let threads: HashSet<_> = recv.iter().take(8).collect();
+        assert_eq!(threads.len(), 2);
+        assert!(matches!(
+            pool.execute_on(2, || {}),
+            Err(ThreadPoolError::NoSuchWorker { worker: 2 })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_on_retired_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .max_workers(3)
+            .keep_alive(Duration::from_millis(20))
+            .build()?;
+        let (send, recv) = channel();
+
+        // Worker 0 is idle, a worker is still spawned to own index 2
+        for _ in 0..2 {
+            let send = send.clone();
+            pool.execute_on(2, move || send.send(()).unwrap())?;
+            recv.recv_timeout(Duration::from_secs(5)).unwrap();
+
+            // Let the extra worker retire
+            thread::sleep(Duration::from_millis(200));
+        }
+        assert_eq!(pool.live_workers(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_on_dead_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (send, recv) = channel();
+
+        // Nothing replace the worker with the default restart policy, an owner is spawned for the job
+        kill_worker(&pool)?;
+        let sender = send.clone();
+        pool.execute_on(0, move || sender.send(()).unwrap())?;
+        recv.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        kill_worker(&pool)?;
+        pool.execute_sticky("alice", move || send.send(()).unwrap())?;
+        recv.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_on_dead_worker_without_replacement() -> Result<(), ThreadPoolError> {
+        /// Only spawn the first worker
+        struct Once(AtomicUsize);
+
+        impl ThreadSpawner for Once {
+            fn spawn(
+                &self,
+                name: Option<String>,
+                worker: Box<dyn FnOnce() + Send>,
+            ) -> io::Result<JoinHandle<()>> {
+                if self.0.fetch_add(1, Ordering::SeqCst) > 0 {
+                    return Err(io::Error::other("Oh no!"));
+                }
+                StdSpawner.spawn(name, worker)
+            }
+        }
+
+        let pool = ThreadPoolBuilder::new(1)
+            .spawner(Once(AtomicUsize::new(0)))
+            .build()?;
+        kill_worker(&pool)?;
+
+        // The job is given back instead of waiting forever in the dead worker inbox
+        let (send, recv) = channel::<()>();
+        let sender = send.clone();
+        assert!(matches!(
+            pool.execute_on(0, move || sender.send(()).unwrap()),
+            Err(ThreadPoolError::SpawnFailed { .. })
+        ));
+        assert!(matches!(
+            pool.execute_sticky("alice", move || send.send(()).unwrap()),
+            Err(ThreadPoolError::SpawnFailed { .. })
+        ));
+        assert_eq!(
+            recv.recv_timeout(Duration::from_secs(1)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_sticky_same_key_same_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(4)?;
+        let (send, recv) = channel();
+
+        for i in 0..32 {
+            let send = send.clone();
+            let key = ["alice", "bob", "carol"][i % 3];
+            pool.execute_sticky(key, move || {
+                send.send((key, thread::current().id())).unwrap()
+            })?;
+        }
+
+        let threads: HashSet<_> = recv.iter().take(32).collect();
+        assert_eq!(threads.len(), 3);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod drain {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::{thread, time::Duration};
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, PoolEvent, PoolState, ShutdownPhase, ThreadPool, ThreadPoolBuilder,
+    };
+
+    #[test]
+    fn drain_refuse_new_job() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let job_finished = Arc::clone(&finished);
+        pool.execute(move || {
+            thread::sleep(Duration::from_millis(100));
+            job_finished.store(true, Ordering::SeqCst);
+        })?;
+
+        pool.drain();
+        assert!(matches!(
+            pool.execute(|| {}),
+            Err(ThreadPoolError::Draining)
+        ));
+
+        pool.wait_for_drain();
+        assert!(finished.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shutdown_phase_in_order() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let events = pool.subscribe();
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let job_finished = Arc::clone(&finished);
+        pool.execute(move || {
+            thread::sleep(Duration::from_millis(100));
+            job_finished.store(true, Ordering::SeqCst);
+        })?;
+
+        let mut phases = Vec::new();
+        pool.shutdown_with(|phase| {
+            // The queued job is done before the drained phase
+            assert_eq!(
+                finished.load(Ordering::SeqCst),
+                phase != ShutdownPhase::Draining
+            );
+            phases.push(phase);
+        });
+        assert_eq!(
+            phases,
+            [
+                ShutdownPhase::Draining,
+                ShutdownPhase::Drained,
+                ShutdownPhase::Stopped
+            ]
+        );
+
+        let observed: Vec<ShutdownPhase> = events
+            .iter()
+            .filter_map(|event| match event {
+                PoolEvent::Shutdown { phase } => Some(phase),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(observed, phases);
+
+        Ok(())
+    }
+
+    #[test]
+    fn state_watch_yield_change() -> Result<(), ThreadPoolError> {
+        use std::sync::mpsc::channel;
+
+        let pool = ThreadPoolBuilder::new(1).high_water_mark(2).build()?;
+        let mut states = pool.state_watch();
+        assert_eq!(states.try_recv(), None);
+        assert_eq!(states.borrow().unwrap().live_workers, 1);
+
+        let (release, wait_release) = channel::<()>();
+        pool.execute(move || {
+            let _ = wait_release.recv();
+        })?;
+        pool.execute(|| {})?;
+        pool.execute(|| {})?;
+
+        let mut next = |matches: fn(&PoolState) -> bool| {
+            states
+                .by_ref()
+                .find(matches)
+                .expect("the pool is still open")
+        };
+        assert!(next(|state| state.above_high_water).queue_depth >= 2);
+        drop(release);
+        next(|state| !state.above_high_water);
+
+        pool.drain();
+        assert!(next(|state| state.draining).draining);
+        drop(pool);
+        assert_eq!(states.find(|_| false), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn serve_until_drain() -> Result<(), ThreadPoolError> {
+        use std::io::{Read, Write};
+        use std::net::{TcpListener, TcpStream};
+        use std::sync::mpsc::channel;
+
+        let pool = ThreadPool::new(2)?;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let finished = Arc::new(AtomicBool::new(false));
+        let (started, wait_started) = channel();
+
+        let job_finished = Arc::clone(&finished);
+        thread::scope(|scope| {
+            let server = scope.spawn(|| {
+                pool.serve(listener, move |mut stream| {
+                    let mut request = [0; 4];
+                    stream.read_exact(&mut request).unwrap();
+                    if &request == b"slow" {
+                        started.send(()).unwrap();
+                        thread::sleep(Duration::from_millis(100));
+                        job_finished.store(true, Ordering::SeqCst);
+                    }
+                    stream.write_all(b"done").unwrap();
+                })
+            });
+
+            let mut response = String::new();
+            let mut client = TcpStream::connect(address).unwrap();
+            client.write_all(b"fast").unwrap();
+            client.read_to_string(&mut response).unwrap();
+            assert_eq!(response, "done");
+
+            // The connection in flight is still handled once the pool start draining
+            let mut client = TcpStream::connect(address).unwrap();
+            client.write_all(b"slow").unwrap();
+            wait_started.recv().unwrap();
+            pool.drain();
+
+            server.join().unwrap().unwrap();
+            assert!(finished.load(Ordering::SeqCst));
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn serve_with_grace_cancel_stuck_connection() -> Result<(), ThreadPoolError> {
+        use std::net::{TcpListener, TcpStream};
+        use std::sync::mpsc::channel;
+        use std::time::Instant;
+
+        let pool = ThreadPool::new(2)?;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (started, wait_started) = channel();
+        let (cancelled, wait_cancelled) = channel();
+
+        thread::scope(|scope| {
+            let server = scope.spawn(|| {
+                pool.serve_with_grace(listener, Duration::from_millis(50), move |_, token| {
+                    started.send(()).unwrap();
+                    // A client that never finish
+                    while !token.is_cancelled() {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+                    cancelled.send(()).unwrap();
+                })
+            });
+
+            let _client = TcpStream::connect(address).unwrap();
+            wait_started.recv().unwrap();
+            let start = Instant::now();
+            pool.drain();
+
+            server.join().unwrap().unwrap();
+            assert!(start.elapsed() < Duration::from_secs(5));
+            wait_cancelled.recv_timeout(Duration::from_secs(5)).unwrap();
+        });
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "signal", unix))]
+    #[test]
+    fn drain_on_signal() -> Result<(), ThreadPoolError> {
+        use unknownrori_simple_thread_pool::signal_hook::{consts::SIGUSR1, low_level};
+
+        let pool = ThreadPool::new(2)?;
+        pool.drain_on_signal(&[SIGUSR1]).unwrap();
+        assert!(!pool.is_draining());
+
+        low_level::raise(SIGUSR1).unwrap();
+        pool.wait_for_drain();
+        assert!(pool.is_draining());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mock {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, Executor, MockPool};
+
+    fn record(
+        executor: &impl Executor,
+        log: &Arc<Mutex<Vec<usize>>>,
+    ) -> Result<(), ThreadPoolError> {
+        for i in 0..3 {
+            let log = Arc::clone(log);
+            executor.execute(move || log.lock().unwrap().push(i))?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn inline_and_manual() -> Result<(), ThreadPoolError> {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        record(&MockPool::inline(), &log)?;
+        assert_eq!(*log.lock().unwrap(), vec![0, 1, 2]);
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pool = MockPool::manual();
+        record(&pool, &log)?;
+        assert!(log.lock().unwrap().is_empty());
+        assert_eq!(pool.pending(), 3);
+
+        assert_eq!(pool.run_pending(), 3);
+        assert_eq!(*log.lock().unwrap(), vec![0, 1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn delayed_job_follow_manual_clock() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pool = MockPool::manual();
+
+        for (i, delay) in [(0, 30), (1, 10), (2, 20)] {
+            let log = Arc::clone(&log);
+            pool.execute_after(Duration::from_secs(delay), move || {
+                log.lock().unwrap().push(i)
+            });
+        }
+
+        assert_eq!(pool.advance(Duration::from_secs(5)), 0);
+        assert_eq!(pool.advance(Duration::from_secs(20)), 2);
+        assert_eq!(pool.run_pending(), 2);
+        assert_eq!(*log.lock().unwrap(), vec![1, 2]);
+
+        assert_eq!(pool.advance(Duration::from_secs(10)), 1);
+        pool.run_pending();
+        assert_eq!(*log.lock().unwrap(), vec![1, 2, 0]);
+    }
+}
+
+#[cfg(test)]
+mod handle {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::mpsc::channel;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, join_all, select_cancel, try_join_all_cancel, Barrier, BatchError,
+        BatchPolicy, CountDownLatch, JobError, JoinSet, TaskGraph, TaskGroup, ThreadPool,
+        WaitGroup,
+    };
+
+    #[test]
+    fn batch_wait_collect() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+
+        let values = pool
+            .execute_batch((0..8).map(|i| move || Ok::<_, String>(i * 2)))?
+            .wait_collect();
+        assert_eq!(values.unwrap(), (0..8).map(|i| i * 2).collect::<Vec<_>>());
+
+        let jobs = (0..8).map(|i| {
+            move || match i {
+                3 => panic!("Oh no"),
+                i if i % 2 == 1 => Err(i),
+                i => Ok(i),
+            }
+        });
+        let Err(BatchError::Many(errors)) = pool
+            .execute_batch(jobs)?
+            .policy(BatchPolicy::CollectAll)
+            .wait_collect()
+        else {
+            panic!("every failure should be collected");
+        };
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(errors[0], BatchError::Job { index: 1, error: 1 }));
+        assert!(matches!(
+            errors[1],
+            BatchError::Pool {
+                index: 3,
+                error: ThreadPoolError::JobPanicked
+            }
+        ));
+
+        let first = pool
+            .execute_batch([|| Err("first"), || Ok(())])?
+            .wait_collect();
+        assert!(matches!(first, Err(BatchError::Job { error: "first", .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn task_graph_dependency_order() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(3)?;
+        let (sender, receiver) = channel();
+        let mut graph = TaskGraph::new();
+
+        let log = sender.clone();
+        graph.add("link", &["left", "right"], move || {
+            log.send("link").unwrap()
+        });
+        for name in ["left", "right"] {
+            let log = sender.clone();
+            graph.add(name, &["fetch"], move || log.send(name).unwrap());
+        }
+        graph
+            .add("fetch", &[], move || sender.send("fetch").unwrap())
+            .add("broken", &[], || panic!("Oh no"))
+            .add("after-broken", &["broken"], || ());
+
+        let results = pool.run(graph)?;
+        let order: Vec<_> = receiver.iter().collect();
+        assert_eq!(order.first(), Some(&"fetch"));
+        assert_eq!(order.last(), Some(&"link"));
+        assert_eq!(order.len(), 4);
+        assert!(results["link"].is_ok());
+        assert!(matches!(
+            results["broken"],
+            Err(ThreadPoolError::JobPanicked)
+        ));
+        assert!(matches!(
+            results["after-broken"],
+            Err(ThreadPoolError::Cancelled)
+        ));
+
+        let mut cycle = TaskGraph::new();
+        cycle.add("a", &["b"], || ()).add("b", &["a"], || ());
+        assert!(matches!(
+            pool.run(cycle),
+            Err(ThreadPoolError::InvalidGraph { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn then_chain_continuation() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+
+        let length = pool
+            .spawn(|| String::from("hello"))?
+            .then(|word| word.map_or(0, |word| word.len()))
+            .then(|length| length.map_or(0, |length| length * 2));
+        assert_eq!(length.wait()?, 10);
+
+        // The only worker is busy, so the second job is still queued when it's aborted
+        let (release, blocked) = channel::<()>();
+        pool.execute(move || blocked.recv().unwrap())?;
+        let aborted = pool.spawn(|| 42)?;
+        assert!(aborted.abort());
+
+        let reported = aborted.then(|result| matches!(result, Err(ThreadPoolError::Cancelled)));
+        release.send(()).unwrap();
+        assert!(reported.wait()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn task_group_wait() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let group = TaskGroup::new(&pool);
+
+        let handles = (0..4)
+            .map(|i| group.spawn(move || i * 2))
+            .collect::<Result<Vec<_>, _>>()?;
+        group.wait();
+
+        assert!(handles.iter().all(|handle| handle.is_finished()));
+        assert_eq!(
+            handles
+                .into_iter()
+                .map(|handle| handle.wait())
+                .collect::<Result<Vec<_>, _>>()?,
+            vec![0, 2, 4, 6]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn task_group_cancel_on_drop() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (started, wait_started) = channel();
+        let group = TaskGroup::new(&pool);
+
+        let running = group.spawn_cancellable(move |token| {
+            started.send(()).unwrap();
+            while !token.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            40
+        })?;
+        let queued = group.spawn(|| 2)?;
+
+        wait_started.recv().unwrap();
+        drop(group);
+
+        assert_eq!(running.wait()?, 40);
+        assert!(matches!(queued.wait(), Err(ThreadPoolError::Cancelled)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_set_completion_order() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let (release, wait_release) = channel::<()>();
+        let mut set = JoinSet::new();
+
+        let slow = set.spawn(&pool, move || {
+            let _ = wait_release.recv();
+            "slow"
+        })?;
+        let fast = set.spawn(&pool, || "fast")?;
+
+        let (id, result) = set.join_next().unwrap();
+        assert_eq!((id, result?), (fast, "fast"));
+        assert!(set.try_join_next().is_none());
+
+        drop(release);
+        let (id, result) = set.join_next().unwrap();
+        assert_eq!((id, result?), (slow, "slow"));
+        assert!(set.join_next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn abort_queued_job() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+
+        let running = pool.spawn(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+            40
+        })?;
+        let queued = pool.spawn(|| 2)?;
+        wait_started.recv().unwrap();
+
+        assert!(queued.abort());
+        assert!(!running.abort());
+        drop(release);
+
+        assert_eq!(running.wait()?, 40);
+        assert!(matches!(queued.wait(), Err(ThreadPoolError::Cancelled)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn job_timeout() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (stopped, wait_stopped) = channel();
+
+        let slow = pool.execute_with_timeout(Duration::from_millis(50), move |token| {
+            while !token.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            stopped.send(()).unwrap();
+        })?;
+        assert!(matches!(slow.wait(), Err(ThreadPoolError::Timeout)));
+        wait_stopped.recv().unwrap();
+
+        let fast = pool.execute_with_timeout(Duration::from_secs(10), |_| 42)?;
+        assert_eq!(fast.wait()?, 42);
+
+        Ok(())
+    }
+
+    #[test]
+    fn select_cancel_queued() -> Result<(), ThreadPoolError> {
+        let busy = ThreadPool::new(1)?;
+        let idle = ThreadPool::new(1)?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let ran = Arc::new(AtomicBool::new(false));
+
+        busy.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        let queued_ran = Arc::clone(&ran);
+        let queued = busy.spawn(move || queued_ran.store(true, Ordering::SeqCst))?;
+        let fast = idle.spawn(|| ())?;
+
+        let (index, result) = select_cancel([queued, fast]).unwrap();
+        assert_eq!(index, 1);
+        assert!(result.is_ok());
+
+        drop(release);
+        drop(busy);
+        assert!(!ran.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_all_and_try_join_all_cancel() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let handles = (0..4)
+            .map(|i| pool.spawn(move || i * 2))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values: Vec<i32> = join_all(handles).into_iter().map(Result::unwrap).collect();
+        assert_eq!(values, [0, 2, 4, 6]);
+
+        let busy = ThreadPool::new(1)?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+        let ran = Arc::new(AtomicBool::new(false));
+
+        busy.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        let queued_ran = Arc::clone(&ran);
+        let queued = busy.spawn(move || queued_ran.store(true, Ordering::SeqCst))?;
+        let failed = pool.spawn(|| panic!("Oh no! Something went wrong!"))?;
+
+        assert!(matches!(
+            try_join_all_cancel([queued, failed]),
+            Err(ThreadPoolError::JobPanicked)
+        ));
+
+        drop(release);
+        drop(busy);
+        assert!(!ran.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_for_result() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+
+        assert_eq!(pool.spawn(|| 40 + 2)?.wait()?, 42);
+
+        let panicked = pool.spawn(|| panic!("Oh no! Something went wrong!"))?;
+        assert!(matches!(
+            panicked.wait(),
+            Err::<(), _>(ThreadPoolError::JobPanicked)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested_wait_help_instead_of_deadlock() -> Result<(), ThreadPoolError> {
+        let pool = Arc::new(ThreadPool::new(1)?);
+
+        let inner_pool = Arc::clone(&pool);
+        let outer = pool.spawn(move || {
+            let inner = inner_pool.spawn(|| 40)?;
+            Ok::<_, ThreadPoolError>(inner.wait()? + 2)
+        })?;
+
+        assert_eq!(outer.wait()??, 42);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_with_result() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+
+        assert_eq!(pool.execute_with_result(|| 40).recv()?, 40);
+
+        let result = pool.execute_with_result(|| {
+            thread::sleep(Duration::from_millis(200));
+            2
+        });
+        assert!(matches!(
+            result.recv_timeout(Duration::from_millis(10)),
+            Err(ThreadPoolError::Timeout)
+        ));
+        assert_eq!(result.recv_timeout(Duration::from_secs(5))?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_on_handle_run_queued_job() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+
+        // Keep the only worker busy, the caller has to run the job itself
+        pool.execute(move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        let caller = thread::current().id();
+        let handle = pool.spawn(move || thread::current().id() == caller)?;
+        assert!(pool.block_on_handle(handle)?);
+
+        drop(release);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_group_track_subset() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let group = WaitGroup::new();
+        let finished = Arc::new(AtomicUsize::new(0));
+        let (release, wait_release) = channel::<()>();
+
+        // Not part of the group, the wait doesn't depend on it
+        pool.execute(move || {
+            let _ = wait_release.recv();
+        })?;
+        for _ in 0..4 {
+            let finished = Arc::clone(&finished);
+            pool.execute_tracked(&group, move || {
+                thread::sleep(Duration::from_millis(20));
+                finished.fetch_add(1, Ordering::SeqCst);
+            })?;
+        }
+
+        group.wait();
+        assert_eq!(finished.load(Ordering::SeqCst), 4);
+        drop(release);
+
+        Ok(())
+    }
+
+    #[test]
+    fn barrier_with_more_job_than_worker() -> Result<(), ThreadPoolError> {
+        // Every waiting worker run the other job, otherwise the two worker would wait forever
+        let pool = ThreadPool::new(2)?;
+        let phase = Barrier::new(4);
+        let first_phase = Arc::new(AtomicUsize::new(0));
+        let leaders = Arc::new(AtomicUsize::new(0));
+        let done = CountDownLatch::new(4);
+
+        for _ in 0..4 {
+            let phase = phase.clone();
+            let first_phase = Arc::clone(&first_phase);
+            let leaders = Arc::clone(&leaders);
+            let done = done.clone();
+            pool.execute(move || {
+                first_phase.fetch_add(1, Ordering::SeqCst);
+                if phase.wait() {
+                    leaders.fetch_add(1, Ordering::SeqCst);
+                }
+                // Nobody move past the barrier before every job is done with the first phase
+                assert_eq!(first_phase.load(Ordering::SeqCst), 4);
+                done.count_down();
+            })?;
+        }
+
+        done.wait();
+        assert_eq!(done.count(), 0);
+        assert_eq!(leaders.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_singleflight_share_result() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let runs = Arc::new(AtomicUsize::new(0));
+        let (release, wait_release) = channel::<()>();
+
+        // Keep the only worker busy so both submission find the first one still queued
+        pool.execute(move || {
+            let _ = wait_release.recv();
+        })?;
+
+        let fetch = |runs: &Arc<AtomicUsize>| {
+            let runs = Arc::clone(runs);
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+                String::from("fetched")
+            }
+        };
+        let first = pool.execute_singleflight("user:42", fetch(&runs))?;
+        let second = pool.execute_singleflight("user:42", fetch(&runs))?;
+        let other = pool.execute_singleflight("user:7", fetch(&runs))?;
+        drop(release);
+
+        assert_eq!(first.wait()?, "fetched");
+        assert_eq!(second.wait()?, "fetched");
+        assert_eq!(other.wait()?, "fetched");
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        // The flight has landed, the same key run again
+        pool.execute_singleflight("user:42", fetch(&runs))?.wait()?;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_debounced_run_latest() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let (sender, receiver) = channel();
+
+        for change in 0..3 {
+            let sender = sender.clone();
+            pool.execute_debounced("rebuild", Duration::from_millis(50), move || {
+                sender.send(change).unwrap();
+            })?;
+        }
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 2);
+        thread::sleep(Duration::from_millis(100));
+        assert!(receiver.try_recv().is_err());
+
+        // The window is over, a new submission run again
+        pool.execute_debounced("rebuild", Duration::from_millis(10), move || {
+            sender.send(3).unwrap();
+        })?;
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_debounced_window_not_queue_wait() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (sender, receiver) = channel();
+
+        pool.execute_debounced("rebuild", Duration::from_millis(200), move || {
+            sender.send(()).unwrap();
+        })?;
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        let stats = pool.stats();
+        assert_eq!(stats.queue_wait.count, 1);
+        assert!(stats.queue_wait.max < Duration::from_millis(200));
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_debounced_flushed_on_drop() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (sender, receiver) = channel();
+
+        pool.execute_debounced("rebuild", Duration::from_secs(60), move || {
+            sender.send(()).unwrap();
+        })?;
+
+        // The pending job run before the pool is gone instead of waiting for it's window
+        let start = Instant::now();
+        drop(pool);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(receiver.try_recv().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_debounced_many_keys() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        // Waiting key don't hold the only worker
+        for key in 0..20_000 {
+            let runs = Arc::clone(&runs);
+            pool.execute_debounced(&key.to_string(), Duration::from_millis(10), move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })?;
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while runs.load(Ordering::SeqCst) < 20_000 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 20_000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_cached_until_expired() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let runs = Arc::new(AtomicUsize::new(0));
+        let ttl = Duration::from_millis(100);
+        let compute = |runs: &Arc<AtomicUsize>| {
+            let runs = Arc::clone(runs);
+            move || runs.fetch_add(1, Ordering::SeqCst)
+        };
+
+        assert_eq!(
+            pool.execute_cached("rates", ttl, compute(&runs))?.wait()?,
+            0
+        );
+        assert_eq!(
+            pool.execute_cached("rates", ttl, compute(&runs))?.wait()?,
+            0
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        thread::sleep(ttl * 2);
+        assert_eq!(
+            pool.execute_cached("rates", ttl, compute(&runs))?.wait()?,
+            1
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_fallible_outcome() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+
+        let ok = pool.execute_fallible(|| Ok::<_, String>(42))?;
+        let failed = pool.execute_fallible(|| Err::<i32, _>(String::from("bad input")))?;
+        let panicked = pool.execute_fallible(|| -> Result<i32, String> { panic!("Oh no") })?;
+
+        assert_eq!(ok.wait_fallible().unwrap(), 42);
+        assert!(matches!(failed.wait_fallible(), Err(JobError::Failed(err)) if err == "bad input"));
+        assert!(matches!(panicked.wait_fallible(), Err(JobError::Panicked)));
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "metrics")]
+#[cfg(test)]
+mod metrics {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPoolBuilder};
+
+    #[test]
+    fn publish_metrics_labeled_by_pool() -> Result<(), ThreadPoolError> {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let (io, cpu) = ::metrics::with_local_recorder(&recorder, || {
+            Ok::<_, ThreadPoolError>((
+                ThreadPoolBuilder::new(2).name("io").build()?,
+                ThreadPoolBuilder::new(1).name("cpu").build()?,
+            ))
+        })?;
+
+        for _ in 0..3 {
+            io.execute(|| {})?;
+        }
+        cpu.execute(|| {})?;
+        // Dropping the pool wait for every job to finish
+        drop(io);
+        drop(cpu);
+
+        // Reading a counter reset it, so every value is read from the same snapshot
+        let snapshot = snapshotter.snapshot().into_vec();
+        let counter = |name: &str, pool: &str| {
+            snapshot
+                .iter()
+                .find(|(key, ..)| {
+                    key.kind() == MetricKind::Counter
+                        && key.key().name() == name
+                        && key
+                            .key()
+                            .labels()
+                            .any(|label| label.key() == "pool" && label.value() == pool)
+                })
+                .map(|(.., value)| value)
+        };
+        assert_eq!(
+            counter("thread_pool_jobs_completed_total", "io"),
+            Some(&DebugValue::Counter(3))
+        );
+        assert_eq!(
+            counter("thread_pool_jobs_completed_total", "cpu"),
+            Some(&DebugValue::Counter(1))
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "otel")]
+#[cfg(test)]
+mod otel {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use opentelemetry::trace::{Span as _, SpanId, TraceContextExt, Tracer};
+    use opentelemetry::{global, Context};
+    use opentelemetry_sdk::error::OTelSdkResult;
+    use opentelemetry_sdk::trace::{SdkTracerProvider, Span, SpanData, SpanProcessor};
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, SpanRelation, ThreadPoolBuilder};
+
+    #[derive(Debug, Clone, Default)]
+    struct Collect(Arc<Mutex<Vec<SpanData>>>);
+
+    impl SpanProcessor for Collect {
+        fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+        fn on_end(&self, span: SpanData) {
+            self.0.lock().unwrap().push(span);
+        }
+
+        fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn job_span_linked_or_nested() -> Result<(), ThreadPoolError> {
+        let spans = Collect::default();
+        global::set_tracer_provider(
+            SdkTracerProvider::builder()
+                .with_span_processor(spans.clone())
+                .build(),
+        );
+
+        for relation in [SpanRelation::Link, SpanRelation::Parent] {
+            let pool = ThreadPoolBuilder::new(1).otel_spans(relation).build()?;
+            let request = global::tracer("test").start("request");
+            let submitter = request.span_context().clone();
+            let _request = Context::current_with_span(request).attach();
+            pool.execute_named("resize", || {})?;
+            // Dropping the pool wait for the job, so it's span has ended
+            drop(pool);
+
+            let job = spans.0.lock().unwrap().pop().unwrap();
+            assert_eq!(job.name, "resize");
+            match relation {
+                SpanRelation::Link => {
+                    assert_eq!(job.parent_span_id, SpanId::INVALID);
+                    assert_ne!(job.span_context.trace_id(), submitter.trace_id());
+                    assert_eq!(job.links.links[0].span_context, submitter);
+                }
+                SpanRelation::Parent => {
+                    assert_eq!(job.parent_span_id, submitter.span_id());
+                    assert_eq!(job.span_context.trace_id(), submitter.trace_id());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sentry")]
+#[cfg(test)]
+mod sentry {
+    use sentry_core::test::with_captured_events;
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, SentryReporter, ThreadPoolBuilder,
+    };
+
+    #[test]
+    fn report_panic_to_sentry() -> Result<(), ThreadPoolError> {
+        let mut built = Ok(());
+        let events = with_captured_events(|| {
+            built = ThreadPoolBuilder::new(1)
+                .panic_reporter(SentryReporter::new())
+                .build()
+                .and_then(|pool| {
+                    pool.execute_named("resize", || panic!("Oh no! Something went wrong!"))
+                });
+        });
+        built?;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].tags.get("job").map(String::as_str),
+            Some("resize")
+        );
+        assert_eq!(events[0].tags.get("worker").map(String::as_str), Some("0"));
+        assert_eq!(
+            events[0].exception[0].value.as_deref(),
+            Some("Oh no! Something went wrong!")
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cputime")]
+#[cfg(test)]
+mod cputime {
+    use std::sync::mpsc::channel;
+    use std::time::{Duration, Instant};
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+
+    #[test]
+    fn worker_cpu_time_in_stats() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(1)?;
+        let (send, recv) = channel();
+
+        pool.execute(|| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(20) {
+                std::hint::spin_loop();
+            }
+        })?;
+        // The worker is done with the busy job once it pick up this one
+        pool.execute(move || send.send(()).unwrap())?;
+        recv.recv().unwrap();
+
+        let stats = pool.stats();
+        assert_eq!(stats.worker_cpu_time.len(), 1);
+        assert!(stats.worker_cpu_time[0] >= Duration::from_millis(10));
+
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "stacks", unix))]
+#[cfg(test)]
+mod stacks {
+    use std::sync::mpsc::channel;
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPoolBuilder};
+
+    #[test]
+    fn dump_stacks_of_blocked_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2).thread_name("stuck").build()?;
+        let (started, wait_started) = channel();
+        let (release, wait_release) = channel::<()>();
+
+        pool.execute_named("stuck-job", move || {
+            started.send(()).unwrap();
+            let _ = wait_release.recv();
+        })?;
+        wait_started.recv().unwrap();
+
+        let stacks = pool.dump_stacks();
+        assert_eq!(stacks.len(), 2);
+
+        let stuck = stacks
+            .iter()
+            .find(|stack| stack.job.as_deref() == Some("stuck-job"))
+            .unwrap();
+        let frames = stuck.frames.as_ref().unwrap();
+        assert!(frames.iter().any(|frame| frame
+            .symbol
+            .as_deref()
+            .is_some_and(|symbol| symbol.contains("dump_stacks_of_blocked_worker"))));
+        assert!(stuck.to_string().starts_with("worker"));
+
+        release.send(()).unwrap();
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "numa")]
+#[cfg(test)]
+mod numa {
+    use std::sync::mpsc::channel;
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, numa::StealStrategy, ThreadPool, ThreadPoolBuilder,
+    };
+
+    #[test]
+    fn execute_on_node() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(2).numa(true).build()?;
+        let (send, recv) = channel();
+
+        assert!(!pool.numa_nodes().is_empty());
+        for node in pool.numa_nodes() {
+            let send = send.clone();
+            let id = node.id;
+            pool.execute_on_node(id, move || send.send(id).unwrap())?;
+        }
+
+        assert_eq!(
+            recv.iter().take(pool.numa_nodes().len()).count(),
+            pool.numa_nodes().len()
+        );
+        assert!(matches!(
+            pool.execute_on_node(usize::MAX, || {}),
+            Err(ThreadPoolError::NoSuchNode { .. })
+        ));
+        assert!(ThreadPool::new(1)?.execute_on_node(0, || {}).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn steal_strategy_run_every_job() -> Result<(), ThreadPoolError> {
+        for strategy in [
+            StealStrategy::RoundRobin,
+            StealStrategy::Random,
+            StealStrategy::MostLoaded,
+        ] {
+            let pool = ThreadPoolBuilder::new(4)
+                .numa(true)
+                .steal_strategy(strategy)
+                .steal_batch(4)
+                .build()?;
+            let (send, recv) = channel();
+
+            // Everything land on the first node, the other node have to steal it
+            for i in 0..64 {
+                let send = send.clone();
+                pool.execute_on_node(pool.numa_nodes()[0].id, move || send.send(i).unwrap())?;
+            }
+
+            let mut result: Vec<_> = recv.iter().take(64).collect();
+            result.sort();
+            assert_eq!(result, (0..64).collect::<Vec<_>>());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "priority")]
+#[cfg(test)]
+mod priority {
+    use std::sync::mpsc::channel;
+
+    use unknownrori_simple_thread_pool::{
+        error::ThreadPoolError, ThreadPoolBuilder, ThreadPriority,
+    };
+
+    #[test]
+    fn lowest_priority_worker() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .thread_priority(ThreadPriority::Lowest)
+            .build()?;
+        let (send, recv) = channel();
+
+        pool.execute(move || send.send(40).unwrap())?;
+        assert_eq!(recv.recv().unwrap(), 40);
+
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "rename", target_os = "linux"))]
+#[cfg(test)]
+mod rename {
+    use std::fs;
+    use std::sync::mpsc::channel;
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPoolBuilder};
+
+    fn os_thread_name() -> String {
+        fs::read_to_string("/proc/thread-self/comm")
+            .unwrap()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn rename_thread_while_job_run() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPoolBuilder::new(1)
+            .thread_name("worker")
+            .rename_thread_per_job(true)
+            .build()?;
+        let (send, recv) = channel();
+
+        let named = send.clone();
+        pool.execute_named("resize-images-in-bucket", move || {
+            named.send(os_thread_name()).unwrap()
+        })?;
+        pool.execute(move || send.send(os_thread_name()).unwrap())?;
+
+        assert_eq!(recv.recv().unwrap(), "resize-images-i");
+        assert_eq!(recv.recv().unwrap(), "worker-0");
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[cfg(test)]
+mod rayon {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, rayon::prelude::*, ThreadPool};
+
+    #[test]
+    fn rayon_scope_from_worker() -> Result<(), ThreadPoolError> {
+        let pool = Arc::new(ThreadPool::new(1)?);
+
+        let worker_pool = Arc::clone(&pool);
+        let sum = pool
+            .spawn(move || {
+                let sum = AtomicUsize::new(0);
+                worker_pool.rayon_scope(|scope| {
+                    for i in 1..=4 {
+                        let sum = &sum;
+                        scope.spawn(move |_| {
+                            sum.fetch_add(i, Ordering::SeqCst);
+                        });
+                    }
+                });
+                sum.into_inner()
+            })?
+            .wait()?;
+
+        assert_eq!(sum, 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_from_rayon() -> Result<(), ThreadPoolError> {
+        let pool = ThreadPool::new(2)?;
+        let offset = 40;
+
+        let results = (0..4)
+            .into_par_iter()
+            .map(|i| pool.install(|| i + offset))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(results, vec![40, 41, 42, 43]);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[cfg(test)]
+mod tracing {
+    use std::collections::HashMap;
+    use std::sync::mpsc::channel;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, ThreadId};
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+    use tracing_core::span::Current;
+    use unknownrori_simple_thread_pool::{error::ThreadPoolError, ThreadPool};
+
+    /// Record which thread entered which span
+    #[derive(Default)]
+    struct Entered {
+        spans: Mutex<Vec<&'static Metadata<'static>>>,
+        stacks: Mutex<HashMap<ThreadId, Vec<Id>>>,
+        entered: Arc<Mutex<Vec<(&'static str, ThreadId)>>>,
+    }
+
+    impl Subscriber for Entered {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata());
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            let name = self.spans.lock().unwrap()[span.into_u64() as usize - 1].name();
+            self.entered
+                .lock()
+                .unwrap()
+                .push((name, thread::current().id()));
+            self.stacks
+                .lock()
+                .unwrap()
+                .entry(thread::current().id())
+                .or_default()
+                .push(span.clone());
+        }
+
+        fn exit(&self, _: &Id) {
+            if let Some(stack) = self.stacks.lock().unwrap().get_mut(&thread::current().id()) {
+                stack.pop();
+            }
+        }
+
+        fn current_span(&self) -> Current {
+            let stacks = self.stacks.lock().unwrap();
+            match stacks
+                .get(&thread::current().id())
+                .and_then(|stack| stack.last())
+            {
+                Some(id) => Current::new(
+                    id.clone(),
+                    self.spans.lock().unwrap()[id.into_u64() as usize - 1],
+                ),
+                None => Current::none(),
+            }
+        }
+    }
+
+    #[test]
+    fn enter_submitter_span() -> Result<(), ThreadPoolError> {
+        let subscriber = Entered::default();
+        let entered = Arc::clone(&subscriber.entered);
+        let pool = ThreadPool::new(1)?;
+        let (send, recv) = channel();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _request = tracing::info_span!("request").entered();
+            pool.execute(move || send.send(thread::current().id()).unwrap())
+        })?;
+
+        let worker = recv.recv().unwrap();
+        drop(pool);
+
+        assert!(entered.lock().unwrap().contains(&("request", worker)));
+
+        Ok(())
+    }
+}
+
+#[cfg(loom)]
+#[cfg(test)]
+mod loom {
+    use std::sync::Arc;
+
+    use loom::sync::atomic::{AtomicUsize, Ordering};
+    use loom::thread;
+
+    use unknownrori_simple_thread_pool::ThreadPool;
+
+    #[test]
+    fn inline_pool_submitted_from_many_thread() {
+        loom::model(|| {
+            // Without worker every job run on the submitting thread, so the whole pool live inside the model
+            let pool = Arc::new(ThreadPool::new(0).unwrap());
+            let runs = Arc::new(AtomicUsize::new(0));
+
+            let submitter = {
+                let (pool, runs) = (Arc::clone(&pool), Arc::clone(&runs));
+                thread::spawn(move || {
+                    pool.execute(move || {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .unwrap();
+                })
+            };
+
+            let counted = Arc::clone(&runs);
+            pool.execute(move || {
+                counted.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+            submitter.join().unwrap();
+
+            assert_eq!(runs.load(Ordering::SeqCst), 2);
+        });
+    }
 }